@@ -0,0 +1,237 @@
+// Shared request path for calls to the backend API
+
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+
+use crate::{auth, clock, timing, AppError, AppState};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Streamed bodies (multipart uploads) can't be hashed up front
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+// Longest body preview included when a response isn't JSON
+const SNIPPET_CHARS: usize = 200;
+
+// Build the canonical string covered by the signature
+fn signing_message(method: &str, path: &str, timestamp: u64, body: Option<&[u8]>) -> String {
+    let body_hash = match body {
+        Some(bytes) => hex::encode(Sha256::digest(bytes)),
+        None => UNSIGNED_PAYLOAD.to_string(),
+    };
+    format!("{}\n{}\n{}\n{}", method, path, timestamp, body_hash)
+}
+
+// Hex-encoded HMAC-SHA256 of the canonical request
+fn signature(key: &[u8], method: &str, path: &str, timestamp: u64, body: Option<&[u8]>) -> String {
+    hmac_hex(key, signing_message(method, path, timestamp, body).as_bytes())
+}
+
+// Hex-encoded HMAC-SHA256 of arbitrary bytes, for signing exported files with the same key
+pub fn hmac_hex(key: &[u8], bytes: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(bytes);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// Attach X-Timestamp and X-Signature headers to a built request
+fn sign(request: &mut reqwest::Request, key: &[u8], timestamp: u64) -> Result<(), String> {
+    let path = match request.url().query() {
+        Some(query) => format!("{}?{}", request.url().path(), query),
+        None => request.url().path().to_string(),
+    };
+    let body = request.body().map(|body| body.as_bytes());
+    let body = match body {
+        Some(Some(bytes)) => Some(bytes),
+        Some(None) => None,
+        None => Some(&[][..]),
+    };
+    let signature = signature(key, request.method().as_str(), &path, timestamp, body);
+
+    let headers = request.headers_mut();
+    headers.insert(
+        "X-Timestamp",
+        timestamp.to_string().parse().map_err(|_| "Invalid timestamp header".to_string())?,
+    );
+    headers.insert(
+        "X-Signature",
+        signature.parse().map_err(|_| "Invalid signature header".to_string())?,
+    );
+    Ok(())
+}
+
+// Build a request with credentials attached, returning the access token it carries
+pub async fn prepare(
+    state: &AppState,
+    builder: reqwest::RequestBuilder,
+) -> Result<(reqwest::Request, Option<String>), String> {
+    let mut request = builder.build()
+        .map_err(|e| format!("Failed to build request: {}", e))?;
+
+    let (access_token, signing_key) = {
+        let settings = state.settings.lock().await;
+        (settings.access_token.clone(), settings.signing_key.clone())
+    };
+    if let Some(token) = &access_token {
+        request.headers_mut().insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {}", token).parse().map_err(|_| "Invalid access token".to_string())?,
+        );
+    }
+    if let Some(key) = signing_key {
+        // Signed with the backend's notion of now so a skewed local clock isn't rejected
+        let timestamp = clock::server_now(state).await;
+        sign(&mut request, key.as_bytes(), timestamp)?;
+    }
+
+    Ok((request, access_token))
+}
+
+// Send a request to the backend, signing it when a key is configured.
+// On a 401 the session is refreshed once and the request retried once.
+pub async fn send(
+    state: &AppState,
+    builder: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, String> {
+    // Streamed bodies can't be replayed, so those only get the refresh
+    let retry = builder.try_clone();
+    let (request, access_token) = prepare(state, builder).await?;
+    let mut timer = match state.timings.enabled() {
+        true => Some(timing::Timer::start(&request).await),
+        false => None,
+    };
+
+    let result = async {
+        let response = state.client().execute(request)
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+        if let Some(timer) = &mut timer {
+            timer.headers_received();
+        }
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        if !auth::refresh(state, access_token.as_deref()).await {
+            return Ok(response);
+        }
+        let Some(retry) = retry else {
+            return Ok(response);
+        };
+
+        let (request, _) = prepare(state, retry).await?;
+        state.client().execute(request)
+            .await
+            .map_err(|e| format!("Failed to send request: {}", e))
+    }
+    .await;
+
+    if let Some(timer) = timer {
+        timer.finish(state, &result).await;
+    }
+    result
+}
+
+// Turn an unsuccessful response into an error, using FastAPI's `detail` when present.
+// A structured detail of { code, message } also carries a machine-readable code.
+pub async fn error_from_response(response: reqwest::Response) -> AppError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let value = serde_json::from_str::<serde_json::Value>(&body).ok();
+    let detail = value.as_ref().and_then(|value| value.get("detail"));
+    let message = detail
+        .and_then(|detail| detail.as_str().or_else(|| detail.get("message").and_then(|message| message.as_str())))
+        .map(str::to_string)
+        .or_else(|| status.canonical_reason().map(str::to_string))
+        .unwrap_or_else(|| "Unknown error".to_string());
+    let code = detail
+        .and_then(|detail| detail.get("code"))
+        .or_else(|| value.as_ref().and_then(|value| value.get("code")))
+        .and_then(|code| code.as_str())
+        .map(str::to_string);
+
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return AppError::Unauthorized { message };
+    }
+    AppError::Backend {
+        status: status.as_u16(),
+        code,
+        message,
+    }
+}
+
+// Deserialize a JSON body, reporting HTML error pages and other non-JSON bodies as such
+pub async fn parse_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, AppError> {
+    let content_type = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("none")
+        .to_string();
+    // A body cut off by a timeout or reset is a transport failure, not a bad response
+    let body = response.bytes()
+        .await
+        .map_err(|e| AppError::Network { message: format!("Failed to read response: {}", e) })?;
+
+    // Proxies and wrong URLs tend to answer with HTML whatever they claim
+    let starts_like_json = body.iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| matches!(byte, b'{' | b'['));
+    if !starts_like_json || content_type.contains("html") {
+        let snippet: String = String::from_utf8_lossy(&body)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(SNIPPET_CHARS)
+            .collect();
+        return Err(AppError::UnexpectedResponse { content_type, snippet });
+    }
+
+    serde_json::from_slice(&body)
+        .map_err(|e| format!("Failed to parse response: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-signing-key";
+    const TIMESTAMP: u64 = 1_700_000_000;
+
+    // RFC 4231 test case 2
+    #[test]
+    fn hmac_matches_reference() {
+        assert_eq!(
+            hmac_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    // Digests computed independently, so a change to the canonical string breaks the test
+    #[test]
+    fn signature_matches_reference() {
+        let body = br#"{"language":"en"}"#;
+        assert_eq!(
+            signing_message("POST", "/process/url?priority=high", TIMESTAMP, Some(body)),
+            "POST\n/process/url?priority=high\n1700000000\n\
+             114a6e8f5c43bea09a4a73b24b44b030440a6f3be212bbe943becdb363f15e29"
+        );
+        assert_eq!(
+            signature(KEY, "POST", "/process/url?priority=high", TIMESTAMP, Some(body)),
+            "f6f0a91eb1d87deb80512f53d9850b48219c3474d3ef908725c83b3df438bbd9"
+        );
+    }
+
+    #[test]
+    fn streamed_body_is_marked_unsigned() {
+        assert_eq!(
+            signing_message("GET", "/job/abc", TIMESTAMP, None),
+            "GET\n/job/abc\n1700000000\nUNSIGNED-PAYLOAD"
+        );
+        assert_eq!(
+            signature(KEY, "GET", "/job/abc", TIMESTAMP, None),
+            "cc58bf40b892b2f2fabfe4a39c8d4356415e8dcf6bdc83f8df5dc237d963df39"
+        );
+    }
+}
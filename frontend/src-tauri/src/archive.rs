@@ -0,0 +1,138 @@
+// Results packaged as a zip of several outputs, unpacked safely
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::io_error;
+use crate::media::TempFile;
+use crate::{formats, integrity, AppError, AppState};
+
+// Limits that stop a small archive from expanding to fill the disk
+const MAX_EXTRACTED_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+const MAX_ENTRIES: usize = 10_000;
+
+// Unix file type bits for a symbolic link
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+#[derive(Debug, Clone, Serialize)]
+struct ArchiveEvent {
+    job_id: String,
+    path: String,
+}
+
+// Tell the UI a download turned out to be an archive, so it can offer to unpack it
+pub fn announce(app: &AppHandle, job_id: &str, path: &str) {
+    let event = ArchiveEvent {
+        job_id: job_id.to_string(),
+        path: path.to_string(),
+    };
+    let _ = app.emit_all("result-archive", event);
+}
+
+// Extract every file in the archive under dest_dir, returning their paths. Files
+// already written are removed if a later entry is rejected.
+fn extract(archive_path: &Path, dest_dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let file = File::open(archive_path).map_err(|e| io_error("Failed to read archive", archive_path, e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Result is not a valid zip archive: {}", e))?;
+    if zip.len() > MAX_ENTRIES {
+        return Err(format!("Archive has {} entries; at most {} are extracted", zip.len(), MAX_ENTRIES).into());
+    }
+
+    let mut extracted = Vec::new();
+    let result = (|| {
+        let mut total: u64 = 0;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index).map_err(|e| format!("Failed to read archive: {}", e))?;
+            // Absolute paths and ".." components would land outside dest_dir
+            let relative = entry.enclosed_name()
+                .map(Path::to_path_buf)
+                .ok_or_else(|| format!("Archive entry '{}' escapes the destination folder", entry.name()))?;
+            if entry.unix_mode().is_some_and(|mode| mode & S_IFMT == S_IFLNK) {
+                return Err(format!("Archive entry '{}' is a symbolic link", entry.name()).into());
+            }
+            let path = dest_dir.join(&relative);
+            if entry.is_dir() {
+                std::fs::create_dir_all(&path).map_err(|e| io_error("Failed to create folder", &path, e))?;
+                continue;
+            }
+            if path.exists() {
+                return Err(format!("{} already exists", path.display()).into());
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| io_error("Failed to create folder", parent, e))?;
+            }
+
+            // Declared sizes can lie, so the copy itself is capped too
+            let remaining = MAX_EXTRACTED_BYTES - total;
+            if entry.size() > remaining {
+                return Err(too_large());
+            }
+            let mut out = File::create(&path).map_err(|e| io_error("Failed to write file", &path, e))?;
+            extracted.push(path.clone());
+            let written = std::io::copy(&mut (&mut entry).take(remaining + 1), &mut out)
+                .map_err(|e| io_error("Failed to write file", &path, e))?;
+            if written > remaining {
+                return Err(too_large());
+            }
+            total += written;
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => Ok(extracted),
+        Err(e) => {
+            for path in &extracted {
+                let _ = std::fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+fn too_large() -> AppError {
+    format!("Archive expands to more than {} bytes", MAX_EXTRACTED_BYTES).into()
+}
+
+#[tauri::command]
+pub async fn download_and_extract_result(
+    state: State<'_, AppState>,
+    job_id: String,
+    format: String,
+    dest_dir: String,
+    format_options: Option<std::collections::HashMap<String, serde_json::Value>>,
+) -> Result<Vec<String>, AppError> {
+    let query = formats::format_query(&format, &format_options.unwrap_or_default())?;
+    let dest_dir = PathBuf::from(dest_dir);
+    tokio::fs::create_dir_all(&dest_dir)
+        .await
+        .map_err(|e| io_error("Failed to create folder", &dest_dir, e))?;
+
+    let temp = TempFile::new("archive", "zip");
+    let download = integrity::download_verified(&state, &job_id, &format, &query, temp.path()).await?;
+    if !download.verified {
+        return Err(format!(
+            "Result for job {} failed verification after {} attempts: {}",
+            job_id, download.attempts, download.problem.unwrap_or_default()
+        ).into());
+    }
+
+    let archive_path = temp.path().to_path_buf();
+    let extracted = tauri::async_runtime::spawn_blocking(move || extract(&archive_path, &dest_dir))
+        .await
+        .map_err(|e| format!("Extraction failed: {}", e))??;
+    drop(temp);
+
+    let mut paths = Vec::with_capacity(extracted.len());
+    for path in extracted {
+        let path = path.to_string_lossy().to_string();
+        crate::record_download(&state, &job_id, &path).await;
+        paths.push(path);
+    }
+    Ok(paths)
+}
@@ -0,0 +1,48 @@
+// Append-only audit log of notable events, one JSON object per line
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::{records, storage, AppState};
+
+pub const AUDIT_FILE: &str = "audit.jsonl";
+
+#[derive(Serialize)]
+struct Entry<'a, T: Serialize> {
+    at: u64,
+    event: &'a str,
+    #[serde(flatten)]
+    details: &'a T,
+}
+
+// Record an event under the logs directory; failures are reported but never fatal
+pub async fn append<T: Serialize>(state: &AppState, event: &str, details: &T) {
+    let entry = Entry {
+        at: records::unix_now(),
+        event,
+        details,
+    };
+    let mut line = match serde_json::to_vec(&entry) {
+        Ok(line) => line,
+        Err(e) => {
+            eprintln!("Failed to encode audit entry: {}", e);
+            return;
+        }
+    };
+    line.push(b'\n');
+
+    let dir = state.data_dir.join(storage::LOGS_DIR);
+    let result = async {
+        tokio::fs::create_dir_all(&dir).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(AUDIT_FILE))
+            .await?;
+        file.write_all(&line).await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to write audit log: {}", e);
+    }
+}
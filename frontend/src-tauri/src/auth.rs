@@ -0,0 +1,98 @@
+// Bearer-token credentials and their refresh when the backend answers 401
+
+use serde::Deserialize;
+use tauri::{Manager, State};
+
+use crate::{AppError, AppState};
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    // Some backends rotate the refresh token on every use
+    refresh_token: Option<String>,
+}
+
+// Tell the UI the user has to log in again
+fn expire(state: &AppState) {
+    if let Some(app) = state.app_handle.get() {
+        let _ = app.emit_all("session-expired", ());
+    }
+}
+
+// Exchange the refresh token for a new access token, reporting whether a usable one is now set.
+// `stale` is the token the failed request carried; if another request already replaced it,
+// nothing is sent, so concurrent 401s share a single refresh.
+pub async fn refresh(state: &AppState, stale: Option<&str>) -> bool {
+    let _guard = state.auth_refresh.lock().await;
+
+    let (access_token, refresh_token) = {
+        let settings = state.settings.lock().await;
+        (settings.access_token.clone(), settings.refresh_token.clone())
+    };
+    if access_token.as_deref() != stale {
+        return access_token.is_some();
+    }
+
+    let refreshed = match refresh_token {
+        Some(refresh_token) => request_tokens(state, &refresh_token).await,
+        None => Err("No refresh token is stored".to_string()),
+    };
+
+    let mut settings = state.settings.lock().await;
+    match refreshed {
+        Ok(tokens) => {
+            settings.access_token = Some(tokens.access_token);
+            if tokens.refresh_token.is_some() {
+                settings.refresh_token = tokens.refresh_token;
+            }
+        }
+        Err(e) => {
+            eprintln!("Re-authentication failed: {}", e);
+            // Dropping the token keeps other in-flight 401s from retrying the refresh
+            settings.access_token = None;
+        }
+    }
+    let refreshed = settings.access_token.is_some();
+    drop(settings);
+
+    if let Err(e) = crate::persist_settings(state).await {
+        eprintln!("Failed to save settings: {}", e);
+    }
+    if !refreshed {
+        expire(state);
+    }
+    refreshed
+}
+
+async fn request_tokens(state: &AppState, refresh_token: &str) -> Result<TokenResponse, String> {
+    let body = serde_json::json!({ "refresh_token": refresh_token });
+    let request = state.client().post(format!("{}/auth/refresh", state.api_url()))
+        .json(&body);
+    let (request, _) = crate::api::prepare(state, request).await?;
+
+    // Executed directly rather than through api::send so a 401 here can't recurse
+    let response = state.client().execute(request)
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backend returned {}", response.status()));
+    }
+    response.json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_credentials(
+    state: State<'_, AppState>,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+) -> Result<(), AppError> {
+    {
+        let mut settings = state.settings.lock().await;
+        settings.access_token = access_token.filter(|token| !token.is_empty());
+        settings.refresh_token = refresh_token.filter(|token| !token.is_empty());
+    }
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
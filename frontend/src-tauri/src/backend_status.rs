@@ -0,0 +1,66 @@
+// Backend load summary, for warning before a large submission
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{api, AppError, AppState};
+
+// Long enough to absorb a burst of UI refreshes, short enough to track the queue
+const STATUS_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendStatus {
+    #[serde(alias = "queued")]
+    pub total_queued: u64,
+    #[serde(alias = "workers")]
+    pub active_workers: u64,
+    #[serde(default, alias = "estimated_wait")]
+    pub estimated_wait_secs: Option<f64>,
+    // More work waiting than workers to take it
+    #[serde(default)]
+    pub busy: bool,
+}
+
+// Cached summary, with None remembering a backend that doesn't publish one
+pub type CachedStatus = Option<(Instant, Option<BackendStatus>)>;
+
+async fn fetch(state: &AppState) -> Result<Option<BackendStatus>, AppError> {
+    let request = state.client().get(format!("{}/queue/summary", state.api_url()));
+    let response = api::send(state, request).await?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND
+        | reqwest::StatusCode::METHOD_NOT_ALLOWED
+        | reqwest::StatusCode::NOT_IMPLEMENTED => return Ok(None),
+        status if !status.is_success() => return Err(api::error_from_response(response).await),
+        _ => {}
+    }
+
+    let mut status: BackendStatus = response.json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    status.busy = status.total_queued > status.active_workers;
+    Ok(Some(status))
+}
+
+#[tauri::command]
+pub async fn get_backend_status(
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<BackendStatus, AppError> {
+    let cached = state.backend_status.lock().await.clone()
+        .filter(|(fetched_at, _)| !force_refresh.unwrap_or(false) && fetched_at.elapsed() < STATUS_TTL);
+    let status = match cached {
+        Some((_, status)) => status,
+        None => {
+            let status = fetch(&state).await?;
+            *state.backend_status.lock().await = Some((Instant::now(), status.clone()));
+            status
+        }
+    };
+    status.ok_or_else(|| AppError::Unsupported {
+        feature: "backend_status".to_string(),
+    })
+}
@@ -0,0 +1,239 @@
+// Export and import of settings and job history for moving to another machine
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::records::{unix_now, JobRecord};
+use crate::result_cache::{self, CacheEntry};
+use crate::settings::Settings;
+use crate::{network, storage, AppError, AppState};
+
+// Bump when the archive layout changes; newer archives are refused
+const STATE_ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const SETTINGS_ENTRY: &str = "settings.json";
+const RECORDS_ENTRY: &str = "records.json";
+// Lists the cached results; the results themselves stay behind and are fetched again
+const CACHE_ENTRY: &str = "cache.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    app_version: String,
+    exported_at: u64,
+    includes_secrets: bool,
+}
+
+// How imported job records combine with the local ones
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    // Keep local records and add the ones that are missing
+    #[default]
+    Merge,
+    // Drop local records in favour of the archive
+    Replace,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportSummary {
+    records_imported: usize,
+    records_skipped: usize,
+    secrets_imported: bool,
+    // Jobs cached on the exporting machine but not here, for cache_full_result to fetch
+    results_to_recache: Vec<String>,
+}
+
+fn write_archive(
+    path: &PathBuf,
+    manifest: &Manifest,
+    settings: &Settings,
+    records: &[JobRecord],
+    cache: &[CacheEntry],
+) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let entries = [
+        (MANIFEST_ENTRY, serde_json::to_vec_pretty(manifest)),
+        (SETTINGS_ENTRY, serde_json::to_vec_pretty(settings)),
+        (RECORDS_ENTRY, serde_json::to_vec_pretty(records)),
+        (CACHE_ENTRY, serde_json::to_vec_pretty(cache)),
+    ];
+    for (name, contents) in entries {
+        let contents = contents.map_err(|e| format!("Failed to encode {}: {}", name, e))?;
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(&contents).map_err(zip::result::ZipError::from))
+            .map_err(|e| format!("Failed to write archive: {}", e))?;
+    }
+
+    zip.finish().map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(())
+}
+
+fn read_entry<T: serde::de::DeserializeOwned>(
+    zip: &mut zip::ZipArchive<File>,
+    name: &str,
+) -> Result<T, String> {
+    let mut contents = String::new();
+    zip.by_name(name)
+        .map_err(|_| format!("Archive is missing {}", name))?
+        .read_to_string(&mut contents)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid {}: {}", name, e))
+}
+
+struct Archive {
+    manifest: Manifest,
+    settings: Settings,
+    records: Vec<JobRecord>,
+    cache: Vec<CacheEntry>,
+}
+
+fn read_archive(path: &PathBuf) -> Result<Archive, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Not a QuickScript state archive: {}", e))?;
+
+    // Check the version before trusting the rest of the layout
+    let manifest: Manifest = read_entry(&mut zip, MANIFEST_ENTRY)?;
+    if manifest.version == 0 || manifest.version > STATE_ARCHIVE_VERSION {
+        return Err(format!(
+            "State archive version {} is not supported (this app reads up to version {}); \
+             update QuickScript {} to import it",
+            manifest.version, STATE_ARCHIVE_VERSION, manifest.app_version
+        ));
+    }
+
+    let settings = read_entry(&mut zip, SETTINGS_ENTRY)?;
+    let records = read_entry(&mut zip, RECORDS_ENTRY)?;
+    // Archives from before the cache listing don't have one
+    let has_cache = zip.file_names().any(|name| name == CACHE_ENTRY);
+    let cache = match has_cache {
+        true => read_entry(&mut zip, CACHE_ENTRY)?,
+        false => Vec::new(),
+    };
+    Ok(Archive { manifest, settings, records, cache })
+}
+
+#[tauri::command]
+pub async fn export_state(
+    state: State<'_, AppState>,
+    dest_path: String,
+    include_secrets: Option<bool>,
+) -> Result<String, AppError> {
+    let include_secrets = include_secrets.unwrap_or(false);
+
+    let mut settings = state.settings.lock().await.clone();
+    if !include_secrets {
+        settings = settings.without_secrets();
+    }
+    let mut records: Vec<JobRecord> = state.job_records.lock().await.values().cloned().collect();
+    records.sort_by_key(|record| record.submitted_at);
+
+    let manifest = Manifest {
+        version: STATE_ARCHIVE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: unix_now(),
+        includes_secrets: include_secrets,
+    };
+
+    let path = PathBuf::from(&dest_path);
+    let cache_dir = state.data_dir.join(storage::CACHE_DIR);
+    tokio::task::spawn_blocking(move || {
+        let cache = result_cache::entries(&cache_dir);
+        write_archive(&path, &manifest, &settings, &records, &cache)
+    })
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+
+    Ok(dest_path)
+}
+
+#[tauri::command]
+pub async fn import_state(
+    state: State<'_, AppState>,
+    src_path: String,
+    mode: Option<ImportMode>,
+    include_secrets: Option<bool>,
+) -> Result<ImportSummary, AppError> {
+    let path = PathBuf::from(&src_path);
+    let cache_dir = state.data_dir.join(storage::CACHE_DIR);
+    let (archive, local_cache) = tokio::task::spawn_blocking(move || {
+        read_archive(&path).map(|archive| (archive, result_cache::entries(&cache_dir)))
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))??;
+
+    // Secrets only come across when the archive has them and the user opted in
+    let secrets_imported = include_secrets.unwrap_or(false)
+        && archive.manifest.includes_secrets
+        && archive.settings.has_secrets();
+    {
+        let mut settings = state.settings.lock().await;
+        let imported_settings = archive.settings.with_post_process_from(&settings);
+        let imported_settings = if secrets_imported {
+            imported_settings
+        } else {
+            imported_settings.with_secrets_from(&settings)
+        };
+        // Nothing is imported if the new settings can't be connected with
+        let connection = network::connect(&imported_settings)?;
+        *settings = imported_settings;
+        state.set_connection(connection);
+        state.status_cache.lock().await.set_ttl(settings.status_cache_ttl());
+    }
+    // What the old backend supported says nothing about the imported one
+    *state.capabilities.lock().await = None;
+    *state.backend_status.lock().await = None;
+
+    let mut records_imported = 0;
+    let mut records_skipped = 0;
+    {
+        let mut records = state.job_records.lock().await;
+        if let ImportMode::Replace = mode.unwrap_or_default() {
+            records.clear();
+        }
+        for record in archive.records {
+            if records.contains_key(&record.job_id) {
+                records_skipped += 1;
+            } else {
+                records.insert(record.job_id.clone(), record);
+                records_imported += 1;
+            }
+        }
+
+        *state.processing_jobs.lock().await = records.values()
+            .filter(|record| !record.is_finished())
+            .map(|record| record.job_id.clone())
+            .collect();
+    }
+
+    // Cached results aren't in the archive; list the ones this machine lacks
+    let results_to_recache: Vec<String> = {
+        let records = state.job_records.lock().await;
+        archive.cache
+            .into_iter()
+            .filter(|entry| records.contains_key(&entry.job_id))
+            .filter(|entry| !local_cache.iter().any(|local| {
+                local.job_id == entry.job_id && (entry.sha256.is_none() || local.sha256 == entry.sha256)
+            }))
+            .map(|entry| entry.job_id)
+            .collect()
+    };
+
+    crate::persist_settings(&state).await?;
+    crate::persist_records(&state).await;
+
+    Ok(ImportSummary {
+        records_imported,
+        records_skipped,
+        secrets_imported,
+        results_to_recache,
+    })
+}
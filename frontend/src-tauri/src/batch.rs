@@ -0,0 +1,593 @@
+// Directory uploads and multi-job downloads
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::io_error;
+use crate::records::{unix_now, JobSource};
+use crate::{api, concurrency, media, persist, presets, settings, silence, storage, transfers, AppError, AppState, ProcessOptions, UploadForm};
+
+// Manifests of batch downloads, under the app data dir
+const BATCHES_DIR: &str = "batches";
+// Manifests of batches left unfinished this long are given up on
+const MANIFEST_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+// Extensions picked up when uploading a directory
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp3", "wav", "m4a", "aac", "flac", "ogg", "opus", "wma", "mp4", "mkv", "mov", "avi", "webm", "wmv",
+];
+
+#[derive(Debug, Serialize)]
+pub struct BatchUploadItem {
+    path: String,
+    job_id: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchDownloadItem {
+    job_id: String,
+    path: Option<String>,
+    error: Option<String>,
+}
+
+fn is_media_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.as_str()))
+}
+
+// Media files under dir, sorted so batches run in a stable order
+fn collect_media(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if is_media_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Make a path component safe to create on any platform
+fn sanitize_component(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_control() || r#"<>:"/\|?*"#.contains(c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim_end_matches(['.', ' ']).to_string();
+    if cleaned.is_empty() {
+        "_".to_string()
+    } else {
+        cleaned
+    }
+}
+
+// Where a job's result goes under dest_root, mirroring its source's place under the input root
+fn result_path(dest_root: &Path, source: &JobSource, input_root: Option<&str>, job_id: &str, format: &str) -> PathBuf {
+    let source_path = match source {
+        JobSource::File { path } | JobSource::Segment { path, .. } | JobSource::Pipe { path } => Some(Path::new(path)),
+        JobSource::Url { .. } | JobSource::Fetched { .. } | JobSource::Remote => None,
+    };
+    let stem = source_path
+        .and_then(|path| path.file_stem())
+        .map(|stem| sanitize_component(&stem.to_string_lossy()))
+        .unwrap_or_else(|| sanitize_component(job_id));
+
+    let mut dir = dest_root.to_path_buf();
+    let relative_dir = source_path
+        .zip(input_root)
+        .and_then(|(path, root)| path.parent()?.strip_prefix(root).ok());
+    for component in relative_dir.into_iter().flat_map(Path::components) {
+        if let Component::Normal(name) = component {
+            dir.push(sanitize_component(&name.to_string_lossy()));
+        }
+    }
+
+    dir.join(format!("{}.{}", stem, format))
+}
+
+#[tauri::command]
+pub async fn upload_directory(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    dir: String,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    recursive: Option<bool>,
+    preserve_structure: Option<bool>,
+) -> Result<Vec<BatchUploadItem>, AppError> {
+    let root = PathBuf::from(&dir);
+    let scan_root = root.clone();
+    let recursive = recursive.unwrap_or(true);
+    let files = tauri::async_runtime::spawn_blocking(move || collect_media(&scan_root, recursive))
+        .await
+        .map_err(|e| format!("Failed to read directory: {}", e))??;
+
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+    // Remembering the input root lets download_results mirror the tree later
+    let input_root = preserve_structure.unwrap_or(false).then(|| dir.clone());
+
+    // Submissions run side by side, as many as the backend keeps up with
+    let mut limit = concurrency::limit_for(&state, "batch").await;
+    let mut files = files.into_iter().enumerate();
+    let mut in_flight = FuturesUnordered::new();
+    let mut items = Vec::new();
+    loop {
+        while in_flight.len() < limit.current() {
+            let Some((index, file)) = files.next() else {
+                break;
+            };
+            let state = &state;
+            let options = &options;
+            in_flight.push(async move {
+                let file_name = file.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let started = Instant::now();
+                let result = crate::send_file(state, &file, file_name, options, &UploadForm::default()).await;
+                (index, file, started.elapsed(), result)
+            });
+        }
+        let Some((index, file, latency, result)) = in_flight.next().await else {
+            break;
+        };
+        if let Some(concurrency) = limit.observe(latency, result.as_ref().map(|_| ())) {
+            concurrency::announce(&state, &limit, concurrency);
+        }
+
+        let path = file.to_string_lossy().to_string();
+        match result {
+            Ok(api_response) => {
+                let job_id = api_response.job_id;
+                crate::track_job(&state, &job_id, JobSource::File { path: path.clone() }, options.clone()).await;
+                if let Some(record) = state.job_records.lock().await.get_mut(&job_id) {
+                    record.input_root = input_root.clone();
+                }
+                items.push((index, BatchUploadItem { path, job_id: Some(job_id), error: None }));
+            }
+            Err(e) => items.push((index, BatchUploadItem { path, job_id: None, error: Some(e.to_string()) })),
+        }
+    }
+    // Report in the order the files were found, not the order they finished
+    items.sort_by_key(|(index, _)| *index);
+    let items: Vec<BatchUploadItem> = items.into_iter().map(|(_, item)| item).collect();
+
+    if input_root.is_some() {
+        crate::persist_records(&state).await;
+    }
+    Ok(items)
+}
+
+// Progress of a multi-job download, saved after every file so it can be resumed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchManifest {
+    batch_id: String,
+    format: String,
+    created_at: u64,
+    entries: Vec<BatchEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchEntry {
+    job_id: String,
+    path: String,
+    // SHA-256 of the file as written; set once it is complete
+    sha256: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchDownloadReport {
+    batch_id: String,
+    downloaded: usize,
+    skipped: usize,
+    items: Vec<BatchDownloadItem>,
+}
+
+fn next_batch_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("b-{}-{}", unix_now(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+fn manifest_path(state: &AppState, batch_id: &str) -> Result<PathBuf, AppError> {
+    // The ID names a file, so only IDs this module could have made are accepted
+    if batch_id.is_empty() || !batch_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid batch ID '{}'", batch_id).into());
+    }
+    Ok(state.data_dir.join(BATCHES_DIR).join(format!("{}.json", batch_id)))
+}
+
+async fn file_sha256(path: &Path) -> Option<String> {
+    let contents = tokio::fs::read(path).await.ok()?;
+    Some(hex::encode(Sha256::digest(&contents)))
+}
+
+// Fetch every entry not already on disk with its recorded checksum
+async fn run_batch(state: &AppState, mut manifest: BatchManifest) -> Result<BatchDownloadReport, AppError> {
+    let manifest_path = manifest_path(state, &manifest.batch_id)?;
+    let mut report = BatchDownloadReport {
+        batch_id: manifest.batch_id.clone(),
+        downloaded: 0,
+        skipped: 0,
+        items: Vec::with_capacity(manifest.entries.len()),
+    };
+
+    for index in 0..manifest.entries.len() {
+        let entry = manifest.entries[index].clone();
+        let path = PathBuf::from(&entry.path);
+        if let Some(expected) = &entry.sha256 {
+            if file_sha256(&path).await.as_ref() == Some(expected) {
+                report.skipped += 1;
+                report.items.push(BatchDownloadItem { job_id: entry.job_id, path: Some(entry.path), error: None });
+                continue;
+            }
+        }
+
+        let result = async {
+            let bytes = crate::fetch_result(state, &entry.job_id, &manifest.format, &[]).await?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .map_err(|e| io_error("Failed to create directory", parent, e))?;
+            }
+            tokio::fs::write(&path, &bytes)
+                .await
+                .map_err(|e| io_error("Failed to write file", &path, e))?;
+            crate::record_download(state, &entry.job_id, &entry.path).await;
+            Ok::<_, AppError>(hex::encode(Sha256::digest(&bytes)))
+        }
+        .await;
+
+        match result {
+            Ok(sha256) => {
+                manifest.entries[index].sha256 = Some(sha256);
+                persist::save_json(&manifest_path, &manifest).await?;
+                report.downloaded += 1;
+                report.items.push(BatchDownloadItem { job_id: entry.job_id, path: Some(entry.path), error: None });
+            }
+            Err(e) => report.items.push(BatchDownloadItem { job_id: entry.job_id, path: None, error: Some(e.to_string()) }),
+        }
+    }
+
+    // Nothing is left to resume
+    if report.items.iter().all(|item| item.error.is_none()) {
+        let _ = tokio::fs::remove_file(&manifest_path).await;
+    }
+    Ok(report)
+}
+
+// Delete manifests no batch has touched in MANIFEST_MAX_AGE; each is saved as its batch progresses
+async fn prune_manifests(state: &AppState) {
+    let Ok(mut entries) = tokio::fs::read_dir(state.data_dir.join(BATCHES_DIR)).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let stale = entry.metadata().await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > MANIFEST_MAX_AGE);
+        if stale {
+            let _ = tokio::fs::remove_file(entry.path()).await;
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn download_results(
+    state: State<'_, AppState>,
+    job_ids: Vec<String>,
+    format: String,
+    dest_dir: String,
+    preserve_structure: Option<bool>,
+) -> Result<BatchDownloadReport, AppError> {
+    let dest_root = PathBuf::from(&dest_dir);
+    let preserve_structure = preserve_structure.unwrap_or(false);
+    let mut written = HashSet::new();
+
+    // Paths are fixed up front so a resumed batch writes to the same files
+    let mut entries = Vec::with_capacity(job_ids.len());
+    for job_id in job_ids {
+        let record = state.job_records.lock().await.get(&job_id).cloned();
+        let mut path = match &record {
+            Some(record) => {
+                let input_root = record.input_root.as_deref().filter(|_| preserve_structure);
+                result_path(&dest_root, &record.source, input_root, &job_id, &format)
+            }
+            None => dest_root.join(format!("{}.{}", sanitize_component(&job_id), format)),
+        };
+        // Sources with the same name would otherwise overwrite each other
+        if written.contains(&path) {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            path.set_file_name(format!("{}-{}.{}", stem, sanitize_component(&job_id), format));
+        }
+        entries.push(BatchEntry {
+            job_id,
+            path: path.to_string_lossy().to_string(),
+            sha256: None,
+        });
+        written.insert(path);
+    }
+
+    prune_manifests(&state).await;
+    let manifest = BatchManifest {
+        batch_id: next_batch_id(),
+        format,
+        created_at: unix_now(),
+        entries,
+    };
+    persist::save_json(&manifest_path(&state, &manifest.batch_id)?, &manifest).await?;
+    run_batch(&state, manifest).await
+}
+
+// Finish an interrupted download_results batch, skipping files that are already complete
+#[tauri::command]
+pub async fn resume_batch_download(
+    state: State<'_, AppState>,
+    batch_id: String,
+) -> Result<BatchDownloadReport, AppError> {
+    let manifest: BatchManifest = persist::load_json(&manifest_path(&state, &batch_id)?)
+        .ok_or_else(|| format!("Unknown batch {}", batch_id))?;
+    run_batch(&state, manifest).await
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResultSize {
+    job_id: String,
+    format: String,
+    // None when the backend didn't report a length
+    bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DownloadEstimate {
+    total_known_bytes: u64,
+    unknown_count: usize,
+    free_bytes: Option<u64>,
+    // Set when the known sizes alone exceed the free space
+    warning: Option<String>,
+    results: Vec<ResultSize>,
+}
+
+// Content-Length of a result, from a HEAD request or, for backends that only route
+// GET, from the headers of a GET whose body is dropped unread
+async fn result_size(state: &AppState, job_id: &str, format: &str) -> Result<Option<u64>, AppError> {
+    let url = format!("{}/download/{}/{}", state.api_url(), job_id, format);
+    let mut response = api::send(state, state.client().head(&url)).await?;
+    if response.status() == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+        response = api::send(state, state.client().get(&url)).await?;
+    }
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+    Ok(response.headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok()))
+}
+
+// Sum the sizes of results before a batch download and compare with the free space at dest_dir
+#[tauri::command]
+pub async fn estimate_download_size(
+    state: State<'_, AppState>,
+    jobs: Vec<(String, String)>,
+    dest_dir: String,
+) -> Result<DownloadEstimate, AppError> {
+    let mut results = Vec::with_capacity(jobs.len());
+    for (job_id, format) in jobs {
+        let bytes = match result_size(&state, &job_id, &format).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Failed to size result for job {}: {}", job_id, e);
+                None
+            }
+        };
+        results.push(ResultSize { job_id, format, bytes });
+    }
+
+    let total_known_bytes = results.iter().filter_map(|result| result.bytes).sum();
+    let unknown_count = results.iter().filter(|result| result.bytes.is_none()).count();
+    let free_bytes = storage::free_space(Path::new(&dest_dir)).await;
+    let warning = free_bytes.filter(|free| total_known_bytes > *free).map(|free| {
+        format!(
+            "The results need {} bytes but only {} are free at {}",
+            total_known_bytes, free, dest_dir
+        )
+    });
+
+    Ok(DownloadEstimate {
+        total_known_bytes,
+        unknown_count,
+        free_bytes,
+        warning,
+        results,
+    })
+}
+
+type ResultZip = zip::ZipWriter<BufWriter<File>>;
+
+// Copy a downloaded result into the archive as one entry
+fn add_zip_entry(zip: &mut ResultZip, name: String, source: &Path) -> Result<(), String> {
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut file = File::open(source).map_err(|e| format!("Failed to read download: {}", e))?;
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    std::io::copy(&mut file, zip).map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(())
+}
+
+// Stream a result to a temp file, so a failed download never leaves a partial entry
+async fn download_to_temp(state: &AppState, job_id: &str, format: &str, bytes: &mut u64) -> Result<media::TempFile, AppError> {
+    if let Some(restored) = silence::restored_result(state, job_id, format, &[]).await? {
+        let temp = media::TempFile::new("zip-entry", format);
+        tokio::fs::write(temp.path(), &restored)
+            .await
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        *bytes += restored.len() as u64;
+        return Ok(temp);
+    }
+
+    let response = crate::open_result(state, job_id, format, &[]).await?;
+    let mut response = transfers::Download::new(response);
+    let temp = media::TempFile::new("zip-entry", format);
+    let mut file = tokio::fs::File::create(temp.path())
+        .await
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    while let Some((chunk, _buffer)) = state.transfers.next_chunk(&mut response)
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        *bytes += chunk.len() as u64;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    Ok(temp)
+}
+
+// Entry name for a job's result, unique within the archive
+fn zip_entry_name(record: Option<&crate::records::JobRecord>, job_id: &str, format: &str, used: &mut HashSet<String>) -> String {
+    let stem = match record.map(|record| &record.source) {
+        Some(JobSource::File { path }) | Some(JobSource::Segment { path, .. }) => Path::new(path)
+            .file_stem()
+            .map(|stem| sanitize_component(&stem.to_string_lossy())),
+        _ => None,
+    }
+    .unwrap_or_else(|| sanitize_component(job_id));
+
+    let mut name = format!("{}.{}", stem, format);
+    let mut counter = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{}-{}.{}", stem, counter, format);
+        counter += 1;
+    }
+    name
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ZipProgressEvent {
+    dest_path: String,
+    entry: String,
+    job_id: String,
+    // "started", "done" or "failed"
+    status: &'static str,
+    entry_bytes: u64,
+    completed: usize,
+    total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ZipEntryReport {
+    job_id: String,
+    format: String,
+    entry: String,
+    bytes: u64,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ZipReport {
+    path: String,
+    entries: Vec<ZipEntryReport>,
+}
+
+// Stream each result through a temp file into the archive, so memory stays bounded.
+// A failed download is reported and skipped; the archive carries on.
+#[tauri::command]
+pub async fn download_results_zip(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    jobs: Vec<(String, String)>,
+    dest_zip_path: String,
+) -> Result<ZipReport, AppError> {
+    // Formats become part of each entry name
+    if let Some((_, format)) = jobs.iter().find(|(_, format)| !settings::is_format_name(format)) {
+        return Err(format!("Invalid result format '{}'", format).into());
+    }
+    let file = tokio::fs::File::create(&dest_zip_path)
+        .await
+        .map_err(|e| format!("Failed to create archive: {}", e))?
+        .into_std()
+        .await;
+    let mut zip: ResultZip = zip::ZipWriter::new(BufWriter::new(file));
+
+    let total = jobs.len();
+    let mut used = HashSet::new();
+    let mut entries = Vec::with_capacity(total);
+
+    for (index, (job_id, format)) in jobs.into_iter().enumerate() {
+        let record = state.job_records.lock().await.get(&job_id).cloned();
+        let entry = zip_entry_name(record.as_ref(), &job_id, &format, &mut used);
+        let progress = |status, entry_bytes, completed| ZipProgressEvent {
+            dest_path: dest_zip_path.clone(),
+            entry: entry.clone(),
+            job_id: job_id.clone(),
+            status,
+            entry_bytes,
+            completed,
+            total,
+        };
+        let _ = app.emit_all("zip-progress", progress("started", 0, index));
+
+        let mut bytes = 0;
+        let error = match download_to_temp(&state, &job_id, &format, &mut bytes).await {
+            Ok(temp) => {
+                // Archive write errors are fatal; the zip can't be trusted after one
+                let name = entry.clone();
+                let (returned, written) = tauri::async_runtime::spawn_blocking(move || {
+                    let written = add_zip_entry(&mut zip, name, temp.path());
+                    (zip, written)
+                })
+                .await
+                .map_err(|e| format!("Archive task failed: {}", e))?;
+                zip = returned;
+                written?;
+                None
+            }
+            Err(e) => Some(e.to_string()),
+        };
+
+        let status = if error.is_some() { "failed" } else { "done" };
+        let _ = app.emit_all("zip-progress", progress(status, bytes, index + 1));
+        entries.push(ZipEntryReport { job_id, format, entry, bytes, error });
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        zip.finish()
+            .map_err(|e| e.to_string())
+            .and_then(|mut writer| writer.flush().map_err(|e| e.to_string()))
+    })
+    .await
+    .map_err(|e| format!("Archive task failed: {}", e))?
+    .map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    Ok(ZipReport {
+        path: dest_zip_path,
+        entries,
+    })
+}
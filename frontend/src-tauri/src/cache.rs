@@ -0,0 +1,163 @@
+// Short-lived cache of job status responses
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::JobStatusResponse;
+
+// Upper bound on cached jobs; the least recently fetched entry is evicted first
+const STATUS_CACHE_CAPACITY: usize = 256;
+
+// How long a job the backend reported as missing is remembered
+const MISSING_TTL: Duration = Duration::from_secs(5);
+
+// Sent to offer delta responses, and echoed by backends that answer with one
+pub const DELTA_HEADER: &str = "X-Status-Delta";
+// Body flag for backends that mark deltas in the payload instead
+const DELTA_FIELD: &str = "delta";
+
+pub struct StatusCache {
+    ttl: Duration,
+    entries: HashMap<String, (Instant, JobStatusResponse)>,
+    missing: HashMap<String, Instant>,
+}
+
+impl StatusCache {
+    pub fn new(ttl: Duration) -> Self {
+        StatusCache {
+            ttl,
+            entries: HashMap::new(),
+            missing: HashMap::new(),
+        }
+    }
+
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+    }
+
+    // Cached status for a job, if it is still fresh
+    pub fn get(&self, job_id: &str) -> Option<JobStatusResponse> {
+        self.entries
+            .get(job_id)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, status)| status.clone())
+    }
+
+    // Last status seen for a job, however old
+    pub fn last(&self, job_id: &str) -> Option<JobStatusResponse> {
+        self.entries.get(job_id).map(|(_, status)| status.clone())
+    }
+
+    pub fn insert(&mut self, job_id: &str, status: JobStatusResponse) {
+        if !self.entries.contains_key(job_id) && self.entries.len() >= STATUS_CACHE_CAPACITY {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (fetched_at, _))| *fetched_at)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.missing.remove(job_id);
+        self.entries.insert(job_id.to_string(), (Instant::now(), status));
+    }
+
+    pub fn invalidate(&mut self, job_id: &str) {
+        self.entries.remove(job_id);
+        self.missing.remove(job_id);
+    }
+
+    // Remember that the backend doesn't know this job
+    pub fn mark_missing(&mut self, job_id: &str) {
+        self.entries.remove(job_id);
+        self.missing.retain(|_, marked_at| marked_at.elapsed() < MISSING_TTL);
+        self.missing.insert(job_id.to_string(), Instant::now());
+    }
+
+    pub fn is_known_missing(&self, job_id: &str) -> bool {
+        self.missing
+            .get(job_id)
+            .is_some_and(|marked_at| marked_at.elapsed() < MISSING_TTL)
+    }
+}
+
+// Whether a status response carries only the fields that changed
+pub fn is_delta(headers: &reqwest::header::HeaderMap, body: &serde_json::Value) -> bool {
+    headers.get(DELTA_HEADER).is_some_and(|value| value.as_bytes() == b"1")
+        || body.get(DELTA_FIELD).and_then(serde_json::Value::as_bool).unwrap_or(false)
+}
+
+// Apply a delta's fields over the full status it updates; a null clears an optional field
+pub fn merge_delta(base: &JobStatusResponse, delta: serde_json::Value) -> Result<JobStatusResponse, String> {
+    let serde_json::Value::Object(changes) = delta else {
+        return Err("Partial status is not a JSON object".to_string());
+    };
+    let mut merged = serde_json::to_value(base).map_err(|e| format!("Failed to merge status: {}", e))?;
+    if let Some(fields) = merged.as_object_mut() {
+        for (key, value) in changes {
+            if key != DELTA_FIELD && key != "job_id" {
+                fields.insert(key, value);
+            }
+        }
+    }
+    serde_json::from_value(merged).map_err(|e| format!("Partial status does not fit the cached one: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use serde_json::json;
+
+    fn base() -> JobStatusResponse {
+        JobStatusResponse {
+            job_id: "job-1".to_string(),
+            status: "processing".to_string(),
+            progress: 0.4,
+            message: Some("Transcribing".to_string()),
+            result_url: None,
+            warnings: Vec::new(),
+            result_expires_at: Some(1_700_000_000),
+        }
+    }
+
+    #[test]
+    fn full_responses_are_not_deltas() {
+        let body = serde_json::to_value(base()).unwrap();
+        assert!(!is_delta(&HeaderMap::new(), &body));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(DELTA_HEADER, HeaderValue::from_static("1"));
+        assert!(is_delta(&headers, &json!({ "progress": 0.5 })));
+        assert!(is_delta(&HeaderMap::new(), &json!({ "delta": true, "progress": 0.5 })));
+    }
+
+    // Fields the delta leaves out keep their cached values, and the job can't be swapped for another
+    #[test]
+    fn deltas_update_only_their_fields() {
+        let delta = json!({ "delta": true, "job_id": "job-2", "status": "complete", "progress": 1.0 });
+        let merged = merge_delta(&base(), delta).unwrap();
+        assert_eq!(merged.job_id, "job-1");
+        assert_eq!(merged.status, "complete");
+        assert_eq!(merged.progress, 1.0);
+        assert_eq!(merged.message.as_deref(), Some("Transcribing"));
+        assert_eq!(merged.result_expires_at, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn null_clears_an_optional_field() {
+        let merged = merge_delta(&base(), json!({ "message": null, "result_expires_at": null })).unwrap();
+        assert_eq!(merged.message, None);
+        assert_eq!(merged.result_expires_at, None);
+        assert_eq!(merged.status, "processing");
+    }
+
+    #[test]
+    fn non_object_deltas_are_rejected() {
+        assert!(merge_delta(&base(), json!([{ "status": "complete" }])).is_err());
+        assert!(merge_delta(&base(), json!("complete")).is_err());
+        // A delta that doesn't fit the status shape is refused rather than half applied
+        assert!(merge_delta(&base(), json!({ "progress": "half" })).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+// Local listener for job-completion callbacks posted by the backend
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::tasks::{self, TaskId, TaskKind};
+use crate::{records, AppError, AppState, JobStatusResponse, ProcessOptions};
+
+// Limits on what a single callback request may send
+const MAX_CALLBACK_HEADER_BYTES: usize = 16 * 1024;
+const MAX_CALLBACK_BODY_BYTES: usize = 64 * 1024;
+const CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Waits after a failed accept, doubling while it keeps failing, e.g. out of file descriptors
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(50);
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(5);
+
+pub struct CallbackListener {
+    url: String,
+    task_id: TaskId,
+}
+
+// Unguessable token for callback URLs and local access, 32 bytes from the OS random source
+pub fn generate_secret() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate a secret: {}", e))?;
+    Ok(hex::encode(bytes))
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// URL the backend should post to for new submissions, if callbacks are on
+pub async fn callback_url(state: &AppState) -> Option<String> {
+    state.callbacks.lock().await.as_ref().map(|listener| listener.url.clone())
+}
+
+// Options payload for a submission, with the callback URL added when there is one
+pub fn options_payload(options: &ProcessOptions, callback_url: Option<&str>) -> Result<serde_json::Value, String> {
+    let mut payload = serde_json::to_value(options)
+        .map_err(|e| format!("Failed to encode options: {}", e))?;
+    if let (Some(url), Some(fields)) = (callback_url, payload.as_object_mut()) {
+        fields.insert("callback_url".to_string(), url.into());
+    }
+    Ok(payload)
+}
+
+// Whether a submission failed because the backend refused the callback URL
+pub fn rejected(status: reqwest::StatusCode) -> bool {
+    matches!(
+        status,
+        reqwest::StatusCode::BAD_REQUEST | reqwest::StatusCode::UNPROCESSABLE_ENTITY
+    )
+}
+
+// Read one HTTP request, returning its method, path and body
+async fn read_request(stream: &mut TcpStream) -> Result<(String, String, Vec<u8>), &'static str> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buffer.len() > MAX_CALLBACK_HEADER_BYTES {
+            return Err("431 Request Header Fields Too Large");
+        }
+        let read = stream.read(&mut chunk).await.map_err(|_| "400 Bad Request")?;
+        if read == 0 {
+            return Err("400 Bad Request");
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_CALLBACK_BODY_BYTES {
+        return Err("413 Payload Too Large");
+    }
+
+    let mut body = buffer.split_off(header_end);
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await.map_err(|_| "400 Bad Request")?;
+        if read == 0 {
+            return Err("400 Bad Request");
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Ok((method, path, body))
+}
+
+// Validate a callback and apply the reported status
+async fn handle_request(app: &AppHandle, stream: &mut TcpStream, expected_path: &str) -> &'static str {
+    let (method, path, body) = match tokio::time::timeout(CALLBACK_READ_TIMEOUT, read_request(stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(status)) => return status,
+        Err(_) => return "408 Request Timeout",
+    };
+
+    if !constant_time_eq(path.as_bytes(), expected_path.as_bytes()) {
+        return "403 Forbidden";
+    }
+    if method != "POST" {
+        return "405 Method Not Allowed";
+    }
+    let status: JobStatusResponse = match serde_json::from_slice(&body) {
+        Ok(status) => status,
+        Err(_) => return "400 Bad Request",
+    };
+
+    let state = app.state::<AppState>();
+    crate::apply_status(&state, &status.job_id, &status).await;
+
+    if records::is_finished_status(&status.status) {
+        // Nothing held back may arrive after the final state
+        state.progress_events.discard(&status.job_id);
+        let _ = app.emit_all("job-complete", status);
+    } else {
+        crate::progress_events::emit(app, &state, &status).await;
+    }
+    "204 No Content"
+}
+
+// Next incoming connection. Failures are logged and retried after a growing pause, since
+// the usual causes such as running out of file descriptors don't clear up at once.
+pub async fn accept(listener: &TcpListener, name: &str) -> TcpStream {
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => return stream,
+            Err(e) => {
+                eprintln!("{} failed to accept a connection: {}", name, e);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+            }
+        }
+    }
+}
+
+async fn serve(app: AppHandle, listener: TcpListener, expected_path: String) {
+    loop {
+        let mut stream = accept(&listener, "Callback listener").await;
+        let app = app.clone();
+        let expected_path = expected_path.clone();
+        tauri::async_runtime::spawn(async move {
+            let status = handle_request(&app, &mut stream, &expected_path).await;
+            let reply = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+            let _ = stream.write_all(reply.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+// Stop the listener; called when the app exits
+pub fn shutdown(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let listener = state.callbacks.try_lock().ok().and_then(|mut callbacks| callbacks.take());
+    if let Some(listener) = listener {
+        tasks::kill(&state.tasks, listener.task_id);
+    }
+}
+
+#[tauri::command]
+pub async fn enable_callbacks(app: AppHandle, state: State<'_, AppState>) -> Result<String, AppError> {
+    let mut callbacks = state.callbacks.lock().await;
+    if let Some(listener) = callbacks.as_ref() {
+        return Ok(listener.url.clone());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to start callback listener: {}", e))?;
+    let port = listener.local_addr()
+        .map_err(|e| format!("Failed to start callback listener: {}", e))?
+        .port();
+
+    let path = format!("/callback/{}", generate_secret()?);
+    let url = format!("http://127.0.0.1:{}{}", port, path);
+    let task_id = tasks::spawn(&state.tasks, TaskKind::CallbackListener, None, serve(app, listener, path));
+
+    *callbacks = Some(CallbackListener { url: url.clone(), task_id });
+    Ok(url)
+}
+
+#[tauri::command]
+pub async fn disable_callbacks(state: State<'_, AppState>) -> Result<bool, AppError> {
+    let listener = state.callbacks.lock().await.take();
+    if let Some(listener) = &listener {
+        tasks::kill(&state.tasks, listener.task_id);
+    }
+    Ok(listener.is_some())
+}
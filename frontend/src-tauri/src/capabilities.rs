@@ -0,0 +1,131 @@
+// What the connected backend advertises it can do
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{api, AppError, AppState, FieldError, ProcessOptions};
+
+// Languages, models and output formats the backend accepts; empty lists mean unknown
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Capabilities {
+    pub languages: Vec<String>,
+    pub models: Vec<String>,
+    pub formats: Vec<String>,
+    // Optional features such as diarization or translation, keyed by name
+    pub features: BTreeMap<String, Feature>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Feature {
+    // Smallest model that supports the feature, when it depends on the model
+    pub min_model: Option<String>,
+}
+
+impl Capabilities {
+    // Options or format this backend would reject, by field
+    pub fn field_errors(&self, options: &ProcessOptions, format: Option<&str>) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if let Some(language) = &options.language {
+            if !self.languages.is_empty() && !self.languages.contains(language) {
+                errors.push(FieldError::new("language", format!("Language '{}' is not supported", language)));
+            }
+        }
+        if let Some(model) = &options.model {
+            if !self.models.is_empty() && !self.models.contains(model) {
+                errors.push(FieldError::new("model", format!("Model '{}' is not available", model)));
+            }
+        }
+        if let Some(targets) = options.translate_to.as_ref().filter(|targets| !targets.is_empty()) {
+            if !self.features.is_empty() && !self.features.contains_key("translation") {
+                errors.push(FieldError::new("translate_to", "The backend does not offer translation".to_string()));
+            }
+            for target in targets {
+                if !self.languages.is_empty() && !self.languages.contains(target) {
+                    errors.push(FieldError::new("translate_to", format!("Language '{}' is not supported", target)));
+                }
+            }
+        }
+        if let Some(format) = format {
+            if !self.formats.is_empty() && !self.formats.iter().any(|f| f == format) {
+                errors.push(FieldError::new("format", format!("Output format '{}' is not supported", format)));
+            }
+        }
+        errors
+    }
+
+    // Reasons the options or format would be rejected by this backend
+    pub fn problems_with(&self, options: &ProcessOptions, format: Option<&str>) -> Vec<String> {
+        self.field_errors(options, format).into_iter().map(|error| error.reason).collect()
+    }
+}
+
+// Capabilities from the cache or the backend; None when the backend doesn't publish them
+pub async fn fetch(state: &AppState, force_refresh: bool) -> Result<Option<Capabilities>, AppError> {
+    if !force_refresh {
+        if let Some(capabilities) = state.capabilities.lock().await.clone() {
+            return Ok(Some(capabilities));
+        }
+    }
+
+    let request = state.client().get(format!("{}/capabilities", state.api_url()));
+    let response = api::send(state, request).await?;
+    let capabilities = from_response(response).await?;
+    if let Some(capabilities) = &capabilities {
+        *state.capabilities.lock().await = Some(capabilities.clone());
+    }
+    Ok(capabilities)
+}
+
+// Parse a /capabilities response; None when the backend doesn't publish them
+pub async fn from_response(response: reqwest::Response) -> Result<Option<Capabilities>, AppError> {
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND
+        | reqwest::StatusCode::METHOD_NOT_ALLOWED
+        | reqwest::StatusCode::NOT_IMPLEMENTED => return Ok(None),
+        status if !status.is_success() => return Err(api::error_from_response(response).await),
+        _ => {}
+    }
+
+    let capabilities: Capabilities = response.json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(Some(capabilities))
+}
+
+#[tauri::command]
+pub async fn get_capabilities(
+    state: State<'_, AppState>,
+    force_refresh: Option<bool>,
+) -> Result<Capabilities, AppError> {
+    fetch(&state, force_refresh.unwrap_or(false))
+        .await?
+        .ok_or_else(|| AppError::Unsupported {
+            feature: "capabilities".to_string(),
+        })
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureSupport {
+    supported: bool,
+    min_model: Option<String>,
+}
+
+// Unknown features, and backends that don't publish capabilities, report unsupported
+#[tauri::command]
+pub async fn supports_feature(
+    state: State<'_, AppState>,
+    feature: String,
+) -> Result<FeatureSupport, AppError> {
+    let feature = fetch(&state, false)
+        .await?
+        .and_then(|capabilities| capabilities.features.get(&feature).cloned());
+
+    Ok(FeatureSupport {
+        supported: feature.is_some(),
+        min_model: feature.and_then(|feature| feature.min_model),
+    })
+}
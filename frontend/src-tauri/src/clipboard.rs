@@ -0,0 +1,34 @@
+// Copying results to the system clipboard
+
+use tauri::{AppHandle, ClipboardManager, State};
+
+use crate::{AppError, AppState};
+
+// Formats that are plain text and make sense to paste
+const CLIPBOARD_FORMATS: &[&str] = &["txt", "md", "srt", "vtt", "json"];
+
+// Fetch a text result and put it on the clipboard, returning how many characters were copied
+#[tauri::command]
+pub async fn copy_transcript_to_clipboard(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    format: String,
+) -> Result<usize, AppError> {
+    if !CLIPBOARD_FORMATS.contains(&format.as_str()) {
+        return Err(format!(
+            "Format '{}' can't be copied as text; expected one of: {}",
+            format,
+            CLIPBOARD_FORMATS.join(", ")
+        ).into());
+    }
+
+    let bytes = crate::fetch_result(&state, &job_id, &format, &[]).await?;
+    let text = String::from_utf8(bytes).map_err(|_| format!("The {} result for job {} is not text", format, job_id))?;
+    let copied = text.chars().count();
+    app.clipboard_manager()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to the clipboard: {}", e))?;
+    crate::note_access(&state, &job_id).await;
+    Ok(copied)
+}
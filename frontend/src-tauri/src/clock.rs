@@ -0,0 +1,89 @@
+// Offset between the local clock and the backend's, for timestamp-sensitive features
+
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::records::unix_now;
+use crate::tasks::{self, TaskKind};
+use crate::{api, AppError, AppState};
+
+// Skew beyond this is reported to the UI as a warning
+const SKEW_WARNING_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClockSync {
+    // Seconds to add to local time to get server time
+    offset_secs: i64,
+    source: &'static str,
+}
+
+fn system_time_secs(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or_default()
+}
+
+// Server time from the /time endpoint, or from the Date header of any response
+async fn server_time(state: &AppState) -> Result<(f64, &'static str), AppError> {
+    let request = state.client().get(format!("{}/time", state.api_url()));
+    let response = api::send(state, request).await?;
+
+    if response.status().is_success() {
+        if let Ok(body) = response.json::<serde_json::Value>().await {
+            if let Some(now) = body.get("now").and_then(|now| now.as_f64()) {
+                return Ok((now, "time_endpoint"));
+            }
+        }
+    }
+
+    let request = state.client().get(format!("{}/", state.api_url()));
+    let response = api::send(state, request).await?;
+    let date = response.headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| httpdate::parse_http_date(value).ok())
+        .ok_or_else(|| "Backend did not report its time".to_string())?;
+    Ok((system_time_secs(date), "date_header"))
+}
+
+// Measure the offset and store it, keeping the old value if the backend can't say
+pub async fn sync(app: &AppHandle) -> Result<ClockSync, AppError> {
+    let state = app.state::<AppState>();
+
+    let sent_at = SystemTime::now();
+    let started = Instant::now();
+    let (server_secs, source) = server_time(&state).await?;
+
+    // Assume the server read its clock halfway through the round trip
+    let local_secs = system_time_secs(sent_at) + started.elapsed().as_secs_f64() / 2.0;
+    let offset_secs = (server_secs - local_secs).round() as i64;
+    *state.clock_offset_secs.lock().await = offset_secs;
+
+    if offset_secs.abs() > SKEW_WARNING_SECS {
+        let _ = app.emit_all("clock-skew-warning", offset_secs);
+    }
+
+    Ok(ClockSync { offset_secs, source })
+}
+
+// Current time on the backend's clock, as seconds since the Unix epoch
+pub async fn server_now(state: &AppState) -> u64 {
+    let offset = *state.clock_offset_secs.lock().await;
+    unix_now().saturating_add_signed(offset)
+}
+
+// Run the initial sync in the background so startup never waits on the network
+pub fn sync_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::ClockSync, None, async move {
+        // Give the backend a moment when both are launched together
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        if let Err(e) = sync(&app).await {
+            eprintln!("Clock sync failed, using local time: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn sync_clock(app: AppHandle) -> Result<ClockSync, AppError> {
+    sync(&app).await
+}
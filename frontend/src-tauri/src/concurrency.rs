@@ -0,0 +1,157 @@
+// Adaptive submission concurrency: additive increase while the backend keeps up, multiplicative decrease on overload
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::retry::ErrorClass;
+use crate::{AppError, AppState};
+
+// Weight of the newest latency in the running average
+const LATENCY_SMOOTHING: f64 = 0.2;
+// A success this much slower than average is a sign of congestion rather than health
+const SLOW_FACTOR: f64 = 2.0;
+
+#[derive(Debug, Clone, Serialize)]
+struct ConcurrencyChangedEvent {
+    // "batch" or "queue"
+    scope: &'static str,
+    concurrency: usize,
+}
+
+#[derive(Debug)]
+pub struct AdaptiveLimit {
+    scope: &'static str,
+    min: usize,
+    max: usize,
+    // Fractional so a full window of successes adds one slot
+    limit: f64,
+    average_latency: Option<f64>,
+}
+
+impl AdaptiveLimit {
+    pub fn new(scope: &'static str, min: usize, max: usize) -> Self {
+        let min = min.max(1);
+        let max = max.max(min);
+        AdaptiveLimit {
+            scope,
+            min,
+            max,
+            limit: min as f64,
+            average_latency: None,
+        }
+    }
+
+    // Bounds from the settings, keeping the current limit inside them
+    pub fn set_bounds(&mut self, min: usize, max: usize) {
+        self.min = min.max(1);
+        self.max = max.max(self.min);
+        self.limit = self.limit.clamp(self.min as f64, self.max as f64);
+    }
+
+    pub fn current(&self) -> usize {
+        (self.limit.floor() as usize).clamp(self.min, self.max)
+    }
+
+    // Adjust after a submission; returns the new limit when it changed
+    pub fn observe(&mut self, latency: Duration, result: Result<(), &AppError>) -> Option<usize> {
+        let before = self.current();
+        let latency = latency.as_secs_f64();
+        match result {
+            Ok(()) => {
+                let slow = self.average_latency.is_some_and(|average| latency > average * SLOW_FACTOR);
+                self.average_latency = Some(match self.average_latency {
+                    Some(average) => average + LATENCY_SMOOTHING * (latency - average),
+                    None => latency,
+                });
+                if !slow {
+                    self.limit = (self.limit + 1.0 / self.limit).min(self.max as f64);
+                }
+            }
+            // Timeouts, 429s and 5xx mean the backend is struggling; other failures say nothing about load
+            Err(e) if e.class() == ErrorClass::Retryable => {
+                self.limit = (self.limit / 2.0).max(self.min as f64);
+            }
+            Err(_) => {}
+        }
+        let after = self.current();
+        (after != before).then_some(after)
+    }
+}
+
+// Tell the UI the effective concurrency changed
+pub fn announce(state: &AppState, limit: &AdaptiveLimit, concurrency: usize) {
+    if let Some(app) = state.app_handle.get() {
+        let _ = app.emit_all(
+            "concurrency-changed",
+            ConcurrencyChangedEvent {
+                scope: limit.scope,
+                concurrency,
+            },
+        );
+    }
+}
+
+// Batch and queue limit, from the settings
+pub async fn limit_for(state: &AppState, scope: &'static str) -> AdaptiveLimit {
+    let settings = state.settings.lock().await;
+    AdaptiveLimit::new(scope, settings.min_concurrency, settings.max_concurrency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LATENCY: Duration = Duration::from_millis(200);
+
+    fn network_error() -> AppError {
+        AppError::Network { message: "Failed to send request: operation timed out".to_string() }
+    }
+
+    #[test]
+    fn successes_add_one_slot_per_window() {
+        let mut limit = AdaptiveLimit::new("test", 2, 4);
+        let changes: Vec<usize> = (0..20).filter_map(|_| limit.observe(LATENCY, Ok(()))).collect();
+        // Never past the maximum
+        assert_eq!(changes, [3, 4]);
+        assert_eq!(limit.current(), 4);
+    }
+
+    #[test]
+    fn slow_successes_hold_the_limit() {
+        let mut limit = AdaptiveLimit::new("test", 1, 8);
+        limit.observe(LATENCY, Ok(()));
+        let before = limit.current();
+        assert_eq!(limit.observe(LATENCY * 3, Ok(())), None);
+        assert_eq!(limit.current(), before);
+    }
+
+    #[test]
+    fn retryable_failures_halve_the_limit() {
+        let mut limit = AdaptiveLimit::new("test", 1, 16);
+        while limit.current() < 8 {
+            limit.observe(LATENCY, Ok(()));
+        }
+        let error = network_error();
+        assert_eq!(limit.observe(LATENCY, Err(&error)), Some(4));
+        let overloaded = AppError::Backend { status: 503, code: None, message: "busy".to_string() };
+        assert_eq!(limit.observe(LATENCY, Err(&overloaded)), Some(2));
+        limit.observe(LATENCY, Err(&error));
+        // Never under the minimum
+        assert_eq!(limit.observe(LATENCY, Err(&error)), None);
+        assert_eq!(limit.current(), 1);
+    }
+
+    #[test]
+    fn other_failures_leave_the_limit() {
+        let mut limit = AdaptiveLimit::new("test", 1, 8);
+        while limit.current() < 4 {
+            limit.observe(LATENCY, Ok(()));
+        }
+        let invalid = AppError::Backend { status: 400, code: Some("invalid_media".to_string()), message: "bad".to_string() };
+        assert_eq!(limit.observe(LATENCY, Err(&invalid)), None);
+        assert_eq!(limit.observe(LATENCY, Err(&AppError::Other("Failed to read file".to_string()))), None);
+        assert_eq!(limit.current(), 4);
+    }
+}
@@ -0,0 +1,441 @@
+// Support diagnostics for checking the connection to the backend
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::capabilities::{self, Capabilities};
+use crate::{api, network, records, transfers, AppError, AppState, JobStatusResponse, ProcessOptions, UploadForm};
+
+// Tiny known-good clip so the self-test never depends on user files
+const SELF_TEST_AUDIO: &[u8] = include_bytes!("../assets/self_test.wav");
+const SELF_TEST_FORMAT: &str = "md";
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(120);
+const SELF_TEST_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Throughput probe sizes; the cap keeps a careless call from wasting bandwidth
+const DEFAULT_THROUGHPUT_BYTES: usize = 2 * 1024 * 1024;
+const MAX_THROUGHPUT_BYTES: usize = 16 * 1024 * 1024;
+const LATENCY_SAMPLES: usize = 3;
+
+// A candidate backend that takes longer than this to answer fails the test
+const TEST_BACKEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize)]
+pub struct StageReport {
+    stage: String,
+    passed: bool,
+    duration_ms: u64,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    passed: bool,
+    total_ms: u64,
+    stages: Vec<StageReport>,
+}
+
+// Run one stage, recording its outcome and timing
+async fn run_stage<T, E, F>(stages: &mut Vec<StageReport>, stage: &str, future: F) -> Option<T>
+where
+    E: std::fmt::Display,
+    F: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let result = future.await;
+    stages.push(StageReport {
+        stage: stage.to_string(),
+        passed: result.is_ok(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: result.as_ref().err().map(|e| e.to_string()),
+    });
+    result.ok()
+}
+
+// Poll until the job finishes processing
+pub async fn wait_for_completion(state: &AppState, job_id: &str, timeout: Duration) -> Result<(), AppError> {
+    let started = Instant::now();
+    loop {
+        let status = crate::fetch_status(state, job_id).await?;
+        if records::is_finished_status(&status.status) {
+            return match status.status.as_str() {
+                "complete" => Ok(()),
+                "cancelled" => Err(format!("Job {} was cancelled before it finished", job_id).into()),
+                _ => Err(status.message.unwrap_or_else(|| "Processing failed".to_string()).into()),
+            };
+        }
+        if started.elapsed() > timeout {
+            return Err(format!("Job did not finish within {}s", timeout.as_secs()).into());
+        }
+        tokio::time::sleep(SELF_TEST_POLL_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub async fn run_self_test(state: State<'_, AppState>) -> Result<SelfTestReport, AppError> {
+    let started = Instant::now();
+    let mut stages = Vec::new();
+
+    let options = ProcessOptions::default();
+    let form = UploadForm::default();
+    let upload = crate::send_bytes(
+        &state,
+        SELF_TEST_AUDIO.to_vec(),
+        "quickscript_self_test.wav".to_string(),
+        &options,
+        &form,
+    );
+    let job_id = run_stage(&mut stages, "upload", upload).await.map(|response| response.job_id);
+
+    if let Some(job_id) = &job_id {
+        let processed = run_stage(&mut stages, "process", wait_for_completion(&state, job_id, SELF_TEST_TIMEOUT)).await;
+
+        if processed.is_some() {
+            let download = async {
+                let bytes = crate::fetch_result(&state, job_id, SELF_TEST_FORMAT, &[]).await?;
+                if bytes.is_empty() {
+                    return Err(AppError::from("Downloaded result is empty".to_string()));
+                }
+                Ok(())
+            };
+            run_stage(&mut stages, "download", download).await;
+        }
+
+        // The test job is never tracked, so removing it from the backend is enough
+        let cleanup = async {
+            let status = crate::send_cancel(&state, job_id).await?;
+            if status.is_success() || status == reqwest::StatusCode::NOT_FOUND {
+                Ok(())
+            } else {
+                Err(format!("Failed to remove test job: {}", status))
+            }
+        };
+        run_stage(&mut stages, "cleanup", cleanup).await;
+        state.status_cache.lock().await.invalidate(job_id);
+    }
+
+    Ok(SelfTestReport {
+        passed: job_id.is_some() && stages.iter().all(|stage| stage.passed),
+        total_ms: started.elapsed().as_millis() as u64,
+        stages,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThroughputReport {
+    latency_ms: f64,
+    test_bytes: usize,
+    // None when the backend has no benchmark endpoint for that direction
+    upload_mbps: Option<f64>,
+    download_mbps: Option<f64>,
+}
+
+fn mbps(bytes: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+    (bytes as f64 * 8.0) / secs / 1_000_000.0
+}
+
+// One cheap round trip to resolve DNS and leave a pooled connection open
+async fn warm_up(state: &AppState) -> Result<f64, String> {
+    let started = Instant::now();
+    let request = state.client().get(format!("{}/", state.api_url()));
+    api::send(state, request).await?;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    println!("Backend connection warmed up in {:.0}ms", latency_ms);
+    Ok(latency_ms)
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    reachable: bool,
+    status: Option<u16>,
+    latency_ms: f64,
+    remote_address: Option<String>,
+    // "ipv4" or "ipv6", for diagnosing dual-stack setups
+    address_family: Option<&'static str>,
+    error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn health_check(state: State<'_, AppState>) -> Result<HealthReport, AppError> {
+    let started = Instant::now();
+    let request = state.client().get(format!("{}/", state.api_url()));
+    let result = api::send(&state, request).await;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    Ok(match result {
+        Ok(response) => {
+            let remote = response.remote_addr();
+            HealthReport {
+                reachable: true,
+                status: Some(response.status().as_u16()),
+                latency_ms,
+                remote_address: remote.map(|addr| addr.to_string()),
+                address_family: remote.map(|addr| network::address_family(&addr.ip())),
+                error: None,
+            }
+        }
+        Err(e) => HealthReport {
+            reachable: false,
+            status: None,
+            latency_ms,
+            remote_address: None,
+            address_family: None,
+            error: Some(e),
+        },
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackendTest {
+    url: String,
+    reachable: bool,
+    status: Option<u16>,
+    latency_ms: f64,
+    // Reported by backends that include a version in their root response
+    version: Option<String>,
+    // None when the backend doesn't publish capabilities or they couldn't be read
+    capabilities: Option<Capabilities>,
+    capabilities_error: Option<String>,
+    error: Option<String>,
+}
+
+// Check a backend URL with a throwaway client built from the current network settings.
+// Nothing in the app state changes, and no credentials are sent to the candidate.
+#[tauri::command]
+pub async fn test_backend(state: State<'_, AppState>, url: String) -> Result<BackendTest, AppError> {
+    let mut settings = state.settings.lock().await.clone();
+    settings.api_url = url.trim().to_string();
+    let connection = network::connect(&settings)?;
+    let get = |path: &str| {
+        connection.client
+            .get(format!("{}{}", connection.api_url, path))
+            .timeout(TEST_BACKEND_TIMEOUT)
+            .send()
+    };
+
+    let started = Instant::now();
+    let response = get("/").await;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let mut report = BackendTest {
+        url: connection.api_url.clone(),
+        reachable: false,
+        status: None,
+        latency_ms,
+        version: None,
+        capabilities: None,
+        capabilities_error: None,
+        error: None,
+    };
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            report.error = Some(format!("Failed to send request: {}", e));
+            return Ok(report);
+        }
+    };
+    report.reachable = true;
+    report.status = Some(response.status().as_u16());
+    if !response.status().is_success() {
+        report.error = Some(api::error_from_response(response).await.to_string());
+        return Ok(report);
+    }
+    report.version = response.json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|body| body.get("version")?.as_str().map(str::to_string));
+
+    match get("/capabilities").await {
+        Ok(response) => match capabilities::from_response(response).await {
+            Ok(capabilities) => report.capabilities = capabilities,
+            Err(e) => report.capabilities_error = Some(e.to_string()),
+        },
+        Err(e) => report.capabilities_error = Some(format!("Failed to send request: {}", e)),
+    }
+    Ok(report)
+}
+
+// Warm the connection at startup without holding it up
+pub fn prewarm_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::Prewarm, None, async move {
+        if let Err(e) = warm_up(&app.state::<AppState>()).await {
+            eprintln!("Connection warm-up failed: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn prewarm(state: State<'_, AppState>) -> Result<f64, AppError> {
+    Ok(warm_up(&state).await?)
+}
+
+// Fastest of a few round trips to the API root
+async fn measure_latency(state: &AppState) -> Result<f64, String> {
+    let mut best: Option<Duration> = None;
+    for _ in 0..LATENCY_SAMPLES {
+        let started = Instant::now();
+        let request = state.client().get(format!("{}/", state.api_url()));
+        api::send(state, request).await?;
+        let elapsed = started.elapsed();
+        best = Some(best.map_or(elapsed, |current| current.min(elapsed)));
+    }
+    Ok(best.unwrap_or_default().as_secs_f64() * 1000.0)
+}
+
+// The upload endpoint discards what it receives, so nothing is left behind
+async fn measure_upload(state: &AppState, bytes: usize) -> Result<Option<f64>, String> {
+    let payload = vec![0u8; bytes];
+    let started = Instant::now();
+    let request = state.client().post(format!("{}/benchmark/upload", state.api_url()))
+        .body(payload);
+    let response = api::send(state, request).await?;
+    let elapsed = started.elapsed();
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("Upload benchmark failed: {}", response.status()));
+    }
+    Ok(Some(mbps(bytes, elapsed)))
+}
+
+async fn measure_download(state: &AppState, bytes: usize) -> Result<Option<f64>, String> {
+    let started = Instant::now();
+    let request = state.client().get(format!("{}/benchmark/download", state.api_url()))
+        .query(&[("bytes", bytes)]);
+    let mut response = api::send(state, request).await?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(format!("Download benchmark failed: {}", response.status()));
+    }
+
+    // Count bytes as they arrive instead of buffering the whole blob
+    let mut received = 0;
+    while let Some(chunk) = response.chunk()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        received += chunk.len();
+    }
+    Ok(Some(mbps(received, started.elapsed())))
+}
+
+#[tauri::command]
+pub async fn measure_throughput(
+    state: State<'_, AppState>,
+    test_bytes: Option<usize>,
+) -> Result<ThroughputReport, AppError> {
+    let test_bytes = test_bytes
+        .unwrap_or(DEFAULT_THROUGHPUT_BYTES)
+        .clamp(1, MAX_THROUGHPUT_BYTES);
+
+    let latency_ms = measure_latency(&state).await?;
+    let upload_mbps = measure_upload(&state, test_bytes).await?;
+    let download_mbps = measure_download(&state, test_bytes).await?;
+
+    Ok(ThroughputReport {
+        latency_ms,
+        test_bytes,
+        upload_mbps,
+        download_mbps,
+    })
+}
+
+// How an upload body is produced
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadStrategy {
+    // Whole file read into memory first
+    Buffered,
+    // File read in chunks as the request is sent
+    Streamed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadBenchmark {
+    strategy: UploadStrategy,
+    bytes: u64,
+    elapsed_ms: u64,
+    mbps: f64,
+    // Rough size of the upload body held in memory at once
+    peak_memory_bytes: u64,
+}
+
+async fn upload_part(state: &AppState, path: &Path, strategy: UploadStrategy, size: u64) -> Result<reqwest::multipart::Part, String> {
+    let part = match strategy {
+        UploadStrategy::Buffered => {
+            let content = tokio::fs::read(path)
+                .await
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            reqwest::multipart::Part::bytes(content)
+        }
+        UploadStrategy::Streamed => {
+            let file = tokio::fs::File::open(path)
+                .await
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            let body = reqwest::Body::wrap_stream(state.transfers.upload_chunks(file));
+            reqwest::multipart::Part::stream_with_length(body, size)
+        }
+    };
+    Ok(part.file_name(
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "benchmark".to_string()),
+    ))
+}
+
+#[tauri::command]
+pub async fn benchmark_upload(
+    state: State<'_, AppState>,
+    path: String,
+    strategy: UploadStrategy,
+) -> Result<UploadBenchmark, AppError> {
+    let path = PathBuf::from(path);
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+
+    // Timed from the first read so buffering counts against the buffered strategy
+    let started = Instant::now();
+    let part = upload_part(&state, &path, strategy, size).await?;
+    let form = reqwest::multipart::Form::new()
+        .part(crate::DEFAULT_PART_NAME, part)
+        .text("options", "{}");
+    let request = state.client().post(format!("{}/process/file", state.api_url()))
+        .multipart(form);
+    let response = api::send(&state, request).await?;
+    let elapsed = started.elapsed();
+
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+
+    // The job only existed to receive the upload
+    if let Ok(job) = response.json::<JobStatusResponse>().await {
+        if let Err(e) = crate::send_cancel(&state, &job.job_id).await {
+            eprintln!("Failed to discard benchmark job {}: {}", job.job_id, e);
+        }
+    }
+
+    let peak_memory_bytes = match strategy {
+        UploadStrategy::Buffered => size,
+        UploadStrategy::Streamed => size.min(transfers::CHUNK_BYTES as u64),
+    };
+    Ok(UploadBenchmark {
+        strategy,
+        bytes: size,
+        elapsed_ms: elapsed.as_millis() as u64,
+        mbps: mbps(size as usize, elapsed),
+        peak_memory_bytes,
+    })
+}
@@ -0,0 +1,216 @@
+// Transcript markers for video editor projects, generated client-side from the JSON transcript
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::transcript::{self, Segment};
+use crate::{formats, AppError, AppState};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditorFormat {
+    // FCPXML with one marker per segment on a gap clip
+    FinalCutXml,
+    // Marker list Premiere panels and scripts import
+    PremiereCsv,
+    // CMX3600 EDL in the marker layout DaVinci Resolve imports
+    DavinciEdl,
+}
+
+impl EditorFormat {
+    // Whether positions have to land on frames
+    fn is_frame_based(self) -> bool {
+        matches!(self, EditorFormat::FinalCutXml | EditorFormat::DavinciEdl)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EditorExport {
+    path: String,
+    markers: usize,
+}
+
+// Frame rate as an exact fraction, recognising the NTSC rates like 29.97 as n * 1000/1001
+#[derive(Debug, Clone, Copy)]
+struct FrameRate {
+    // Frames per second, rounded, for timecode counting
+    nominal: u64,
+    // Seconds per frame as numerator / denominator
+    frame_num: u64,
+    frame_den: u64,
+}
+
+impl FrameRate {
+    fn new(fps: f64) -> Result<FrameRate, String> {
+        if !(fps > 0.0 && fps <= formats::MAX_FRAME_RATE) {
+            return Err(format!("frame_rate must be between 0 and {}, got {}", formats::MAX_FRAME_RATE, fps));
+        }
+        let nominal = fps.round().max(1.0) as u64;
+        if (fps - fps.round()).abs() < 0.001 {
+            return Ok(FrameRate { nominal, frame_num: 1, frame_den: nominal });
+        }
+        let ntsc = (fps * 1.001).round() as u64;
+        if (fps - ntsc as f64 / 1.001).abs() < 0.001 {
+            return Ok(FrameRate { nominal: ntsc, frame_num: 1001, frame_den: ntsc * 1000 });
+        }
+        Err(format!("Unsupported frame_rate {}; use a whole or NTSC rate like 25 or 29.97", fps))
+    }
+
+    fn frames(self, secs: f64) -> u64 {
+        (secs.max(0.0) * self.frame_den as f64 / self.frame_num as f64).round() as u64
+    }
+
+    // FCPXML rational time, e.g. 1001/30000s
+    fn rational(self, frames: u64) -> String {
+        format!("{}/{}s", frames * self.frame_num, self.frame_den)
+    }
+
+    // Non-drop-frame HH:MM:SS:FF
+    fn timecode(self, frames: u64) -> String {
+        let (seconds, frame) = (frames / self.nominal, frames % self.nominal);
+        format!("{:02}:{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60, frame)
+    }
+}
+
+// What a marker says: the segment text, led by its speaker
+fn marker_text(segment: &Segment) -> String {
+    let text = segment.text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match &segment.speaker {
+        Some(speaker) => format!("{}: {}", speaker, text),
+        None => text,
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn write_fcpxml(segments: &[Segment], name: &str, rate: FrameRate) -> String {
+    let end = segments.iter().map(|segment| rate.frames(segment.end)).max().unwrap_or(0).max(1);
+    let name = escape_xml(name);
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE fcpxml>\n<fcpxml version=\"1.9\">\n");
+    xml.push_str(&format!(
+        "  <resources>\n    <format id=\"r1\" frameDuration=\"{}\"/>\n  </resources>\n",
+        rate.rational(1)
+    ));
+    xml.push_str(&format!("  <library>\n    <event name=\"{}\">\n      <project name=\"{}\">\n", name, name));
+    xml.push_str(&format!("        <sequence format=\"r1\" duration=\"{}\">\n          <spine>\n", rate.rational(end)));
+    xml.push_str(&format!(
+        "            <gap name=\"Transcript\" offset=\"0s\" start=\"0s\" duration=\"{}\">\n",
+        rate.rational(end)
+    ));
+    for segment in segments {
+        let start = rate.frames(segment.start);
+        let duration = rate.frames(segment.end).saturating_sub(start).max(1);
+        xml.push_str(&format!(
+            "              <marker start=\"{}\" duration=\"{}\" value=\"{}\"/>\n",
+            rate.rational(start),
+            rate.rational(duration),
+            escape_xml(&marker_text(segment))
+        ));
+    }
+    xml.push_str("            </gap>\n          </spine>\n        </sequence>\n      </project>\n    </event>\n  </library>\n</fcpxml>\n");
+    xml
+}
+
+// HH:MM:SS.mmm, since the marker list isn't tied to a frame rate
+fn clock_time(secs: f64) -> String {
+    let millis = (secs.max(0.0) * 1000.0).round() as u64;
+    let seconds = millis / 1000;
+    format!("{:02}:{:02}:{:02}.{:03}", seconds / 3600, seconds / 60 % 60, seconds % 60, millis % 1000)
+}
+
+fn write_premiere_csv(segments: &[Segment]) -> String {
+    let mut csv = String::from("Marker Name,Description,In,Out,Duration,Marker Type\n");
+    for segment in segments {
+        let name = segment.speaker.clone().unwrap_or_else(|| "Transcript".to_string());
+        csv.push_str(&format!(
+            "{},{},{},{},{},Comment\n",
+            escape_csv(&name),
+            escape_csv(&marker_text(segment)),
+            clock_time(segment.start),
+            clock_time(segment.end),
+            clock_time((segment.end - segment.start).max(0.0))
+        ));
+    }
+    csv
+}
+
+// Resolve timelines start at 01:00:00:00 unless changed, so markers are placed from there
+fn write_davinci_edl(segments: &[Segment], name: &str, rate: FrameRate) -> String {
+    let timeline_start = rate.nominal * 3600;
+    let mut edl = format!("TITLE: {}\nFCM: NON-DROP FRAME\n\n", name.replace(['\n', '\r'], " "));
+    for (index, segment) in segments.iter().enumerate() {
+        let start = timeline_start + rate.frames(segment.start);
+        let duration = rate.frames(segment.end).saturating_sub(rate.frames(segment.start)).max(1);
+        let (record_in, record_out) = (rate.timecode(start), rate.timecode(start + 1));
+        edl.push_str(&format!(
+            "{:03}  001      V     C        {} {} {} {}  \n |C:ResolveColorBlue |M:{} |D:{}\n\n",
+            index + 1,
+            record_in,
+            record_out,
+            record_in,
+            record_out,
+            marker_text(segment).replace('|', "/"),
+            duration
+        ));
+    }
+    edl
+}
+
+#[tauri::command]
+pub async fn export_to_format(
+    state: State<'_, AppState>,
+    job_id: String,
+    target: EditorFormat,
+    dest_path: String,
+    frame_rate: Option<f64>,
+) -> Result<EditorExport, AppError> {
+    let rate = match (target.is_frame_based(), frame_rate) {
+        (true, Some(fps)) => Some(FrameRate::new(fps)?),
+        (true, None) => return Err("This editor format is frame-based and needs a frame_rate".to_string().into()),
+        (false, _) => None,
+    };
+
+    let (transcript, _) = transcript::load(&state, &job_id).await?;
+    let segments: Vec<Segment> = transcript.segments
+        .into_iter()
+        .filter(|segment| segment.start.is_finite() && segment.end.is_finite() && !segment.text.trim().is_empty())
+        .collect();
+    if segments.is_empty() || segments.iter().all(|segment| segment.end <= 0.0) {
+        return Err(format!("The result for job {} has no timestamped segments", job_id).into());
+    }
+
+    let name = Path::new(&dest_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| job_id.clone());
+    let contents = match (target, rate) {
+        (EditorFormat::FinalCutXml, Some(rate)) => write_fcpxml(&segments, &name, rate),
+        (EditorFormat::DavinciEdl, Some(rate)) => write_davinci_edl(&segments, &name, rate),
+        (EditorFormat::PremiereCsv, _) => write_premiere_csv(&segments),
+        (_, None) => unreachable!("frame-based formats always have a rate"),
+    };
+
+    tokio::fs::write(&dest_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(EditorExport {
+        path: dest_path,
+        markers: segments.len(),
+    })
+}
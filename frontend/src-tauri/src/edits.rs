@@ -0,0 +1,162 @@
+// Local corrections to a transcript, kept beside the cached original without changing it
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::unix_now;
+use crate::{persist, result_cache, AppError, AppState};
+
+// Larger than any real transcript; stops a runaway paste from filling the cache
+const MAX_EDIT_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredEdit {
+    job_id: String,
+    format: String,
+    content: String,
+    edited_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    // Checksum of the cached original the edit was made against, if it was cached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    original_sha256: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EditedTranscript {
+    job_id: String,
+    format: String,
+    content: String,
+    // False when there is no edit and this is the original
+    edited: bool,
+    edited_at: Option<u64>,
+    note: Option<String>,
+    // The cached original changed after the edit was made, e.g. it was re-cached
+    original_changed: bool,
+}
+
+async fn load_edit(state: &AppState, job_id: &str) -> Result<Option<StoredEdit>, AppError> {
+    let path = result_cache::edited_path(state, job_id);
+    let contents = match tokio::fs::read(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(crate::error::io_error("Failed to read edited transcript", &path, e)),
+    };
+    serde_json::from_slice(&contents)
+        .map(Some)
+        .map_err(|e| format!("Edited transcript for job {} is unreadable: {}", job_id, e).into())
+}
+
+// Edits are stored under the job ID, so only jobs in the records may name a file
+async fn ensure_known(state: &AppState, job_id: &str) -> Result<(), AppError> {
+    if !state.job_records.lock().await.contains_key(job_id) {
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
+    Ok(())
+}
+
+async fn original_checksum(state: &AppState, job_id: &str) -> Result<Option<String>, String> {
+    Ok(result_cache::read(state, job_id).await?.map(|contents| result_cache::sha256_hex(&contents)))
+}
+
+// Save an edited copy of a job's transcript; the original result is left as it was
+#[tauri::command]
+pub async fn save_transcript_edits(
+    state: State<'_, AppState>,
+    job_id: String,
+    edited_content: String,
+    format: String,
+    note: Option<String>,
+) -> Result<EditedTranscript, AppError> {
+    ensure_known(&state, &job_id).await?;
+    if format.is_empty() || !format.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid result format '{}'", format).into());
+    }
+    if edited_content.len() > MAX_EDIT_BYTES {
+        return Err(format!("Edited transcript is larger than {} bytes", MAX_EDIT_BYTES).into());
+    }
+    if format == "json" {
+        serde_json::from_str::<serde_json::Value>(&edited_content)
+            .map_err(|e| format!("Edited transcript is not valid JSON: {}", e))?;
+    }
+
+    let edit = StoredEdit {
+        job_id: job_id.clone(),
+        format,
+        content: edited_content,
+        edited_at: unix_now(),
+        note: note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+        original_sha256: original_checksum(&state, &job_id).await?,
+    };
+    persist::save_json(&result_cache::edited_path(&state, &job_id), &edit).await?;
+    Ok(EditedTranscript {
+        job_id,
+        format: edit.format,
+        content: edit.content,
+        edited: true,
+        edited_at: Some(edit.edited_at),
+        note: edit.note,
+        original_changed: false,
+    })
+}
+
+// The edited transcript when there is one, else the original in the given format (JSON by default)
+#[tauri::command]
+pub async fn get_edited_transcript(
+    state: State<'_, AppState>,
+    job_id: String,
+    format: Option<String>,
+) -> Result<EditedTranscript, AppError> {
+    ensure_known(&state, &job_id).await?;
+    if let Some(edit) = load_edit(&state, &job_id).await? {
+        let original_changed = match &edit.original_sha256 {
+            Some(checksum) => original_checksum(&state, &job_id).await?.is_some_and(|current| current != *checksum),
+            None => false,
+        };
+        return Ok(EditedTranscript {
+            job_id,
+            format: edit.format,
+            content: edit.content,
+            edited: true,
+            edited_at: Some(edit.edited_at),
+            note: edit.note,
+            original_changed,
+        });
+    }
+
+    let format = format.unwrap_or_else(|| "json".to_string());
+    let cached = match format.as_str() {
+        "json" => result_cache::read(&state, &job_id).await?,
+        _ => None,
+    };
+    let bytes = match cached {
+        Some(bytes) => bytes,
+        None => crate::fetch_result(&state, &job_id, &format, &[]).await?,
+    };
+    let content = String::from_utf8(bytes)
+        .map_err(|_| format!("The {} result for job {} is not text", format, job_id))?;
+    Ok(EditedTranscript {
+        job_id,
+        format,
+        content,
+        edited: false,
+        edited_at: None,
+        note: None,
+        original_changed: false,
+    })
+}
+
+// Discard the edited copy, reporting whether there was one
+#[tauri::command]
+pub async fn revert_transcript_edits(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<bool, AppError> {
+    ensure_known(&state, &job_id).await?;
+    let path = result_cache::edited_path(&state, &job_id);
+    match tokio::fs::remove_file(&path).await {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(crate::error::io_error("Failed to remove edited transcript", &path, e)),
+    }
+}
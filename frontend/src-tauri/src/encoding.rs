@@ -0,0 +1,93 @@
+// Character encodings text results can be re-encoded to for tools that mishandle plain UTF-8
+
+use std::path::Path;
+
+use crate::error::{io_error, AppError};
+
+// Formats the backend returns as UTF-8 text meant to be opened in other tools
+const TEXT_FORMATS: &[&str] = &["srt", "vtt", "txt", "md"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    pub fn parse(name: &str) -> Result<Encoding, String> {
+        match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            // Unmarked UTF-16 is little-endian, as Windows tools expect
+            "utf-16" | "utf16" | "utf-16le" => Ok(Encoding::Utf16Le),
+            "utf-16be" => Ok(Encoding::Utf16Be),
+            _ => Err(format!("Unsupported encoding '{}'; expected utf-8, utf-16le or utf-16be", name)),
+        }
+    }
+
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Encoding::Utf16Le => &[0xFF, 0xFE],
+            Encoding::Utf16Be => &[0xFE, 0xFF],
+        }
+    }
+
+    pub fn encode(self, text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(text.len() * 2 + 3);
+        if bom {
+            bytes.extend_from_slice(self.bom());
+        }
+        match self {
+            Encoding::Utf8 => bytes.extend_from_slice(text.as_bytes()),
+            Encoding::Utf16Le => bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes)),
+            Encoding::Utf16Be => bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes)),
+        }
+        bytes
+    }
+}
+
+// How a downloaded text result should be written; UTF-8 without a BOM leaves it untouched
+#[derive(Debug, Clone, Copy)]
+pub struct OutputEncoding {
+    encoding: Encoding,
+    bom: bool,
+}
+
+impl OutputEncoding {
+    // UTF-16 gets a BOM unless asked not to, since readers can't tell the byte order otherwise
+    pub fn parse(format: &str, encoding: Option<&str>, bom: Option<bool>) -> Result<Option<OutputEncoding>, String> {
+        let encoding = encoding.map(Encoding::parse).transpose()?.unwrap_or(Encoding::Utf8);
+        let bom = bom.unwrap_or(encoding != Encoding::Utf8);
+        if encoding == Encoding::Utf8 && !bom {
+            return Ok(None);
+        }
+        if !TEXT_FORMATS.contains(&format) {
+            return Err(format!(
+                "Format '{}' can't be re-encoded; encodings apply to {}",
+                format,
+                TEXT_FORMATS.join(", ")
+            ));
+        }
+        Ok(Some(OutputEncoding { encoding, bom }))
+    }
+
+    // Re-encode a downloaded UTF-8 file in place, replacing it atomically
+    pub async fn apply(self, path: &Path) -> Result<(), AppError> {
+        let contents = tokio::fs::read(path)
+            .await
+            .map_err(|e| io_error("Failed to read file", path, e))?;
+        let text = String::from_utf8(contents).map_err(|_| "Result is not valid UTF-8".to_string())?;
+        // The backend may already have marked it
+        let text = text.strip_prefix('\u{FEFF}').unwrap_or(&text);
+
+        let mut part = path.as_os_str().to_owned();
+        part.push(".part");
+        tokio::fs::write(&part, self.encoding.encode(text, self.bom))
+            .await
+            .map_err(|e| io_error("Failed to write file", path, e))?;
+        tokio::fs::rename(&part, path)
+            .await
+            .map_err(|e| io_error("Failed to write file", path, e))
+    }
+}
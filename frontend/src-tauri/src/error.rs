@@ -0,0 +1,172 @@
+// Errors returned to the frontend by Tauri commands
+
+use std::path::{Path, PathBuf};
+
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+
+use crate::retry::{self, ErrorClass};
+
+// One invalid field, named the way the frontend sends it
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, reason: String) -> Self {
+        FieldError {
+            field: field.to_string(),
+            reason,
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Job {job_id} was not found; it may have expired or been cancelled")]
+    JobNotFound { job_id: String },
+    #[error("Backend returned {status}: {message}")]
+    Backend { status: u16, code: Option<String>, message: String },
+    #[error("Could not reach the backend: {message}")]
+    Network { message: String },
+    #[error("Not authorized: {message}")]
+    Unauthorized { message: String },
+    #[error("The backend does not support {feature}")]
+    Unsupported { feature: String },
+    #[error("Expected JSON from the backend but got content type {content_type}: {snippet}")]
+    UnexpectedResponse { content_type: String, snippet: String },
+    #[error("Failed to download {url}: {message}")]
+    FetchFailed { url: String, message: String },
+    #[error("Operation {op_id} was cancelled")]
+    Cancelled { op_id: String },
+    #[error("Invalid options: {}", errors.iter().map(FieldError::to_string).collect::<Vec<_>>().join("; "))]
+    Validation { errors: Vec<FieldError> },
+    #[error("{command} is disabled in viewer mode")]
+    Disabled { command: String },
+    #[error("{command} is unavailable while QuickScript shuts down")]
+    ShuttingDown { command: String },
+    #[error("Permission denied for {path}. {suggestion}")]
+    PermissionDenied { path: String, suggestion: String },
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    // Stable identifier the UI can match on
+    fn kind(&self) -> &'static str {
+        match self {
+            AppError::JobNotFound { .. } => "job_not_found",
+            AppError::Backend { .. } => "backend",
+            AppError::Network { .. } => "network",
+            AppError::Unauthorized { .. } => "unauthorized",
+            AppError::Unsupported { .. } => "unsupported",
+            AppError::UnexpectedResponse { .. } => "unexpected_response",
+            AppError::FetchFailed { .. } => "fetch_failed",
+            AppError::Cancelled { .. } => "cancelled",
+            AppError::Validation { .. } => "validation",
+            AppError::Disabled { .. } => "disabled",
+            AppError::ShuttingDown { .. } => "shutting_down",
+            AppError::PermissionDenied { .. } => "permission_denied",
+            AppError::Other(_) => "other",
+        }
+    }
+}
+
+impl AppError {
+    // Whether retrying could help, for deciding whether to offer it
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            AppError::Backend { status, code, .. } => retry::classify(*status, code.as_deref()),
+            AppError::Network { .. } | AppError::UnexpectedResponse { .. } | AppError::FetchFailed { .. } => {
+                ErrorClass::Retryable
+            }
+            AppError::Unauthorized { .. } | AppError::Validation { .. } | AppError::PermissionDenied { .. } => {
+                ErrorClass::NeedsUserAction
+            }
+            AppError::JobNotFound { .. }
+            | AppError::Unsupported { .. }
+            | AppError::Cancelled { .. }
+            | AppError::Disabled { .. }
+            | AppError::ShuttingDown { .. }
+            | AppError::Other(_) => ErrorClass::Fatal,
+        }
+    }
+}
+
+// Folders macOS guards behind Files and Folders privacy consent
+const MACOS_PROTECTED_DIRS: &[&str] = &["Desktop", "Documents", "Downloads"];
+
+// What the user can do about a denied path
+fn permission_suggestion(path: &Path) -> String {
+    if cfg!(target_os = "macos") {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let protected = home.is_some_and(|home| {
+            MACOS_PROTECTED_DIRS.iter().any(|dir| path.starts_with(home.join(dir)))
+        }) || path.starts_with("/Volumes");
+        if protected {
+            return "Grant QuickScript access to this folder in System Settings > Privacy & Security > Files and Folders, or choose a different folder.".to_string();
+        }
+    }
+    "Choose a different folder, or check that your account can read and write it.".to_string()
+}
+
+// A filesystem error, turning permission problems into PermissionDenied with a suggestion
+pub fn io_error(context: &str, path: &Path, e: std::io::Error) -> AppError {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        return AppError::PermissionDenied {
+            path: path.display().to_string(),
+            suggestion: permission_suggestion(path),
+        };
+    }
+    AppError::Other(format!("{}: {}", context, e))
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+// Serialized as { kind, message, ...details } so the UI can both display and branch on it
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        map.serialize_entry("class", &self.class())?;
+        match self {
+            AppError::JobNotFound { job_id } => map.serialize_entry("job_id", job_id)?,
+            AppError::Backend { status, code, .. } => {
+                map.serialize_entry("status", status)?;
+                if let Some(code) = code {
+                    map.serialize_entry("code", code)?;
+                }
+            }
+            AppError::Unsupported { feature } => map.serialize_entry("feature", feature)?,
+            AppError::UnexpectedResponse { content_type, snippet } => {
+                map.serialize_entry("content_type", content_type)?;
+                map.serialize_entry("snippet", snippet)?;
+            }
+            AppError::FetchFailed { url, .. } => map.serialize_entry("url", url)?,
+            AppError::Cancelled { op_id } => map.serialize_entry("op_id", op_id)?,
+            AppError::Validation { errors } => map.serialize_entry("errors", errors)?,
+            AppError::Disabled { command } | AppError::ShuttingDown { command } => {
+                map.serialize_entry("command", command)?
+            }
+            AppError::PermissionDenied { path, suggestion } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("suggestion", suggestion)?;
+            }
+            AppError::Unauthorized { .. } | AppError::Network { .. } | AppError::Other(_) => {}
+        }
+        map.end()
+    }
+}
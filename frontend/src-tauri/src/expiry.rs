@@ -0,0 +1,72 @@
+// Warning about results the backend is about to delete
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::{clock, AppError, AppState};
+
+// How often records are checked for approaching expiry
+const WATCH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// Results expiring sooner than this are reported
+const EXPIRY_WARNING_SECS: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiringJob {
+    job_id: String,
+    // Unix seconds on the backend's clock
+    result_expires_at: u64,
+    expires_in_secs: u64,
+    downloaded: bool,
+}
+
+// Finished jobs whose results expire within within_secs, soonest first
+async fn expiring_within(state: &AppState, within_secs: u64) -> Vec<ExpiringJob> {
+    let now = clock::server_now(state).await;
+    let mut jobs: Vec<ExpiringJob> = state.job_records.lock().await
+        .values()
+        .filter(|record| record.status == "complete")
+        .filter_map(|record| {
+            let expires_at = record.result_expires_at?;
+            let expires_in_secs = expires_at.checked_sub(now)?;
+            (expires_in_secs <= within_secs).then(|| ExpiringJob {
+                job_id: record.job_id.clone(),
+                result_expires_at: expires_at,
+                expires_in_secs,
+                downloaded: !record.downloads.is_empty(),
+            })
+        })
+        .collect();
+    jobs.sort_by_key(|job| job.result_expires_at);
+    jobs
+}
+
+// Emit "result-expiring-soon" once per job as its result nears expiry
+pub fn watch_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::ExpiryWatcher, None, async move {
+        let mut warned: HashSet<String> = HashSet::new();
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            for job in expiring_within(&state, EXPIRY_WARNING_SECS).await {
+                if warned.insert(job.job_id.clone()) {
+                    let _ = app.emit_all("result-expiring-soon", job);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_expiring_jobs(
+    state: State<'_, AppState>,
+    within_secs: u64,
+) -> Result<Vec<ExpiringJob>, AppError> {
+    Ok(expiring_within(&state, within_secs).await)
+}
@@ -0,0 +1,182 @@
+// Client-side fetching of remote media so it can be uploaded like a local file
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::AsyncWriteExt;
+
+use crate::media::TempFile;
+use crate::records::JobSource;
+use crate::{presets, timeouts, transfers, AppError, AppState, ProcessOptions, UploadForm};
+
+// Largest remote file that will be downloaded for upload
+const MAX_FETCH_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+// Emit a progress event at most once per this many bytes
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+struct FetchProgressEvent {
+    url: String,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+}
+
+// Downloaded media, removed from disk when dropped
+pub struct FetchedMedia {
+    pub file: TempFile,
+    pub file_name: String,
+}
+
+fn is_media_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    // Servers that don't label their files are given the benefit of the doubt
+    mime.is_empty()
+        || mime.starts_with("audio/")
+        || mime.starts_with("video/")
+        || matches!(mime.as_str(), "application/octet-stream" | "application/ogg")
+}
+
+// Extension for unnamed downloads, from the content type
+fn extension_for(content_type: &str) -> &'static str {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "audio/mpeg" => "mp3",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/mp4" | "audio/x-m4a" => "m4a",
+        "audio/aac" => "aac",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/ogg" | "application/ogg" => "ogg",
+        "audio/opus" => "opus",
+        "audio/webm" | "video/webm" => "webm",
+        "video/mp4" => "mp4",
+        "video/quicktime" => "mov",
+        "video/x-matroska" => "mkv",
+        _ => "bin",
+    }
+}
+
+// Name to upload the media under: the URL's last path segment, or one made up from the content type
+fn file_name_for(url: &reqwest::Url, content_type: &str) -> String {
+    let last_segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|segment| segment.contains('.'))
+        .map(|segment| segment.replace(['/', '\\'], "_"));
+    last_segment.unwrap_or_else(|| format!("media.{}", extension_for(content_type)))
+}
+
+// Download url into a temp file, reporting progress as "fetch-progress" events
+pub async fn fetch_to_temp(state: &AppState, url: &str) -> Result<FetchedMedia, AppError> {
+    let fail = |message: String| AppError::FetchFailed {
+        url: url.to_string(),
+        message,
+    };
+
+    let parsed = reqwest::Url::parse(url).map_err(|e| fail(format!("Invalid URL: {}", e)))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(fail(format!("Unsupported URL scheme: {}", parsed.scheme())));
+    }
+
+    // Not a backend request, so it goes out unsigned
+    let response = state
+        .client()
+        .get(parsed.clone())
+        .send()
+        .await
+        .map_err(|e| fail(format!("Failed to send request: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(fail(format!("Server returned {}", response.status())));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !is_media_content_type(&content_type) {
+        return Err(fail(format!("URL does not point to audio or video ({})", content_type)));
+    }
+
+    let total_bytes = response.content_length();
+    if total_bytes.is_some_and(|len| len > MAX_FETCH_BYTES) {
+        return Err(fail(format!("Remote file is larger than {} bytes", MAX_FETCH_BYTES)));
+    }
+
+    let file_name = file_name_for(&parsed, &content_type);
+    let extension = std::path::Path::new(&file_name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "bin".to_string());
+    let temp = TempFile::new("fetch", &extension);
+    let mut file = tokio::fs::File::create(temp.path())
+        .await
+        .map_err(|e| fail(format!("Failed to create temp file: {}", e)))?;
+
+    let emit_progress = |downloaded_bytes: u64| {
+        if let Some(app) = state.app_handle.get() {
+            let event = FetchProgressEvent {
+                url: url.to_string(),
+                downloaded_bytes,
+                total_bytes,
+            };
+            let _ = app.emit_all("fetch-progress", event);
+        }
+    };
+
+    let mut response = transfers::Download::new(response);
+    // Count as we go so a missing or wrong Content-Length can't exceed the cap
+    let mut downloaded: u64 = 0;
+    let mut last_reported: u64 = 0;
+    emit_progress(0);
+    while let Some((chunk, _buffer)) = state.transfers
+        .next_chunk(&mut response)
+        .await
+        .map_err(|e| fail(format!("Failed to read response: {}", e)))?
+    {
+        downloaded += chunk.len() as u64;
+        if downloaded > MAX_FETCH_BYTES {
+            return Err(fail(format!("Remote file is larger than {} bytes", MAX_FETCH_BYTES)));
+        }
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| fail(format!("Failed to write temp file: {}", e)))?;
+        if downloaded - last_reported >= PROGRESS_STEP_BYTES {
+            last_reported = downloaded;
+            emit_progress(downloaded);
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|e| fail(format!("Failed to write temp file: {}", e)))?;
+    if downloaded == 0 {
+        return Err(fail("Remote file is empty".to_string()));
+    }
+    emit_progress(downloaded);
+
+    Ok(FetchedMedia { file: temp, file_name })
+}
+
+// Download remote media ourselves and upload it, for URLs the backend can't reach
+#[tauri::command]
+pub async fn fetch_and_upload(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    max_duration_secs: Option<u64>,
+) -> Result<String, AppError> {
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+
+    // Download errors come back as fetch_failed; upload errors keep their usual kinds
+    let media = fetch_to_temp(&state, &url).await?;
+    let api_response =
+        crate::send_file(&state, media.file.path(), media.file_name.clone(), &options, &UploadForm::default()).await?;
+    drop(media);
+
+    crate::track_job(&state, &api_response.job_id, JobSource::Fetched { url }, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
+
+    Ok(api_response.job_id)
+}
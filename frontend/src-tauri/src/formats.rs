@@ -0,0 +1,93 @@
+// Output format options understood by the download endpoint
+
+use std::collections::HashMap;
+
+// Subtitle layout options shared by the cue-based formats
+const SUBTITLE_OPTIONS: &[&str] = &[
+    "max_line_length",
+    "max_lines_per_cue",
+    "include_speaker_labels",
+    "timestamp_style",
+    "frame_rate",
+];
+const TEXT_OPTIONS: &[&str] = &["include_speaker_labels", "include_timestamps", "timestamp_style", "frame_rate"];
+
+// How timestamps are written: decimal comma (SRT), decimal period (VTT) or HH:MM:SS:FF frames
+const TIMESTAMP_STYLES: &[&str] = &["comma", "period", "frames"];
+pub const MAX_FRAME_RATE: f64 = 240.0;
+
+// Option keys the backend accepts for a given output format
+fn allowed_options(format: &str) -> &'static [&'static str] {
+    match format {
+        "srt" | "vtt" => SUBTITLE_OPTIONS,
+        "txt" | "md" => TEXT_OPTIONS,
+        _ => &[],
+    }
+}
+
+// Validate format options and flatten them into query parameters
+pub fn format_query(
+    format: &str,
+    options: &HashMap<String, serde_json::Value>,
+) -> Result<Vec<(String, String)>, String> {
+    let allowed = allowed_options(format);
+    let mut query = Vec::with_capacity(options.len());
+
+    for (key, value) in options {
+        if !allowed.contains(&key.as_str()) {
+            return Err(if allowed.is_empty() {
+                format!("Format '{}' does not take any options (got '{}')", format, key)
+            } else {
+                format!(
+                    "Unknown option '{}' for format '{}'; expected one of: {}",
+                    key,
+                    format,
+                    allowed.join(", ")
+                )
+            });
+        }
+
+        let value = match value {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Number(number) => number.to_string(),
+            serde_json::Value::Bool(flag) => flag.to_string(),
+            _ => return Err(format!("Option '{}' must be a string, number or boolean", key)),
+        };
+        query.push((key.clone(), value));
+    }
+
+    validate_timestamp_options(options)?;
+
+    // Stable ordering keeps download URLs reproducible
+    query.sort();
+    Ok(query)
+}
+
+// Check the timestamp options make sense together
+fn validate_timestamp_options(options: &HashMap<String, serde_json::Value>) -> Result<(), String> {
+    let style = match options.get("timestamp_style") {
+        None => None,
+        Some(serde_json::Value::String(style)) if TIMESTAMP_STYLES.contains(&style.as_str()) => Some(style.as_str()),
+        Some(other) => {
+            return Err(format!(
+                "Invalid timestamp_style {}; expected one of: {}",
+                other,
+                TIMESTAMP_STYLES.join(", ")
+            ))
+        }
+    };
+
+    let frame_rate = match options.get("frame_rate") {
+        None => None,
+        Some(value) => match value.as_f64().or_else(|| value.as_str().and_then(|text| text.parse().ok())) {
+            Some(rate) if rate > 0.0 && rate <= MAX_FRAME_RATE => Some(rate),
+            _ => return Err(format!("frame_rate must be a number between 0 and {}, got {}", MAX_FRAME_RATE, value)),
+        },
+    };
+
+    match (style, frame_rate) {
+        (Some("frames"), None) => Err("timestamp_style 'frames' requires a frame_rate".to_string()),
+        (Some("frames"), Some(_)) | (_, None) => Ok(()),
+        (_, Some(_)) => Err("frame_rate only applies with timestamp_style 'frames'".to_string()),
+    }
+}
@@ -0,0 +1,172 @@
+// Job groups: recordings split across files, transcribed as one
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::unix_now;
+use crate::{api, persist, silence, AppError, AppState};
+
+pub const GROUPS_FILE: &str = "groups.json";
+
+// Formats that still read correctly when members are joined end to end
+const CLIENT_MERGE_FORMATS: &[&str] = &["txt", "md"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobGroup {
+    pub group_id: String,
+    pub name: String,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupDownload {
+    path: String,
+    // "backend" when the backend merged the group, "client" when members were joined here
+    merged_by: &'static str,
+    job_ids: Vec<String>,
+}
+
+fn next_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("g-{}-{}", unix_now(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+async fn persist_groups(state: &AppState) {
+    let mut groups: Vec<JobGroup> = state.groups.lock().await.values().cloned().collect();
+    groups.sort_by_key(|group| group.created_at);
+    if let Err(e) = persist::save_json(&state.data_dir.join(GROUPS_FILE), &groups).await {
+        eprintln!("Failed to save job groups: {}", e);
+    }
+}
+
+pub async fn ensure_exists(state: &AppState, group_id: &str) -> Result<(), AppError> {
+    if state.groups.lock().await.contains_key(group_id) {
+        Ok(())
+    } else {
+        Err(format!("Unknown job group {}", group_id).into())
+    }
+}
+
+// Member jobs in the order they were submitted
+async fn members(state: &AppState, group_id: &str) -> Vec<String> {
+    let records = state.job_records.lock().await;
+    let mut members: Vec<_> = records
+        .values()
+        .filter(|record| record.options.group_id.as_deref() == Some(group_id))
+        .map(|record| (record.submitted_at, record.job_id.clone()))
+        .collect();
+    members.sort();
+    members.into_iter().map(|(_, job_id)| job_id).collect()
+}
+
+// Ask the backend for the combined transcript; None when it can't merge groups
+async fn fetch_merged(state: &AppState, group_id: &str, format: &str) -> Result<Option<Vec<u8>>, AppError> {
+    let request = state.client().get(format!("{}/group/{}/download/{}", state.api_url(), group_id, format));
+    let response = api::send(state, request).await?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND
+        | reqwest::StatusCode::METHOD_NOT_ALLOWED
+        | reqwest::StatusCode::NOT_IMPLEMENTED => Ok(None),
+        status if !status.is_success() => Err(api::error_from_response(response).await),
+        _ => {
+            let bytes = response.bytes()
+                .await
+                .map_err(|e| format!("Failed to read response: {}", e))?;
+            Ok(Some(bytes.to_vec()))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn create_job_group(state: State<'_, AppState>, name: String) -> Result<String, AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Group name cannot be empty".to_string().into());
+    }
+
+    let group = JobGroup {
+        group_id: next_id(),
+        name,
+        created_at: unix_now(),
+    };
+    let group_id = group.group_id.clone();
+    state.groups.lock().await.insert(group_id.clone(), group);
+    persist_groups(&state).await;
+    Ok(group_id)
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupSummary {
+    #[serde(flatten)]
+    group: JobGroup,
+    job_ids: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn list_job_groups(state: State<'_, AppState>) -> Result<Vec<GroupSummary>, AppError> {
+    let mut groups: Vec<JobGroup> = state.groups.lock().await.values().cloned().collect();
+    groups.sort_by_key(|group| group.created_at);
+
+    let mut summaries = Vec::with_capacity(groups.len());
+    for group in groups {
+        let job_ids = members(&state, &group.group_id).await;
+        summaries.push(GroupSummary { group, job_ids });
+    }
+    Ok(summaries)
+}
+
+// Fetch the group's combined transcript. Backends that can't merge get the members'
+// results joined here in submission order, which only works for plain-text formats.
+#[tauri::command]
+pub async fn download_group_result(
+    state: State<'_, AppState>,
+    group_id: String,
+    format: String,
+    dest_path: String,
+) -> Result<GroupDownload, AppError> {
+    ensure_exists(&state, &group_id).await?;
+    let job_ids = members(&state, &group_id).await;
+    if job_ids.is_empty() {
+        return Err(format!("Job group {} has no jobs", group_id).into());
+    }
+
+    // The backend would merge trimmed members on their trimmed timelines
+    let merged = if silence::any_trimmed(&state, &job_ids).await {
+        None
+    } else {
+        fetch_merged(&state, &group_id, &format).await?
+    };
+    let (bytes, merged_by) = match merged {
+        Some(bytes) => (bytes, "backend"),
+        None => {
+            if !CLIENT_MERGE_FORMATS.contains(&format.as_str()) {
+                return Err(AppError::Unsupported {
+                    feature: format!("merged {} results for job groups", format),
+                });
+            }
+            let mut merged = Vec::new();
+            for job_id in &job_ids {
+                let bytes = crate::fetch_result(&state, job_id, &format, &[]).await?;
+                if !merged.is_empty() {
+                    merged.extend_from_slice(b"\n\n");
+                }
+                merged.extend_from_slice(bytes.trim_ascii_end());
+            }
+            merged.push(b'\n');
+            (merged, "client")
+        }
+    };
+
+    tokio::fs::write(&dest_path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(GroupDownload {
+        path: dest_path,
+        merged_by,
+        job_ids,
+    })
+}
@@ -0,0 +1,210 @@
+// Standalone HTML transcripts, rendered client-side from the JSON transcript
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::transcript::{self, Segment};
+use crate::{AppError, AppState};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HtmlTheme {
+    // Follow the reader's system preference
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HtmlOptions {
+    pub theme: HtmlTheme,
+    // Page heading; the job ID when unset
+    pub title: Option<String>,
+    // Mark words, or whole segments without word scores, below low_confidence
+    pub highlight_confidence: bool,
+    pub low_confidence: Option<f64>,
+    // Audio or video the timestamps seek when clicked; plain anchors otherwise
+    pub media_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HtmlExport {
+    path: String,
+    segments: usize,
+    // Distinct speakers labelled; zero when the transcript has no speaker data
+    speakers: usize,
+    // Segments or words highlighted as low confidence; None when there was no confidence data
+    low_confidence: Option<usize>,
+}
+
+const LIGHT_COLORS: &str = "--bg:#ffffff;--fg:#1d1d1f;--muted:#6e6e73;--accent:#0a66c2;--low:#fff3cd;";
+const DARK_COLORS: &str = "--bg:#1c1c1e;--fg:#f2f2f7;--muted:#98989d;--accent:#64a8ff;--low:#5c4a12;";
+
+const STYLE: &str = "\
+body{margin:0 auto;max-width:46rem;padding:2rem 1rem;background:var(--bg);color:var(--fg);\
+font:16px/1.6 -apple-system,BlinkMacSystemFont,'Segoe UI',Roboto,sans-serif}\
+h1{font-size:1.4rem;margin:0 0 1.5rem}\
+audio,video{width:100%;margin-bottom:1.5rem}\
+.segment{display:flex;gap:1rem;margin:0 0 .9rem}\
+.time{flex:none;min-width:4.5rem;color:var(--accent);font-variant-numeric:tabular-nums;text-decoration:none}\
+.time:hover{text-decoration:underline}\
+.speaker{display:block;font-weight:600;font-size:.85rem;color:var(--muted)}\
+.low{background:var(--low);border-radius:.2rem}\
+:target{outline:2px solid var(--accent);outline-offset:.3rem;border-radius:.2rem}";
+
+// Seeks the embedded player instead of only jumping to the anchor
+const SEEK_SCRIPT: &str = "\
+document.addEventListener('click',function(e){var t=e.target.closest('.time');\
+var m=document.getElementById('media');if(!t||!m)return;\
+m.currentTime=parseFloat(t.dataset.start);m.play();});";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+// H:MM:SS, or M:SS under an hour
+fn display_time(secs: f64) -> String {
+    let seconds = secs.max(0.0).floor() as u64;
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+fn theme_css(theme: HtmlTheme) -> String {
+    match theme {
+        HtmlTheme::Light => format!(":root{{{}}}", LIGHT_COLORS),
+        HtmlTheme::Dark => format!(":root{{{}}}", DARK_COLORS),
+        HtmlTheme::Auto => format!(
+            ":root{{{}}}@media(prefers-color-scheme:dark){{:root{{{}}}}}",
+            LIGHT_COLORS, DARK_COLORS
+        ),
+    }
+}
+
+// Segment text with low-confidence words wrapped, or the whole text when only the segment is scored.
+// Returns the number of highlighted spans, None without confidence data.
+fn segment_body(segment: &Segment, threshold: Option<f64>) -> (String, Option<usize>) {
+    let Some(threshold) = threshold else {
+        return (escape_html(segment.text.trim()), None);
+    };
+    if !segment.words.is_empty() && segment.words.iter().all(|word| word.probability.is_some()) {
+        let mut low = 0;
+        let words: Vec<String> = segment.words.iter().map(|word| {
+            let text = escape_html(word.word.trim());
+            if word.probability.is_some_and(|probability| probability < threshold) {
+                low += 1;
+                format!("<span class=\"low\">{}</span>", text)
+            } else {
+                text
+            }
+        }).collect();
+        return (words.join(" "), Some(low));
+    }
+    match segment.confidence() {
+        Some(confidence) if confidence < threshold => (
+            format!("<span class=\"low\">{}</span>", escape_html(segment.text.trim())),
+            Some(1),
+        ),
+        Some(_) => (escape_html(segment.text.trim()), Some(0)),
+        None => (escape_html(segment.text.trim()), None),
+    }
+}
+
+fn render(segments: &[Segment], title: &str, options: &HtmlOptions, labelled: bool) -> (String, Option<usize>) {
+    let threshold = options.highlight_confidence
+        .then(|| options.low_confidence.unwrap_or(transcript::DEFAULT_LOW_CONFIDENCE));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{theme}{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+        title = escape_html(title),
+        theme = theme_css(options.theme),
+        style = STYLE,
+    );
+    if let Some(url) = &options.media_url {
+        html.push_str(&format!("<audio id=\"media\" controls preload=\"metadata\" src=\"{}\"></audio>\n", escape_html(url)));
+    }
+
+    let mut low_total: Option<usize> = None;
+    let mut previous_speaker: Option<&str> = None;
+    for (index, segment) in segments.iter().enumerate() {
+        let (body, low) = segment_body(segment, threshold);
+        if let Some(low) = low {
+            *low_total.get_or_insert(0) += low;
+        }
+        // Label only where the speaker changes, as a reader would expect
+        let speaker = segment.speaker.as_deref().filter(|_| labelled);
+        let label = match speaker {
+            Some(name) if previous_speaker != Some(name) => {
+                format!("<span class=\"speaker\">{}</span>", escape_html(name))
+            }
+            _ => String::new(),
+        };
+        previous_speaker = speaker;
+        html.push_str(&format!(
+            "<p class=\"segment\" id=\"t{index}\"><a class=\"time\" href=\"#t{index}\" data-start=\"{start:.3}\">{time}</a>\
+             <span class=\"text\">{label}{body}</span></p>\n",
+            index = index,
+            start = segment.start,
+            time = display_time(segment.start),
+            label = label,
+            body = body,
+        ));
+    }
+
+    if options.media_url.is_some() {
+        html.push_str(&format!("<script>{}</script>\n", SEEK_SCRIPT));
+    }
+    html.push_str("</body>\n</html>\n");
+    (html, low_total)
+}
+
+#[tauri::command]
+pub async fn export_html(
+    state: State<'_, AppState>,
+    job_id: String,
+    dest_path: String,
+    options: Option<HtmlOptions>,
+) -> Result<HtmlExport, AppError> {
+    let options = options.unwrap_or_default();
+    if let Some(threshold) = options.low_confidence {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(format!("low_confidence must be between 0 and 1, got {}", threshold).into());
+        }
+    }
+
+    let (transcript, _) = transcript::load(&state, &job_id).await?;
+    let segments: Vec<Segment> = transcript.segments
+        .into_iter()
+        .filter(|segment| segment.start.is_finite() && !segment.text.trim().is_empty())
+        .collect();
+    if segments.is_empty() {
+        return Err(format!("The result for job {} has no timestamped segments", job_id).into());
+    }
+
+    // Without speaker data the transcript is rendered unlabelled rather than as one "unknown" voice
+    let mut speakers: Vec<&str> = segments.iter().filter_map(|segment| segment.speaker.as_deref()).collect();
+    speakers.sort_unstable();
+    speakers.dedup();
+    let speakers = speakers.len();
+
+    let title = options.title.clone().unwrap_or_else(|| format!("Transcript {}", job_id));
+    let (contents, low_confidence) = render(&segments, &title, &options, speakers > 0);
+    tokio::fs::write(&dest_path, contents)
+        .await
+        .map_err(|e| crate::error::io_error("Failed to write file", std::path::Path::new(&dest_path), e))?;
+    Ok(HtmlExport {
+        path: dest_path,
+        segments: segments.len(),
+        speakers,
+        low_confidence,
+    })
+}
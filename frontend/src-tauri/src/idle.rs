@@ -0,0 +1,162 @@
+// Forgetting finished jobs nobody has come back to
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::records::{unix_now, JobRecord};
+use crate::tasks::{self, TaskKind};
+use crate::{audit, result_cache, AppError, AppState};
+
+// How often finished jobs are checked for idleness
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// Shortest idle period allowed, so a slip can't forget jobs as they finish
+pub const MIN_IDLE_SECS: u64 = 60 * 60;
+const DEFAULT_GRACE_SECS: u64 = 24 * 60 * 60;
+
+// Opt-in: forget jobs idle in a terminal state for after_secs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdleExpiry {
+    pub after_secs: u64,
+    // Warning period between the job-auto-expired event and forgetting the job
+    #[serde(default = "default_grace_secs")]
+    pub grace_secs: u64,
+    // Also delete the job and its result on the backend
+    #[serde(default)]
+    pub delete_on_backend: bool,
+}
+
+fn default_grace_secs() -> u64 {
+    DEFAULT_GRACE_SECS
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ForgottenEntry<'a> {
+    job_id: &'a str,
+    idle_secs: u64,
+    deleted_on_backend: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AutoExpiredEvent {
+    job_id: String,
+    idle_secs: u64,
+    // When the job will be forgotten unless it is downloaded or pinned first
+    forget_at: u64,
+    delete_on_backend: bool,
+}
+
+// Last time the user did something with the job
+fn last_touched(record: &JobRecord) -> u64 {
+    [Some(record.submitted_at), record.completed_at, record.last_accessed_at]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or_default()
+}
+
+// Finished, unpinned jobs untouched for at least after_secs, with how long they've been idle
+async fn idle_jobs(state: &AppState, after_secs: u64) -> Vec<(String, u64)> {
+    let now = unix_now();
+    state.job_records.lock().await
+        .values()
+        .filter(|record| record.is_finished() && !record.pinned)
+        .filter_map(|record| {
+            let idle_secs = now.saturating_sub(last_touched(record));
+            (idle_secs >= after_secs).then(|| (record.job_id.clone(), idle_secs))
+        })
+        .collect()
+}
+
+// Drop the job's record and cached result, deleting it on the backend if asked
+async fn forget(state: &AppState, job_id: &str, delete_on_backend: bool) -> Result<(), String> {
+    if delete_on_backend {
+        let status = crate::send_cancel(state, job_id).await?;
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            return Err(format!("Failed to delete job on the backend: {}", status));
+        }
+    }
+    state.processing_jobs.lock().await.remove(job_id);
+    state.job_records.lock().await.remove(job_id);
+    state.status_cache.lock().await.invalidate(job_id);
+    result_cache::invalidate(state, job_id).await;
+    crate::persist_records(state).await;
+    Ok(())
+}
+
+// Warn about idle jobs, then forget the ones still idle once their grace period is over
+pub fn sweep_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::IdleSweeper, None, async move {
+        // When each job was warned about; a restart warns again rather than acting early
+        let mut warned: HashMap<String, u64> = HashMap::new();
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            let Some(config) = state.settings.lock().await.idle_expiry.clone() else {
+                warned.clear();
+                continue;
+            };
+
+            let idle = idle_jobs(&state, config.after_secs).await;
+            // Downloading or pinning a job during its grace period keeps it
+            warned.retain(|job_id, _| idle.iter().any(|(idle_id, _)| idle_id == job_id));
+            let now = unix_now();
+            for (job_id, idle_secs) in idle {
+                match warned.get(&job_id) {
+                    None => {
+                        warned.insert(job_id.clone(), now);
+                        let event = AutoExpiredEvent {
+                            job_id,
+                            idle_secs,
+                            forget_at: now + config.grace_secs,
+                            delete_on_backend: config.delete_on_backend,
+                        };
+                        let _ = app.emit_all("job-auto-expired", event);
+                    }
+                    Some(warned_at) if now.saturating_sub(*warned_at) >= config.grace_secs => {
+                        match forget(&state, &job_id, config.delete_on_backend).await {
+                            Ok(()) => {
+                                warned.remove(&job_id);
+                                let entry = ForgottenEntry {
+                                    job_id: &job_id,
+                                    idle_secs,
+                                    deleted_on_backend: config.delete_on_backend,
+                                };
+                                audit::append(&state, "idle-job-forgotten", &entry).await;
+                            }
+                            Err(e) => eprintln!("Failed to forget idle job {}: {}", job_id, e),
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    });
+}
+
+// Turn idle expiry on with the given period, or off with None
+#[tauri::command]
+pub async fn set_idle_expiry(
+    state: State<'_, AppState>,
+    config: Option<IdleExpiry>,
+) -> Result<(), AppError> {
+    if let Some(config) = &config {
+        if config.after_secs < MIN_IDLE_SECS {
+            return Err(format!("Idle expiry must be at least {}s, got {}s", MIN_IDLE_SECS, config.after_secs).into());
+        }
+        if config.grace_secs < SWEEP_INTERVAL.as_secs() {
+            return Err(format!(
+                "The grace period must be at least {}s so the warning can go out first",
+                SWEEP_INTERVAL.as_secs()
+            ).into());
+        }
+    }
+    state.settings.lock().await.idle_expiry = config;
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
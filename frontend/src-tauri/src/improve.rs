@@ -0,0 +1,92 @@
+// Reprocessing low-confidence results with a fallback model
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{transcript, AppError, AppState};
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImprovePath {
+    // Confidence was good enough; the original job stands
+    Kept,
+    // Resubmitted with the fallback model
+    Escalated,
+    // Escalated before, by this job or one it came from; no further attempts are made
+    AlreadyEscalated,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImproveOutcome {
+    // The job whose result to use
+    job_id: String,
+    path: ImprovePath,
+    average_confidence: f64,
+}
+
+#[tauri::command]
+pub async fn auto_improve(
+    state: State<'_, AppState>,
+    job_id: String,
+    confidence_threshold: Option<f64>,
+    fallback_model: Option<String>,
+) -> Result<ImproveOutcome, AppError> {
+    let (threshold, fallback_model) = {
+        let settings = state.settings.lock().await;
+        (
+            confidence_threshold.unwrap_or(settings.auto_improve_threshold),
+            fallback_model.or_else(|| settings.fallback_model.clone()),
+        )
+    };
+
+    let record = state.job_records.lock().await
+        .get(&job_id)
+        .cloned()
+        .ok_or_else(|| AppError::JobNotFound { job_id: job_id.clone() })?;
+    if record.status != "complete" {
+        return Err(format!("Job {} is {}, not complete", job_id, record.status).into());
+    }
+
+    let (result, _) = transcript::load(&state, &job_id).await?;
+    let average_confidence = result
+        .average_confidence()
+        .ok_or_else(|| format!("The result for job {} has no confidence data", job_id))?;
+
+    // One automatic escalation per source, whichever job in the chain is asked
+    let escalated = state.job_records.lock().await
+        .values()
+        .find(|other| other.escalated_from.as_deref() == Some(job_id.as_str()))
+        .map(|other| other.job_id.clone());
+    if let Some(escalated) = escalated {
+        return Ok(ImproveOutcome { job_id: escalated, path: ImprovePath::AlreadyEscalated, average_confidence });
+    }
+    if record.escalated_from.is_some() {
+        return Ok(ImproveOutcome { job_id, path: ImprovePath::AlreadyEscalated, average_confidence });
+    }
+    if average_confidence >= threshold {
+        return Ok(ImproveOutcome { job_id, path: ImprovePath::Kept, average_confidence });
+    }
+
+    let fallback_model = fallback_model
+        .ok_or_else(|| "No fallback model is configured for auto_improve".to_string())?;
+    if record.options.model.as_deref() == Some(fallback_model.as_str()) {
+        return Err(format!("Job {} already used the fallback model {}", job_id, fallback_model).into());
+    }
+    let mut options = record.options.clone();
+    options.model = Some(fallback_model);
+    options.validate()?;
+
+    let api_response = crate::submit_source(&state, &record.source, &options).await?;
+    crate::track_job(&state, &api_response.job_id, record.source, options).await;
+    if let Some(new_record) = state.job_records.lock().await.get_mut(&api_response.job_id) {
+        new_record.escalated_from = Some(job_id);
+        new_record.pinned = record.pinned;
+    }
+    crate::persist_records(&state).await;
+
+    Ok(ImproveOutcome {
+        job_id: api_response.job_id,
+        path: ImprovePath::Escalated,
+        average_confidence,
+    })
+}
@@ -0,0 +1,193 @@
+// Checking downloaded results against the size and checksum the backend reports
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::State;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::io_error;
+use crate::media::TempFile;
+use crate::{formats, silence, transfers, AppError, AppState};
+
+// Header carrying the hex SHA-256 of the result body
+const CHECKSUM_HEADER: &str = "x-content-sha256";
+
+// Attempts before giving up on a result that keeps failing verification
+const MAX_ATTEMPTS: u32 = 2;
+
+#[derive(Debug, Serialize)]
+pub struct VerifiedDownload {
+    // Set when the file was written
+    pub path: Option<String>,
+    pub verified: bool,
+    pub attempts: u32,
+    // Why the last attempt failed verification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<String>,
+    // The backend sent a zip of several outputs rather than a single file
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub archive: bool,
+}
+
+// Whether a Content-Type names a zip archive
+pub fn is_zip_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    matches!(mime.as_str(), "application/zip" | "application/x-zip-compressed")
+}
+
+// Sibling path a download is written to before it is moved into place
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+// Stream a result into a .part file next to path and check it, returning the
+// file and whether it is a zip, or why it failed verification. The file is
+// removed if this is dropped midway.
+async fn fetch_checked(
+    state: &AppState,
+    job_id: &str,
+    format: &str,
+    query: &[(String, String)],
+    path: &Path,
+) -> Result<Result<(TempFile, bool), String>, AppError> {
+    // Rendered here from a restored transcript, so there's no backend copy to check against
+    if let Some(bytes) = silence::restored_result(state, job_id, format, query).await? {
+        let part = TempFile::at(part_path(path));
+        tokio::fs::write(part.path(), &bytes)
+            .await
+            .map_err(|e| io_error("Failed to write file", path, e))?;
+        return Ok(Ok((part, false)));
+    }
+
+    let _permit = state.transfers.download().await;
+    let response = crate::open_result(state, job_id, format, query).await?;
+    let archive = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(is_zip_content_type);
+    let expected_len = response.content_length();
+    let expected_digest = response.headers()
+        .get(CHECKSUM_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_ascii_lowercase());
+
+    let mut response = transfers::Download::new(response);
+    let part = TempFile::at(part_path(path));
+    let mut file = tokio::fs::File::create(part.path())
+        .await
+        .map_err(|e| io_error("Failed to write file", path, e))?;
+    let mut hasher = Sha256::new();
+    let mut received: u64 = 0;
+    while let Some((chunk, _buffer)) = state.transfers.next_chunk(&mut response)
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        hasher.update(&chunk);
+        received += chunk.len() as u64;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| io_error("Failed to write file", path, e))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| io_error("Failed to write file", path, e))?;
+    drop(file);
+
+    // Results without a reported size or checksum are checked as far as possible
+    if let Some(expected_len) = expected_len {
+        if received != expected_len {
+            return Ok(Err(format!("Expected {} bytes but received {}", expected_len, received)));
+        }
+    }
+    if let Some(expected_digest) = expected_digest {
+        let digest = hex::encode(hasher.finalize());
+        if digest != expected_digest {
+            return Ok(Err(format!("Checksum mismatch: expected {}, got {}", expected_digest, digest)));
+        }
+    }
+
+    // Catch short writes, e.g. from a full disk
+    let written = tokio::fs::metadata(part.path())
+        .await
+        .map_err(|e| format!("Failed to read back file: {}", e))?
+        .len();
+    if written != received {
+        return Ok(Err(format!("Wrote {} of {} bytes", written, received)));
+    }
+
+    Ok(Ok((part, archive)))
+}
+
+// Download a result to path, fetching once more if the first copy fails verification
+pub async fn download_verified(
+    state: &AppState,
+    job_id: &str,
+    format: &str,
+    query: &[(String, String)],
+    path: &Path,
+) -> Result<VerifiedDownload, AppError> {
+    let mut problem = None;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let (part, archive) = match fetch_checked(state, job_id, format, query, path).await? {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                eprintln!("Result for job {} failed verification (attempt {}): {}", job_id, attempt, e);
+                problem = Some(e);
+                continue;
+            }
+        };
+
+        // A rename is atomic, so the destination only ever holds a complete file
+        tokio::fs::rename(part.path(), path)
+            .await
+            .map_err(|e| io_error("Failed to write file", path, e))?;
+
+        return Ok(VerifiedDownload {
+            path: Some(path.to_string_lossy().to_string()),
+            verified: true,
+            attempts: attempt,
+            problem: None,
+            archive,
+        });
+    }
+
+    Ok(VerifiedDownload {
+        path: None,
+        verified: false,
+        attempts: MAX_ATTEMPTS,
+        problem,
+        archive: false,
+    })
+}
+
+// Replace a local result with a fresh copy from the backend
+#[tauri::command]
+pub async fn redownload_result(
+    state: State<'_, AppState>,
+    job_id: String,
+    format: String,
+    dest: String,
+    force: bool,
+    format_options: Option<std::collections::HashMap<String, serde_json::Value>>,
+) -> Result<VerifiedDownload, AppError> {
+    let query = formats::format_query(&format, &format_options.unwrap_or_default())?;
+    let path = Path::new(&dest);
+    if path.exists() {
+        if !force {
+            return Err(format!("{} already exists; pass force to replace it", dest).into());
+        }
+        tokio::fs::remove_file(path)
+            .await
+            .map_err(|e| format!("Failed to remove {}: {}", dest, e))?;
+    }
+
+    let download = download_verified(&state, &job_id, &format, &query, path).await?;
+    if download.verified {
+        crate::record_download(&state, &job_id, &dest).await;
+    }
+    Ok(download)
+}
@@ -0,0 +1,132 @@
+// Cheap language detection from a short sample, before committing to a full transcription
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{capabilities, diagnostics, media, AppError, AppState, JobSource, ProcessOptions};
+
+// Length of the sample uploaded from local files
+const DETECT_SAMPLE_SECS: f64 = 30.0;
+
+const DETECT_TIMEOUT: Duration = Duration::from_secs(120);
+
+// The top candidate is ambiguous below this confidence, or this close to the runner-up
+const CONFIDENT_PROBABILITY: f64 = 0.5;
+const CLEAR_MARGIN: f64 = 0.2;
+
+// Capability flag for backends that can skip transcription and only identify the language
+const DETECT_ONLY_FEATURE: &str = "detect_only";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageCandidate {
+    language: String,
+    // None when the backend only reported its best guess
+    confidence: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LanguageDetection {
+    // Most likely first
+    candidates: Vec<LanguageCandidate>,
+    ambiguous: bool,
+    // How much of a local file was sampled; None for URLs
+    sample_secs: Option<f64>,
+}
+
+// Candidates from a JSON result: "language_probabilities" as a map or list when
+// present, else "language" with an optional "language_probability"
+fn candidates_from(result: &serde_json::Value) -> Vec<LanguageCandidate> {
+    let mut candidates: Vec<LanguageCandidate> = match result.get("language_probabilities") {
+        Some(serde_json::Value::Object(map)) => map
+            .iter()
+            .filter_map(|(language, probability)| {
+                Some(LanguageCandidate { language: language.clone(), confidence: Some(probability.as_f64()?) })
+            })
+            .collect(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| {
+                Some(LanguageCandidate {
+                    language: item.get("language")?.as_str()?.to_string(),
+                    confidence: item.get("probability").and_then(|p| p.as_f64()),
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if candidates.is_empty() {
+        if let Some(language) = result.get("language").and_then(|language| language.as_str()) {
+            candidates.push(LanguageCandidate {
+                language: language.to_string(),
+                confidence: result.get("language_probability").and_then(|p| p.as_f64()),
+            });
+        }
+    }
+
+    candidates.sort_by(|a, b| b.confidence.unwrap_or(0.0).total_cmp(&a.confidence.unwrap_or(0.0)));
+    candidates
+}
+
+fn is_ambiguous(candidates: &[LanguageCandidate]) -> bool {
+    let top = candidates.first().and_then(|candidate| candidate.confidence);
+    let runner_up = candidates.get(1).and_then(|candidate| candidate.confidence);
+    match (top, runner_up) {
+        (None, _) => candidates.len() != 1,
+        (Some(top), _) if top < CONFIDENT_PROBABILITY => true,
+        (Some(top), Some(runner_up)) => top - runner_up < CLEAR_MARGIN,
+        (Some(_), None) => false,
+    }
+}
+
+// Detect the spoken language of a local file or URL. The probe job is never tracked
+// and is removed from the backend afterwards.
+#[tauri::command]
+pub async fn detect_language(state: State<'_, AppState>, path_or_url: String) -> Result<LanguageDetection, AppError> {
+    let detect_only = capabilities::fetch(&state, false)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|capabilities| capabilities.features.contains_key(DETECT_ONLY_FEATURE));
+    let options = ProcessOptions {
+        detect_only: detect_only.then_some(true),
+        ..ProcessOptions::default()
+    };
+
+    let is_url = path_or_url.starts_with("http://") || path_or_url.starts_with("https://");
+    let (job_id, sample_secs) = if is_url {
+        let source = JobSource::Url { url: path_or_url };
+        (crate::submit_source(&state, &source, &options).await?.job_id, None)
+    } else {
+        let path = Path::new(&path_or_url);
+        let sample_secs = media::probe_duration(path).await?.min(DETECT_SAMPLE_SECS);
+        let (api_response, extracted) = crate::send_segment(&state, path, 0.0, sample_secs, &options).await?;
+        (api_response.job_id, Some(extracted))
+    };
+
+    let detected = async {
+        diagnostics::wait_for_completion(&state, &job_id, DETECT_TIMEOUT).await?;
+        let bytes = crate::fetch_result(&state, &job_id, "json", &[]).await?;
+        serde_json::from_slice::<serde_json::Value>(&bytes)
+            .map_err(|e| AppError::from(format!("Failed to parse result: {}", e)))
+    }
+    .await;
+
+    if let Err(e) = crate::send_cancel(&state, &job_id).await {
+        eprintln!("Failed to remove language detection job {}: {}", job_id, e);
+    }
+    state.status_cache.lock().await.invalidate(&job_id);
+
+    let candidates = candidates_from(&detected?);
+    if candidates.is_empty() {
+        return Err("The backend did not report a language".to_string().into());
+    }
+    Ok(LanguageDetection {
+        ambiguous: is_ambiguous(&candidates),
+        candidates,
+        sample_secs,
+    })
+}
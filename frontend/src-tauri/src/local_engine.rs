@@ -0,0 +1,180 @@
+// Offline transcription with whisper.cpp linked in, for small jobs when the backend is unreachable.
+// Only compiled into builds with the local-engine feature; local jobs never touch the job records.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Invoke, Runtime, State};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperError};
+
+use crate::media::{self, TempFile};
+use crate::records::unix_now;
+use crate::transcript::{Segment, Transcript};
+use crate::{persist, AppError, AppState, ProcessOptions};
+
+pub const LOCAL_JOBS_FILE: &str = "local_jobs.json";
+const LOCAL_RESULTS_DIR: &str = "local";
+
+// Local jobs are prefixed so their IDs can never collide with the backend's
+const LOCAL_ID_PREFIX: &str = "local-";
+
+// The bundled engine is slow on CPU, so it only takes short recordings
+const MAX_LOCAL_DURATION_SECS: f64 = 15.0 * 60.0;
+
+// Model shipped next to the app
+const DEFAULT_MODEL_FILE: &str = "ggml-base.bin";
+
+// Commands this module adds to the handler
+const COMMANDS: &[&str] = &["process_locally", "list_local_jobs"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalJob {
+    job_id: String,
+    path: String,
+    options: ProcessOptions,
+    created_at: u64,
+    duration_secs: f64,
+    // Transcript in the backend's JSON shape
+    result_path: String,
+    language: Option<String>,
+}
+
+pub type LocalJobs = BTreeMap<String, LocalJob>;
+
+fn next_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("{}{}-{}", LOCAL_ID_PREFIX, unix_now(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+fn model_path() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("models").join(DEFAULT_MODEL_FILE)))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_MODEL_FILE))
+}
+
+// Run the model over raw 16 kHz mono 16-bit audio, off the async runtime
+async fn transcribe(audio: &Path, options: &ProcessOptions) -> Result<Transcript, String> {
+    let pcm = tokio::fs::read(audio)
+        .await
+        .map_err(|e| format!("Failed to read decoded audio: {}", e))?;
+    let options = options.clone();
+    tauri::async_runtime::spawn_blocking(move || run_model(&pcm, &options).map_err(|e| format!("Local transcription failed: {}", e)))
+        .await
+        .map_err(|e| format!("Local transcription failed: {}", e))?
+}
+
+fn run_model(pcm: &[u8], options: &ProcessOptions) -> Result<Transcript, WhisperError> {
+    let samples: Vec<f32> = pcm
+        .chunks_exact(2)
+        .map(|sample| i16::from_le_bytes([sample[0], sample[1]]) as f32 / 32768.0)
+        .collect();
+
+    let context = WhisperContext::new_with_params(model_path(), WhisperContextParameters::default())?;
+    let mut state = context.create_state()?;
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some(options.language.as_deref().unwrap_or("auto")));
+    if let Some(prompt) = &options.initial_prompt {
+        params.set_initial_prompt(prompt);
+    }
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    state.full(params, &samples)?;
+
+    // Segment times come back in centiseconds
+    let mut segments = Vec::new();
+    for segment in state.as_iter() {
+        let text = segment.to_str_lossy()?.trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+        segments.push(Segment {
+            start: segment.start_timestamp() as f64 / 100.0,
+            end: segment.end_timestamp() as f64 / 100.0,
+            text,
+            ..Default::default()
+        });
+    }
+    let language = whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(String::from);
+    Ok(Transcript {
+        text: segments.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join(" "),
+        segments,
+        language: language.or_else(|| options.language.clone()),
+    })
+}
+
+async fn save_jobs(state: &AppState) {
+    let jobs = state.local_jobs.lock().await.clone();
+    if let Err(e) = persist::save_json(&state.data_dir.join(LOCAL_JOBS_FILE), &jobs).await {
+        eprintln!("Failed to save local jobs: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn process_locally(
+    state: State<'_, AppState>,
+    path: String,
+    options: Option<ProcessOptions>,
+) -> Result<LocalJob, AppError> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+
+    let source = Path::new(&path);
+    let duration_secs = media::probe_duration(source).await?;
+    if duration_secs > MAX_LOCAL_DURATION_SECS {
+        return Err(format!(
+            "Local transcription is limited to {} minutes; this file is {:.0}",
+            MAX_LOCAL_DURATION_SECS / 60.0,
+            duration_secs / 60.0
+        ).into());
+    }
+
+    let audio = TempFile::new("local-audio", "pcm");
+    let codec_args: Vec<String> = ["-ar", "16000", "-ac", "1", "-f", "s16le"].map(String::from).to_vec();
+    media::transcode_audio(source, &codec_args, audio.path()).await?;
+    let transcript = transcribe(audio.path(), &options).await?;
+
+    let job_id = next_id();
+    let result_path = state.data_dir.join(LOCAL_RESULTS_DIR).join(format!("{}.json", job_id));
+    persist::save_json(&result_path, &transcript).await?;
+
+    let job = LocalJob {
+        job_id: job_id.clone(),
+        path,
+        options,
+        created_at: unix_now(),
+        duration_secs,
+        result_path: result_path.to_string_lossy().to_string(),
+        language: transcript.language,
+    };
+    state.local_jobs.lock().await.insert(job_id, job.clone());
+    save_jobs(&state).await;
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn list_local_jobs(state: State<'_, AppState>) -> Result<Vec<LocalJob>, AppError> {
+    Ok(state.local_jobs.lock().await.values().cloned().collect())
+}
+
+// Put this module's commands ahead of handler; generate_handler! entries can't be feature-gated
+pub fn with_commands<R: Runtime>(handler: impl Fn(Invoke<R>) + Send + Sync + 'static) -> impl Fn(Invoke<R>) + Send + Sync + 'static {
+    dispatch(tauri::generate_handler![process_locally, list_local_jobs], handler)
+}
+
+fn dispatch<R: Runtime>(
+    local: impl Fn(Invoke<R>) + Send + Sync + 'static,
+    handler: impl Fn(Invoke<R>) + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) + Send + Sync + 'static {
+    move |invoke: Invoke<R>| {
+        if COMMANDS.contains(&invoke.message.command()) {
+            local(invoke)
+        } else {
+            handler(invoke)
+        }
+    }
+}
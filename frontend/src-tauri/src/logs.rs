@@ -0,0 +1,117 @@
+// One consolidated log feed for every job still processing
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskId, TaskKind};
+use crate::{clock, AppError, AppState};
+
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Oldest lines are dropped past this
+const MAX_LOG_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    job_id: String,
+    // Unix seconds on the backend's clock
+    timestamp: u64,
+    line: String,
+}
+
+#[derive(Default)]
+pub struct LogFeed {
+    lines: VecDeque<LogLine>,
+    task_id: Option<TaskId>,
+}
+
+impl LogFeed {
+    fn push(&mut self, line: LogLine) {
+        if self.lines.len() >= MAX_LOG_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+}
+
+// Poll every processing job and emit "batch-log" for each new status message.
+// The backend has no separate log stream, so a job's status messages are its log.
+async fn tail(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    // Last message seen per job, so repeats aren't logged twice
+    let mut last_messages: HashMap<String, Option<String>> = HashMap::new();
+    let mut interval = tokio::time::interval(LOG_POLL_INTERVAL);
+
+    loop {
+        interval.tick().await;
+        let processing = state.processing_jobs.lock().await.clone();
+        let active: Vec<String> = {
+            let records = state.job_records.lock().await;
+            processing.into_iter()
+                .filter(|job_id| records.get(job_id).is_some_and(|record| !record.is_finished()))
+                .collect()
+        };
+        if active.is_empty() {
+            break;
+        }
+        // Jobs that stopped processing drop out of the feed
+        last_messages.retain(|job_id, _| active.contains(job_id));
+
+        let statuses = futures_util::future::join_all(active.iter().map(|job_id| async {
+            let status = crate::fetch_status(&state, job_id).await;
+            (job_id.clone(), status, clock::server_now(&state).await)
+        }))
+        .await;
+
+        let mut lines = Vec::new();
+        for (job_id, status, timestamp) in statuses {
+            let Ok(status) = status else {
+                continue;
+            };
+            let previous = last_messages.insert(job_id.clone(), status.message.clone());
+            if let Some(message) = status.message.filter(|message| previous.flatten().as_ref() != Some(message)) {
+                lines.push(LogLine { job_id: job_id.clone(), timestamp, line: message });
+            }
+            if matches!(status.status.as_str(), "complete" | "error" | "cancelled") {
+                lines.push(LogLine {
+                    job_id,
+                    timestamp,
+                    line: format!("Finished: {}", status.status),
+                });
+            }
+        }
+
+        // Interleave by when each line was observed
+        lines.sort_by_key(|line| line.timestamp);
+        let mut feed = state.log_feed.lock().await;
+        for line in lines {
+            let _ = app.emit_all("batch-log", line.clone());
+            feed.push(line);
+        }
+    }
+}
+
+// Start the consolidated feed, or return the one already running. It stops once no jobs are processing.
+#[tauri::command]
+pub async fn tail_all_logs(app: AppHandle, state: State<'_, AppState>) -> Result<TaskId, AppError> {
+    let mut feed = state.log_feed.lock().await;
+    if let Some(id) = feed.task_id.filter(|id| tasks::is_running(&state.tasks, *id)) {
+        return Ok(id);
+    }
+
+    let id = tasks::spawn(&state.tasks, TaskKind::LogTail, None, {
+        let app = app.clone();
+        async move { tail(&app).await }
+    });
+    feed.task_id = Some(id);
+    Ok(id)
+}
+
+// Lines collected so far, oldest first
+#[tauri::command]
+pub async fn get_batch_log(state: State<'_, AppState>) -> Result<Vec<LogLine>, AppError> {
+    Ok(state.log_feed.lock().await.lines.iter().cloned().collect())
+}
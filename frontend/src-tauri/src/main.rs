@@ -3,222 +3,2063 @@
     windows_subsystem = "windows"
 )]
 
-use std::fs::File;
-use std::path::PathBuf;
-use tauri::State;
+mod api;
+mod archive;
+mod audit;
+mod auth;
+mod backend_status;
+mod backup;
+mod batch;
+mod cache;
+mod callbacks;
+mod capabilities;
+mod clipboard;
+mod clock;
+mod concurrency;
+mod diagnostics;
+mod editors;
+mod edits;
+mod encoding;
+mod error;
+mod expiry;
+mod fetch;
+mod formats;
+mod groups;
+mod html;
+mod idle;
+mod improve;
+mod integrity;
+mod language;
+#[cfg(feature = "local-engine")]
+mod local_engine;
+mod logs;
+mod manifest;
+mod media;
+mod merge;
+mod network;
+mod operations;
+mod persist;
+mod pins;
+mod postprocess;
+mod power;
+mod polling;
+mod presets;
+mod profiles;
+mod probe;
+mod progress_events;
+mod queue;
+mod reconcile;
+mod repair;
+mod records;
+mod result_cache;
+mod result_server;
+mod result_versions;
+mod retry;
+mod revisions;
+mod search;
+mod session;
+mod settings;
+mod share;
+mod silence;
+mod shutdown;
+mod speed;
+mod storage;
+mod streaming;
+mod submission_sessions;
+mod subtitles;
+mod tasks;
+mod thumbnails;
+mod timeouts;
+mod timing;
+mod transcode;
+mod transcript;
+mod transfers;
+mod translation;
+mod viewer;
+mod words;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::{Manager, State};
 use tokio::sync::Mutex;
 use std::sync::Arc;
+use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
-
-// API URL
-const API_URL: &str = "http://localhost:8000";
+use cache::StatusCache;
+use error::{AppError, FieldError};
+use records::{JobRecord, JobSource};
+use settings::Settings;
 
 // Application state
 struct AppState {
-    api_client: reqwest::Client,
-    processing_jobs: Arc<Mutex<Vec<String>>>,
+    // Client and backend URL, replaced together when network settings or the profile change
+    connection: std::sync::RwLock<network::Connection>,
+    // Unfinished jobs, each listed once however many paths submitted it
+    processing_jobs: Arc<Mutex<HashSet<String>>>,
+    job_records: Arc<Mutex<HashMap<String, JobRecord>>>,
+    status_cache: Arc<Mutex<StatusCache>>,
+    cache_entries: result_cache::EntryLocks,
+    settings: Arc<Mutex<Settings>>,
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    // Seconds to add to local time to get the backend's time
+    clock_offset_secs: Arc<Mutex<i64>>,
+    // Background status pollers keyed by job ID
+    subscriptions: Arc<Mutex<HashMap<String, tasks::TaskId>>>,
+    // Every long-running background task, for inspection
+    tasks: tasks::SharedRegistry,
+    // Last capabilities the backend advertised
+    capabilities: Arc<Mutex<Option<capabilities::Capabilities>>>,
+    // Local listener for backend completion callbacks, when enabled
+    callbacks: Arc<Mutex<Option<callbacks::CallbackListener>>>,
+    // Held while refreshing credentials so concurrent 401s refresh once
+    auth_refresh: Arc<Mutex<()>>,
+    // Set during setup, for emitting events from request helpers
+    app_handle: std::sync::OnceLock<tauri::AppHandle>,
+    // Sources waiting to be submitted, in submission order
+    queue: Arc<Mutex<Vec<queue::QueueItem>>>,
+    // How many queued jobs may run at once, adapted to how the backend copes
+    queue_concurrency: Arc<Mutex<concurrency::AdaptiveLimit>>,
+    // Job groups keyed by group ID; membership lives in the job records
+    groups: Arc<Mutex<HashMap<String, groups::JobGroup>>>,
+    // Consolidated log lines from tail_all_logs
+    log_feed: Arc<Mutex<logs::LogFeed>>,
+    // Cancellable operations keyed by the UI's op_id
+    operations: Arc<Mutex<HashMap<String, tasks::TaskId>>>,
+    // Concurrency limits for uploads and downloads
+    transfers: transfers::TransferLimiter,
+    // Submissions are refused; see viewer::DISABLED_COMMANDS
+    viewer_mode: bool,
+    // Per-request timings, recorded only while diagnostics are on
+    timings: timing::TimingLog,
+    // Saved settings snapshots, switched between with switch_profile
+    profiles: Arc<Mutex<profiles::Profiles>>,
+    // Localhost server for result previews, started on first use
+    result_server: Arc<Mutex<Option<result_server::ResultServer>>>,
+    // Jobs run by the bundled engine, kept apart from backend jobs
+    #[cfg(feature = "local-engine")]
+    local_jobs: Arc<Mutex<local_engine::LocalJobs>>,
+    power: power::PowerMonitor,
+    backend_status: Arc<Mutex<backend_status::CachedStatus>>,
+    progress_events: progress_events::ProgressEvents,
+    sessions: Arc<Mutex<submission_sessions::Sessions>>,
+    shutting_down: std::sync::atomic::AtomicBool,
+}
+
+impl AppState {
+    fn client(&self) -> reqwest::Client {
+        self.connection.read().unwrap_or_else(std::sync::PoisonError::into_inner).client.clone()
+    }
+    
+    // Backend base URL, without a trailing slash
+    fn api_url(&self) -> String {
+        self.connection.read().unwrap_or_else(std::sync::PoisonError::into_inner).api_url.clone()
+    }
+    
+    fn set_connection(&self, connection: network::Connection) {
+        *self.connection.write().unwrap_or_else(std::sync::PoisonError::into_inner) = connection;
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ApiResponse {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JobStatusResponse {
     job_id: String,
     status: String,
     progress: f32,
     message: Option<String>,
     result_url: Option<String>,
+    // Problems the backend noticed in a job that still completed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    // When the backend will delete the result, as Unix seconds on its clock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    result_expires_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct JobWarningEvent {
+    job_id: String,
+    warnings: Vec<String>,
+}
+
+// Processing options forwarded to the backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ProcessOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    // Diarization hints; an exact count wins over the range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_speakers: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_speakers: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_speakers: Option<u32>,
+    // Job group the backend should link this upload into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_id: Option<String>,
+    // Only identify the language, on backends that support it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detect_only: Option<bool>,
+    // Text that biases transcription toward expected vocabulary and names
+    #[serde(default, deserialize_with = "trimmed_text", skip_serializing_if = "Option::is_none")]
+    initial_prompt: Option<String>,
+    // Known section boundaries, such as container chapters, as segmentation hints
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chapters: Option<Vec<media::Chapter>>,
+    // Languages to translate the transcript into, each downloadable on its own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    translate_to: Option<Vec<String>>,
+}
+
+// Longest initial prompt the backend accepts; Whisper keeps roughly 224 tokens of it
+const MAX_INITIAL_PROMPT_CHARS: usize = 896;
+
+// Most chapter hints sent with one job
+const MAX_CHAPTERS: usize = 1000;
+
+// Most translations requested for one job
+const MAX_TRANSLATIONS: usize = 10;
+
+// Trim surrounding whitespace, treating blank text as unset
+fn trimmed_text<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let text = Option::<String>::deserialize(deserializer)?;
+    Ok(text.map(|text| text.trim().to_string()).filter(|text| !text.is_empty()))
+}
+
+// Most speakers diarization is asked to tell apart
+const MAX_SPEAKERS: u32 = 32;
+
+impl ProcessOptions {
+    // Fill unset fields from base
+    fn merged_over(self, base: &ProcessOptions) -> ProcessOptions {
+        ProcessOptions {
+            language: self.language.or_else(|| base.language.clone()),
+            model: self.model.or_else(|| base.model.clone()),
+            num_speakers: self.num_speakers.or(base.num_speakers),
+            min_speakers: self.min_speakers.or(base.min_speakers),
+            max_speakers: self.max_speakers.or(base.max_speakers),
+            group_id: self.group_id.or_else(|| base.group_id.clone()),
+            detect_only: self.detect_only.or(base.detect_only),
+            initial_prompt: self.initial_prompt.or_else(|| base.initial_prompt.clone()),
+            chapters: self.chapters.or_else(|| base.chapters.clone()),
+            translate_to: self.translate_to.or_else(|| base.translate_to.clone()),
+        }
+    }
+    
+    // Every problem with the options, by field
+    fn field_errors(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if let Some(language) = &self.language {
+            if !is_language_code(language) {
+                errors.push(FieldError::new("language", format!("'{}' is not a language code like 'en' or 'pt-BR'", language)));
+            }
+        }
+        if let Some(model) = &self.model {
+            let valid = !model.is_empty()
+                && model.len() <= 64
+                && model.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+            if !valid {
+                errors.push(FieldError::new("model", format!("'{}' is not a valid model name", model)));
+            }
+        }
+        if let Some(prompt) = &self.initial_prompt {
+            let chars = prompt.chars().count();
+            if chars > MAX_INITIAL_PROMPT_CHARS {
+                errors.push(FieldError::new(
+                    "initial_prompt",
+                    format!("must be at most {} characters, got {}", MAX_INITIAL_PROMPT_CHARS, chars),
+                ));
+            }
+        }
+        if let Some(targets) = &self.translate_to {
+            if targets.len() > MAX_TRANSLATIONS {
+                errors.push(FieldError::new("translate_to", format!("must list at most {} languages, got {}", MAX_TRANSLATIONS, targets.len())));
+            }
+            let mut seen = std::collections::HashSet::new();
+            for target in targets {
+                if target == "auto" || !is_language_code(target) {
+                    errors.push(FieldError::new("translate_to", format!("'{}' is not a language code like 'en' or 'pt-BR'", target)));
+                } else if !seen.insert(target.to_lowercase()) {
+                    errors.push(FieldError::new("translate_to", format!("'{}' is listed more than once", target)));
+                }
+            }
+        }
+        if let Some(chapters) = &self.chapters {
+            if chapters.len() > MAX_CHAPTERS {
+                errors.push(FieldError::new("chapters", format!("must be at most {} chapters, got {}", MAX_CHAPTERS, chapters.len())));
+            }
+            let ordered = chapters.iter().all(|chapter| {
+                chapter.start_secs.is_finite() && chapter.start_secs >= 0.0 && chapter.end_secs > chapter.start_secs
+            }) && chapters.windows(2).all(|pair| pair[1].start_secs >= pair[0].start_secs);
+            if !ordered {
+                errors.push(FieldError::new("chapters", "must be non-empty ranges in start order".to_string()));
+            }
+        }
+        let counts = [
+            ("num_speakers", self.num_speakers),
+            ("min_speakers", self.min_speakers),
+            ("max_speakers", self.max_speakers),
+        ];
+        for (name, count) in counts {
+            if let Some(count) = count {
+                if count == 0 || count > MAX_SPEAKERS {
+                    errors.push(FieldError::new(name, format!("must be between 1 and {}, got {}", MAX_SPEAKERS, count)));
+                }
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_speakers, self.max_speakers) {
+            if min > max {
+                errors.push(FieldError::new("min_speakers", format!("{} is greater than max_speakers ({})", min, max)));
+            }
+        }
+        errors
+    }
+    
+    fn validate(&self) -> Result<(), AppError> {
+        let errors = self.field_errors();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation { errors })
+        }
+    }
+}
+
+// "auto", or an ISO 639 code with optional region/script subtags
+fn is_language_code(code: &str) -> bool {
+    if code == "auto" {
+        return true;
+    }
+    let mut parts = code.split(['-', '_']);
+    let primary = parts.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|part| (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[derive(Debug, Serialize)]
+struct SegmentUploadResponse {
+    job_id: String,
+    duration_secs: f64,
+}
+
+// Multipart part name the backend expects for the media file
+const DEFAULT_PART_NAME: &str = "file";
+
+// Where the media part goes relative to the text fields. Streaming parsers on some
+// backends need the options before the file; others expect the file first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FilePosition {
+    #[default]
+    First,
+    Last,
+}
+
+// Extra multipart details for backends that route uploads by type
+#[derive(Debug, Clone)]
+struct UploadForm {
+    part_name: String,
+    fields: BTreeMap<String, String>,
+    // None follows the multipart_file_position setting
+    file_position: Option<FilePosition>,
+}
+
+impl Default for UploadForm {
+    fn default() -> Self {
+        UploadForm {
+            part_name: DEFAULT_PART_NAME.to_string(),
+            fields: BTreeMap::new(),
+            file_position: None,
+        }
+    }
+}
+
+impl UploadForm {
+    fn new(
+        part_name: Option<String>,
+        fields: Option<HashMap<String, String>>,
+        file_position: Option<FilePosition>,
+    ) -> Result<Self, String> {
+        let part_name = part_name.unwrap_or_else(|| DEFAULT_PART_NAME.to_string());
+        validate_field_name(&part_name)?;
+        
+        let fields: BTreeMap<String, String> = fields.unwrap_or_default().into_iter().collect();
+        for name in fields.keys() {
+            validate_field_name(name)?;
+            if name == &part_name || name == "options" {
+                return Err(format!("Form field name '{}' is reserved", name));
+            }
+        }
+        
+        Ok(UploadForm { part_name, fields, file_position })
+    }
+    
+    // Text fields in the order they are sent: options, then extra fields by name
+    fn text_fields(&self, options_json: String) -> Vec<(String, String)> {
+        std::iter::once(("options".to_string(), options_json))
+            .chain(self.fields.iter().map(|(name, value)| (name.clone(), value.clone())))
+            .collect()
+    }
+    
+    // The multipart body with every part in a fixed order
+    fn build(&self, file_part: reqwest::multipart::Part, options_json: String, position: FilePosition) -> reqwest::multipart::Form {
+        let mut multipart = reqwest::multipart::Form::new();
+        let mut file_part = Some(file_part);
+        if position == FilePosition::First {
+            if let Some(part) = file_part.take() {
+                multipart = multipart.part(self.part_name.clone(), part);
+            }
+        }
+        for (name, value) in self.text_fields(options_json) {
+            multipart = multipart.text(name, value);
+        }
+        if let Some(part) = file_part {
+            multipart = multipart.part(self.part_name.clone(), part);
+        }
+        multipart
+    }
+}
+
+fn validate_field_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+    if !valid {
+        return Err(format!("Invalid form field name: '{}'", name));
+    }
+    Ok(())
+}
+
+// Largest chunk read_file_range returns in one call
+const MAX_READ_RANGE_BYTES: u64 = 4 * 1024 * 1024;
+
+// Largest remote text file read_url_text will load into memory
+const MAX_REMOTE_TEXT_BYTES: usize = 10 * 1024 * 1024;
+
+// Progress below which a job counts as stuck, and for how long
+const DEFAULT_STALL_PROGRESS: f32 = 0.5;
+const DEFAULT_STALL_SECS: u64 = 300;
+
+// Upload a local file to the backend as a multipart form
+async fn send_file(
+    state: &AppState,
+    path: &Path,
+    file_name: String,
+    options: &ProcessOptions,
+    form: &UploadForm,
+) -> Result<JobStatusResponse, AppError> {
+    let file = open_upload(path).await?;
+    let size_hint = file.metadata().await.ok().map(|metadata| metadata.len());
+    
+    upload_stream(state, file, Some(path), file_name, size_hint, options, form).await
+}
+
+async fn open_upload(path: &Path) -> Result<tokio::fs::File, AppError> {
+    tokio::fs::File::open(path)
+        .await
+        .map_err(|e| error::io_error("Failed to read file", path, e))
+}
+
+// Upload media from any reader, such as a pipe from another process, streamed as it is read.
+// A reader can only be sent once, so the callback URL is offered only when replay_from names
+// a file to send again if the backend rejects it.
+async fn upload_stream<R>(
+    state: &AppState,
+    reader: R,
+    replay_from: Option<&Path>,
+    file_name: String,
+    size_hint: Option<u64>,
+    options: &ProcessOptions,
+    form: &UploadForm,
+) -> Result<JobStatusResponse, AppError>
+where
+    R: tokio::io::AsyncRead + Send + Sync + Unpin + 'static,
+{
+    let callback_url = match replay_from {
+        Some(_) => callbacks::callback_url(state).await,
+        None => None,
+    };
+    let _permit = state.transfers.upload().await;
+    
+    let part = stream_part(state, reader, size_hint);
+    let mut response = post_file(state, part, &file_name, options, callback_url.as_deref(), form).await?;
+    if let (Some(_), Some(path)) = (&callback_url, replay_from) {
+        if callbacks::rejected(response.status()) {
+            eprintln!("Backend rejected the callback URL, falling back to polling");
+            let part = stream_part(state, open_upload(path).await?, size_hint);
+            response = post_file(state, part, &file_name, options, None, form).await?;
+        }
+    }
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+    
+    // Parse response
+    api::parse_json(response).await
+}
+
+// The file part for an upload, read within the shared transfer buffer budget
+fn stream_part<R>(state: &AppState, reader: R, size_hint: Option<u64>) -> reqwest::multipart::Part
+where
+    R: tokio::io::AsyncRead + Send + Sync + Unpin + 'static,
+{
+    let body = reqwest::Body::wrap_stream(state.transfers.upload_chunks(reader));
+    match size_hint {
+        Some(length) => reqwest::multipart::Part::stream_with_length(body, length),
+        None => reqwest::multipart::Part::stream(body),
+    }
+}
+
+// Upload in-memory media to the backend as a multipart form
+async fn send_bytes(
+    state: &AppState,
+    file_content: Vec<u8>,
+    file_name: String,
+    options: &ProcessOptions,
+    form: &UploadForm,
+) -> Result<JobStatusResponse, AppError> {
+    // Shared so the upload can be repeated without copying the media
+    let file_content = bytes::Bytes::from(file_content);
+    let length = file_content.len() as u64;
+    let part = || reqwest::multipart::Part::stream_with_length(file_content.clone(), length);
+    let callback_url = callbacks::callback_url(state).await;
+    let _permit = state.transfers.upload().await;
+    
+    let mut response = post_file(state, part(), &file_name, options, callback_url.as_deref(), form).await?;
+    if callback_url.is_some() && callbacks::rejected(response.status()) {
+        eprintln!("Backend rejected the callback URL, falling back to polling");
+        response = post_file(state, part(), &file_name, options, None, form).await?;
+    }
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+    
+    // Parse response
+    api::parse_json(response).await
+}
+
+async fn post_file(
+    state: &AppState,
+    file_part: reqwest::multipart::Part,
+    file_name: &str,
+    options: &ProcessOptions,
+    callback_url: Option<&str>,
+    form: &UploadForm,
+) -> Result<reqwest::Response, AppError> {
+    let file_part = file_part.file_name(file_name.to_string());
+    
+    let options_json = callbacks::options_payload(options, callback_url)?.to_string();
+    
+    let position = match form.file_position {
+        Some(position) => position,
+        None => state.settings.lock().await.multipart_file_position,
+    };
+    let multipart = form.build(file_part, options_json, position);
+    
+    // Send request to backend API
+    let request = state.client().post(format!("{}/process/file", state.api_url()))
+        .multipart(multipart);
+    // Transport failures and timeouts count as retryable, e.g. for the adaptive concurrency limit
+    api::send(state, request).await.map_err(|message| AppError::Network { message })
+}
+
+// Extract a time range locally and upload it, returning the extracted duration
+async fn send_segment(
+    state: &AppState,
+    path: &Path,
+    start_secs: f64,
+    end_secs: f64,
+    options: &ProcessOptions,
+) -> Result<(JobStatusResponse, f64), AppError> {
+    let stem = path.file_stem()
+        .ok_or_else(|| "Invalid file path".to_string())?
+        .to_string_lossy()
+        .to_string();
+    let extension = path.extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_else(|| "wav".to_string());
+    
+    // Validate the requested range against the probed duration
+    let duration = media::probe_duration(path).await?;
+    if !start_secs.is_finite() || !end_secs.is_finite() || start_secs < 0.0 || end_secs <= start_secs {
+        return Err(format!("Invalid segment range: {}s to {}s", start_secs, end_secs).into());
+    }
+    if end_secs > duration {
+        return Err(format!(
+            "Segment end {:.2}s is past the end of the media ({:.2}s)",
+            end_secs, duration
+        ).into());
+    }
+    
+    // The temp file is removed when it goes out of scope, including on error
+    let segment = media::TempFile::new("segment", &extension);
+    media::extract_segment(path, start_secs, end_secs, segment.path()).await?;
+    let extracted_duration = media::probe_duration(segment.path()).await?;
+    
+    let file_name = format!("{}_{:.0}-{:.0}.{}", stem, start_secs, end_secs, extension);
+    let api_response = send_file(state, segment.path(), file_name, options, &UploadForm::default()).await?;
+    
+    Ok((api_response, extracted_duration))
+}
+
+// Submit a media URL to the backend
+async fn send_url(
+    state: &AppState,
+    url: &str,
+    options: &ProcessOptions,
+) -> Result<JobStatusResponse, AppError> {
+    let callback_url = callbacks::callback_url(state).await;
+    
+    let mut response = post_url(state, url, options, callback_url.as_deref()).await?;
+    if callback_url.is_some() && callbacks::rejected(response.status()) {
+        eprintln!("Backend rejected the callback URL, falling back to polling");
+        response = post_url(state, url, options, None).await?;
+    }
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+    
+    // Parse response
+    api::parse_json(response).await
+}
+
+async fn post_url(
+    state: &AppState,
+    url: &str,
+    options: &ProcessOptions,
+    callback_url: Option<&str>,
+) -> Result<reqwest::Response, AppError> {
+    // Create request body
+    let body = serde_json::json!({
+        "url": url,
+        "options": callbacks::options_payload(options, callback_url)?
+    });
+    
+    // Send request to backend API
+    let request = state.client().post(format!("{}/process/url", state.api_url()))
+        .json(&body);
+    api::send(state, request).await.map_err(|message| AppError::Network { message })
+}
+
+// Submit a tracked source again
+async fn submit_source(
+    state: &AppState,
+    source: &JobSource,
+    options: &ProcessOptions,
+) -> Result<JobStatusResponse, AppError> {
+    match source {
+        JobSource::File { path } => {
+            let file_path = PathBuf::from(path);
+            let file_name = file_path.file_name()
+                .ok_or_else(|| "Invalid file path".to_string())?
+                .to_string_lossy()
+                .to_string();
+            send_file(state, &file_path, file_name, options, &UploadForm::default()).await
+        }
+        JobSource::Segment { path, start_secs, end_secs } => {
+            send_segment(state, Path::new(path), *start_secs, *end_secs, options)
+                .await
+                .map(|(api_response, _)| api_response)
+        }
+        JobSource::Url { url } => send_url(state, url, options).await,
+        JobSource::Fetched { url } => {
+            let media = fetch::fetch_to_temp(state, url).await?;
+            send_file(state, media.file.path(), media.file_name.clone(), options, &UploadForm::default()).await
+        }
+        JobSource::Remote => Err("This job was submitted elsewhere and can't be resubmitted".to_string().into()),
+        JobSource::Pipe { .. } => Err("This job was streamed from a pipe and can't be resubmitted".to_string().into()),
+    }
+}
+
+// Track a submitted job. A job ID seen before keeps its record, with the source and
+// options refreshed, so a backend that reuses IDs never gets a job polled twice.
+async fn track_job(state: &AppState, job_id: &str, source: JobSource, options: ProcessOptions) {
+    let session_id = submission_sessions::current_id(state).await;
+    state.processing_jobs.lock().await.insert(job_id.to_string());
+    let reused = {
+        let mut records = state.job_records.lock().await;
+        match records.get_mut(job_id) {
+            Some(record) => {
+                record.source = source;
+                record.options = options;
+                true
+            }
+            None => {
+                let mut record = JobRecord::new(job_id.to_string(), source, options);
+                record.session_id = session_id;
+                records.insert(job_id.to_string(), record);
+                false
+            }
+        }
+    };
+    if reused {
+        // Copies served from the earlier run would show its result
+        result_server::invalidate(state, job_id).await;
+    }
+    persist_records(state).await;
+    submission_sessions::note_job(state).await;
+}
+
+// Note that the user viewed, exported or copied a job's result, which keeps it from going idle
+async fn note_access(state: &AppState, job_id: &str) {
+    let now = records::unix_now();
+    let changed = match state.job_records.lock().await.get_mut(job_id) {
+        // Repeated reads within a minute aren't worth rewriting the records for
+        Some(record) if record.last_accessed_at.is_none_or(|at| now.saturating_sub(at) >= 60) => {
+            record.last_accessed_at = Some(now);
+            true
+        }
+        _ => false,
+    };
+    if changed {
+        persist_records(state).await;
+    }
+}
+
+// Remember a saved result file so it counts against the storage budget
+async fn record_download(state: &AppState, job_id: &str, path: &str) {
+    let found = match state.job_records.lock().await.get_mut(job_id) {
+        Some(record) => {
+            if !record.downloads.iter().any(|download| download == path) {
+                record.downloads.push(path.to_string());
+            }
+            // Saving a result again still counts as using the job
+            record.last_accessed_at = Some(records::unix_now());
+            true
+        }
+        None => false,
+    };
+    if found {
+        persist_records(state).await;
+    }
+}
+
+// Save job records to the data directory, oldest first
+async fn persist_records(state: &AppState) {
+    let mut records: Vec<JobRecord> = state.job_records.lock().await.values().cloned().collect();
+    records.sort_by_key(|record| record.submitted_at);
+    
+    if let Err(e) = persist::save_json(&state.data_dir.join(records::RECORDS_FILE), &records).await {
+        eprintln!("Failed to save job records: {}", e);
+    }
+}
+
+// Save settings to the config directory
+async fn persist_settings(state: &AppState) -> Result<(), AppError> {
+    let settings = state.settings.lock().await.clone();
+    persist::save_json(&state.config_dir.join(settings::SETTINGS_FILE), &settings).await
+}
+
+// Ask the backend for a job's status. With a base, a delta of just the changed fields is
+// accepted and merged into it; None means a delta arrived with nothing to merge into.
+async fn request_status(
+    state: &AppState,
+    job_id: &str,
+    base: Option<&JobStatusResponse>,
+) -> Result<Option<JobStatusResponse>, AppError> {
+    // Send request to backend API
+    let mut request = state.client().get(format!("{}/status/{}", state.api_url(), job_id));
+    if base.is_some() {
+        request = request.header(cache::DELTA_HEADER, "1");
+    }
+    let response = retry::send(state, request).await?;
+    
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        state.status_cache.lock().await.mark_missing(job_id);
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+    
+    // Backends without delta support ignore the header and send the full status
+    let headers = response.headers().clone();
+    let body: serde_json::Value = api::parse_json(response).await?;
+    if !cache::is_delta(&headers, &body) {
+        let status = serde_json::from_value(body)
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        return Ok(Some(status));
+    }
+    match base {
+        Some(base) => Ok(Some(cache::merge_delta(base, body)?)),
+        None => Ok(None),
+    }
+}
+
+// Fetch a job's status from the backend and update its record
+async fn fetch_status(state: &AppState, job_id: &str) -> Result<JobStatusResponse, AppError> {
+    let base = state.status_cache.lock().await.last(job_id);
+    let api_response = match request_status(state, job_id, base.as_ref()).await? {
+        Some(status) => status,
+        None => request_status(state, job_id, None).await?
+            .ok_or_else(|| format!("Backend sent only a partial status for job {}", job_id))?,
+    };
+    apply_status(state, job_id, &api_response).await;
+    
+    Ok(api_response)
+}
+
+// Fold a status report into the job's record and the status cache
+async fn apply_status(state: &AppState, job_id: &str, status: &JobStatusResponse) {
+    let (changed, completed, completed_with_warnings) = match state.job_records.lock().await.get_mut(job_id) {
+        Some(record) => {
+            let status_changed = record.observe(&status.status, status.progress);
+            let warnings_changed = !status.warnings.is_empty() && record.warnings != status.warnings;
+            if warnings_changed {
+                record.warnings = status.warnings.clone();
+            }
+            // Responses without expiry info keep whatever was reported before
+            let expiry_changed = status.result_expires_at.is_some() && record.result_expires_at != status.result_expires_at;
+            if expiry_changed {
+                record.result_expires_at = status.result_expires_at;
+            }
+            let completed = status_changed && status.status == "complete";
+            (status_changed || warnings_changed || expiry_changed, completed, completed && !record.warnings.is_empty())
+        }
+        None => (false, false, false),
+    };
+    if changed {
+        persist_records(state).await;
+    }
+    state.status_cache.lock().await.insert(job_id, status.clone());
+    if records::is_finished_status(&status.status) {
+        state.processing_jobs.lock().await.remove(job_id);
+    }
+    
+    // A clean-looking "complete" shouldn't hide problems with the result
+    if completed_with_warnings {
+        if let Some(app) = state.app_handle.get() {
+            let event = JobWarningEvent {
+                job_id: job_id.to_string(),
+                warnings: status.warnings.clone(),
+            };
+            let _ = app.emit_all("job-warning", event);
+        }
+    }
+    if completed {
+        postprocess::on_complete(state, job_id);
+    }
+}
+
+// Ask the backend to cancel a job
+async fn send_cancel(state: &AppState, job_id: &str) -> Result<reqwest::StatusCode, String> {
+    let request = state.client().delete(format!("{}/job/{}", state.api_url(), job_id));
+    let response = api::send(state, request).await?;
+    
+    Ok(response.status())
 }
 
 // Tauri commands
+// Commands take their arguments flat from the frontend
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 async fn upload_file(
-    _app: tauri::AppHandle,
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
     path: String,
-) -> Result<String, String> {
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    part_name: Option<String>,
+    fields: Option<HashMap<String, String>>,
+    max_duration_secs: Option<u64>,
+    file_position: Option<FilePosition>,
+    force: Option<bool>,
+) -> Result<String, AppError> {
     println!("Uploading file from path: {}", path); // Debug log
     
-    // Create a multipart form
+    let form = UploadForm::new(part_name, fields, file_position)?;
+    
     let file_path = PathBuf::from(&path);
     let file_name = file_path.file_name()
         .ok_or_else(|| "Invalid file path".to_string())?
         .to_string_lossy()
         .to_string();
     
-    // Read file content into bytes
-    let file_content = tokio::fs::read(&path)
-        .await
-        .map_err(|e| format!("Failed to read file: {}", e))?;
-    
-    println!("File size: {} bytes", file_content.len()); // Debug log
-    
-    // Create part from bytes
-    let file_part = reqwest::multipart::Part::bytes(file_content)
-        .file_name(file_name);
-    
-    let form = reqwest::multipart::Form::new()
-        .part("file", file_part);
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
     
-    // Send request to backend API
-    let response = state.api_client.post(&format!("{}/process/file", API_URL))
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
+    // Unchanged content with the same options is already being handled, unless forced
+    let fingerprint = revisions::fingerprint(&state, &file_path).await?;
+    let force = force.unwrap_or(false);
+    let revision_of = match revisions::classify(&state, &path, &fingerprint, &options, force).await {
+        revisions::Upload::Duplicate(job_id) => return Ok(job_id),
+        revisions::Upload::New { revision_of } => revision_of,
+    };
     
-    // Parse response
-    let api_response: ApiResponse = response.json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let api_response = send_file(&state, &file_path, file_name, &options, &form).await?;
     
     println!("Got job ID: {}", api_response.job_id); // Debug log
     
     // Store job ID in app state
-    state.processing_jobs.lock().await.push(api_response.job_id.clone());
+    track_job(&state, &api_response.job_id, JobSource::File { path }, options).await;
+    revisions::record(&state, &api_response.job_id, fingerprint, revision_of).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
     
     // Return job ID
     Ok(api_response.job_id)
 }
 
+// Upload media from a named pipe, e.g. one a recorder is writing to, sending it as it
+// arrives. A pipe can only be read once, so the job can't be resubmitted later.
 #[tauri::command]
-async fn process_url(
-    _app: tauri::AppHandle,
+async fn upload_from_pipe(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    url: String,
-) -> Result<String, String> {
-    // Create request body
-    let body = serde_json::json!({
-        "url": url,
-        "options": {}
-    });
-    
-    // Send request to backend API
-    let response = state.api_client.post(&format!("{}/process/url", API_URL))
-        .json(&body)
-        .send()
+    path: String,
+    file_name: Option<String>,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    max_duration_secs: Option<u64>,
+) -> Result<String, AppError> {
+    let pipe_path = PathBuf::from(&path);
+    let metadata = tokio::fs::metadata(&pipe_path)
         .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
+        .map_err(|e| error::io_error("Failed to read pipe", &pipe_path, e))?;
+    if metadata.is_file() || metadata.is_dir() {
+        return Err(format!("{} is not a pipe; upload files with upload_file", path).into());
+    }
+    // The backend goes by the name's extension to tell the container
+    let file_name = match file_name {
+        Some(name) if name.is_empty() || name.contains(['/', '\\']) => {
+            return Err(format!("Invalid file name: '{}'", name).into());
+        }
+        Some(name) => name,
+        None => pipe_path.file_name()
+            .ok_or_else(|| "Invalid pipe path".to_string())?
+            .to_string_lossy()
+            .to_string(),
+    };
+
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+    let reader = open_upload(&pipe_path).await?;
+    let api_response = upload_stream(&state, reader, None, file_name, None, &options, &UploadForm::default()).await?;
+
+    track_job(&state, &api_response.job_id, JobSource::Pipe { path }, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
+    Ok(api_response.job_id)
+}
+
+#[tauri::command]
+async fn upload_file_segment(
+    state: State<'_, AppState>,
+    path: String,
+    start_secs: f64,
+    end_secs: f64,
+    options: Option<ProcessOptions>,
+    max_duration_secs: Option<u64>,
+) -> Result<SegmentUploadResponse, AppError> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let (api_response, extracted_duration) =
+        send_segment(&state, Path::new(&path), start_secs, end_secs, &options).await?;
     
-    // Parse response
-    let api_response: ApiResponse = response.json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    // Store job ID in app state
+    let source = JobSource::Segment { path, start_secs, end_secs };
+    track_job(&state, &api_response.job_id, source, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
+    
+    Ok(SegmentUploadResponse {
+        job_id: api_response.job_id,
+        duration_secs: extracted_duration,
+    })
+}
+
+#[tauri::command]
+async fn process_url(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    max_duration_secs: Option<u64>,
+) -> Result<String, AppError> {
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+    let api_response = send_url(&state, &url, &options).await?;
     
     // Store job ID in app state
-    state.processing_jobs.lock().await.push(api_response.job_id.clone());
+    track_job(&state, &api_response.job_id, JobSource::Url { url }, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
     
     // Return job ID
     Ok(api_response.job_id)
 }
 
+// Check options before submitting; an empty list means they're fine
+#[tauri::command]
+async fn validate_options(
+    state: State<'_, AppState>,
+    options: ProcessOptions,
+    format: Option<String>,
+) -> Result<Vec<FieldError>, AppError> {
+    let mut errors = options.field_errors();
+    
+    // The backend's advertised capabilities catch codes that are well-formed but unsupported
+    match capabilities::fetch(&state, false).await {
+        Ok(Some(capabilities)) => {
+            for error in capabilities.field_errors(&options, format.as_deref()) {
+                if !errors.iter().any(|existing| existing.field == error.field) {
+                    errors.push(error);
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Could not check options against backend capabilities: {}", e),
+    }
+    
+    Ok(errors)
+}
+
+// Page size for list_jobs when the UI doesn't ask for one, and the most it can ask for
+const DEFAULT_JOB_PAGE_SIZE: usize = 50;
+const MAX_JOB_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JobSort {
+    #[default]
+    Date,
+    Status,
+    Name,
+}
+
+#[derive(Debug, Serialize)]
+struct JobPage {
+    jobs: Vec<JobRecord>,
+    // Tracked jobs in all pages
+    total: usize,
+    offset: usize,
+    limit: usize,
+    // Pass back when fetching later pages so jobs submitted meanwhile don't shift them
+    as_of: u64,
+}
+
+// Display name for sorting: the source's file name, or the URL
+fn source_name(source: &JobSource) -> String {
+    match source {
+        JobSource::File { path } | JobSource::Segment { path, .. } | JobSource::Pipe { path } => Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default(),
+        JobSource::Url { url } | JobSource::Fetched { url } => url.to_lowercase(),
+        JobSource::Remote => String::new(),
+    }
+}
+
+// One page of tracked jobs; newest first by default
+#[tauri::command]
+async fn list_jobs(
+    state: State<'_, AppState>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort_by: Option<JobSort>,
+    descending: Option<bool>,
+    as_of: Option<u64>,
+) -> Result<JobPage, AppError> {
+    let sort_by = sort_by.unwrap_or_default();
+    let as_of = as_of.unwrap_or_else(records::unix_now);
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_JOB_PAGE_SIZE).clamp(1, MAX_JOB_PAGE_SIZE);
+    // Dates read newest first, names and statuses A to Z
+    let descending = descending.unwrap_or(matches!(sort_by, JobSort::Date));
+    
+    let mut records: Vec<JobRecord> = state.job_records.lock().await
+        .values()
+        .filter(|record| record.submitted_at <= as_of)
+        .cloned()
+        .collect();
+    // Ties fall back to submission time and then job ID so pages never overlap or skip
+    records.sort_by(|a, b| {
+        let primary = match sort_by {
+            JobSort::Date => std::cmp::Ordering::Equal,
+            JobSort::Status => a.status.cmp(&b.status),
+            JobSort::Name => source_name(&a.source).cmp(&source_name(&b.source)),
+        };
+        primary
+            .then(a.submitted_at.cmp(&b.submitted_at))
+            .then_with(|| a.job_id.cmp(&b.job_id))
+    });
+    if descending {
+        records.reverse();
+    }
+    
+    let total = records.len();
+    let jobs = records.into_iter().skip(offset).take(limit).collect();
+    Ok(JobPage { jobs, total, offset, limit, as_of })
+}
+
 #[tauri::command]
 async fn get_job_status(
     state: State<'_, AppState>,
     job_id: String,
-) -> Result<serde_json::Value, String> {
-    // Send request to backend API
-    let response = state.api_client.get(&format!("{}/status/{}", API_URL, job_id))
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
+    force_refresh: Option<bool>,
+) -> Result<JobStatusResponse, AppError> {
+    // Serve rapid repeat polls from the cache
+    if !force_refresh.unwrap_or(false) {
+        let cache = state.status_cache.lock().await;
+        if let Some(status) = cache.get(&job_id) {
+            return Ok(status);
+        }
+        if cache.is_known_missing(&job_id) {
+            return Err(AppError::JobNotFound { job_id });
+        }
+    }
     
-    // Parse response
-    let api_response: serde_json::Value = response.json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    fetch_status(&state, &job_id).await
+}
+
+// Most recent status observed for a job, without a network call, so remounted views can repaint at once
+#[tauri::command]
+async fn get_last_progress(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<Option<JobStatusResponse>, AppError> {
+    Ok(state.status_cache.lock().await.last(&job_id))
+}
+
+// Whether the backend still knows a job, answering from the cache when possible
+async fn check_job_exists(state: &AppState, job_id: &str) -> Result<bool, AppError> {
+    {
+        let cache = state.status_cache.lock().await;
+        if cache.is_known_missing(job_id) {
+            return Ok(false);
+        }
+        if cache.get(job_id).is_some() {
+            return Ok(true);
+        }
+    }
     
-    // Return status
-    Ok(api_response)
+    match fetch_status(state, job_id).await {
+        Ok(_) => Ok(true),
+        Err(AppError::JobNotFound { .. }) => Ok(false),
+        Err(e) => Err(e),
+    }
 }
 
 #[tauri::command]
-async fn download_result(
-    _app: tauri::AppHandle,
+async fn job_exists(
     state: State<'_, AppState>,
     job_id: String,
-    format: String,
-    save_path: String,
-) -> Result<String, String> {
-    // Send request to backend API
-    let response = state.api_client.get(&format!("{}/download/{}/{}", API_URL, job_id, format))
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
+) -> Result<bool, AppError> {
+    check_job_exists(&state, &job_id).await
+}
+
+#[tauri::command]
+async fn get_job_warnings(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<Vec<String>, AppError> {
+    if let Some(record) = state.job_records.lock().await.get(&job_id) {
+        return Ok(record.warnings.clone());
+    }
     
-    // Check if request was successful
-    if !response.status().is_success() {
-        return Err(format!("Failed to download result: {}", response.status()));
+    // Jobs submitted elsewhere have no record, so ask the backend
+    Ok(fetch_status(&state, &job_id).await?.warnings)
+}
+
+#[tauri::command]
+async fn set_status_cache_ttl(
+    state: State<'_, AppState>,
+    ttl_ms: u64,
+) -> Result<(), AppError> {
+    state.settings.lock().await.status_cache_ttl_ms = ttl_ms;
+    state.status_cache.lock().await.set_ttl(Duration::from_millis(ttl_ms));
+    persist_settings(&state).await?;
+    Ok(())
+}
+
+// Download a job's result in the given format, on the original timeline if silence was trimmed
+async fn fetch_result(
+    state: &AppState,
+    job_id: &str,
+    format: &str,
+    query: &[(String, String)],
+) -> Result<Vec<u8>, AppError> {
+    if let Some(bytes) = silence::restored_result(state, job_id, format, query).await? {
+        return Ok(bytes);
     }
+    fetch_backend_result(state, job_id, format, query).await
+}
+
+// The result exactly as the backend has it
+async fn fetch_backend_result(
+    state: &AppState,
+    job_id: &str,
+    format: &str,
+    query: &[(String, String)],
+) -> Result<Vec<u8>, AppError> {
+    let _permit = state.transfers.download().await;
+    let response = open_result(state, job_id, format, query).await?;
     
     // Get response bytes
     let bytes = response.bytes()
         .await
         .map_err(|e| format!("Failed to read response: {}", e))?;
     
-    // Write to file
-    let path = PathBuf::from(&save_path);
-    tokio::fs::write(&path, &bytes)
-        .await
-        .map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(bytes.to_vec())
+}
+
+// Start downloading a result, leaving the body to be read by the caller
+async fn open_result(
+    state: &AppState,
+    job_id: &str,
+    format: &str,
+    query: &[(String, String)],
+) -> Result<reqwest::Response, AppError> {
+    if state.status_cache.lock().await.is_known_missing(job_id) {
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
     
-    // Return success
-    Ok(save_path)
+    // Send request to backend API
+    let request = state.client().get(format!("{}/download/{}/{}", state.api_url(), job_id, format))
+        .query(query);
+    let response = retry::send(state, request).await?;
+    
+    // A 404 means either the job or just its result is gone
+    if response.status() == reqwest::StatusCode::NOT_FOUND && !check_job_exists(state, job_id).await? {
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
+    
+    // Check if request was successful
+    if !response.status().is_success() {
+        return Err(api::error_from_response(response).await);
+    }
+    
+    Ok(response)
+}
+
+#[tauri::command]
+async fn set_signing_key(
+    state: State<'_, AppState>,
+    key: Option<String>,
+) -> Result<(), AppError> {
+    // An empty or missing key turns signing off
+    state.settings.lock().await.signing_key = key.filter(|key| !key.is_empty());
+    persist_settings(&state).await?;
+    Ok(())
+}
+
+// Default placement of the media part for uploads that don't choose their own
+#[tauri::command]
+async fn set_multipart_file_position(
+    state: State<'_, AppState>,
+    position: FilePosition,
+) -> Result<(), AppError> {
+    state.settings.lock().await.multipart_file_position = position;
+    persist_settings(&state).await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn download_result(
+    app: tauri::AppHandle,
+    job_id: String,
+    format: String,
+    save_path: String,
+    format_options: Option<HashMap<String, serde_json::Value>>,
+    op_id: Option<String>,
+    encoding: Option<String>,
+    bom: Option<bool>,
+    language: Option<String>,
+) -> Result<String, AppError> {
+    // Omitted options fall back to the backend's defaults
+    let mut query = formats::format_query(&format, &format_options.unwrap_or_default())?;
+    // A translation is picked by its language; the original transcript otherwise
+    if let Some(language) = language {
+        let record = app.state::<AppState>().job_records.lock().await.get(&job_id).cloned();
+        query.push(("language".to_string(), translation::check_language(record.as_ref(), &language)?));
+    }
+    // Text results are written as UTF-8 without a BOM unless asked otherwise
+    let output_encoding = encoding::OutputEncoding::parse(&format, encoding.as_deref(), bom)?;
+    
+    // Passing an op_id makes the download cancellable with cancel_download
+    let task_app = app.clone();
+    operations::run(&app, op_id, tasks::TaskKind::Download, Some(job_id.clone()), async move {
+        let state = task_app.state::<AppState>();
+        let download = integrity::download_verified(&state, &job_id, &format, &query, Path::new(&save_path)).await?;
+        if !download.verified {
+            return Err(format!(
+                "Result for job {} failed verification after {} attempts: {}",
+                job_id, download.attempts, download.problem.unwrap_or_default()
+            ).into());
+        }
+        // A zip of several outputs is saved as-is; download_and_extract_result unpacks it
+        if download.archive {
+            archive::announce(&task_app, &job_id, &save_path);
+        } else if let Some(output_encoding) = output_encoding {
+            output_encoding.apply(Path::new(&save_path)).await?;
+        }
+        
+        record_download(&state, &job_id, &save_path).await;
+        
+        // Return success
+        Ok(save_path)
+    }).await
 }
 
 #[tauri::command]
-async fn read_file(path: String) -> Result<String, String> {
+async fn read_file(path: String) -> Result<String, AppError> {
     // Read file content
     let content = tokio::fs::read_to_string(&path)
         .await
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+        .map_err(|e| error::io_error("Failed to read file", Path::new(&path), e))?;
     
     Ok(content)
 }
 
+#[derive(Debug, Serialize)]
+struct FileRange {
+    content: String,
+    offset: u64,
+    // Bytes consumed from the file; the next page starts at offset + bytes_read
+    bytes_read: u64,
+    total_size: u64,
+    // True when invalid UTF-8 was replaced with U+FFFD
+    lossy: bool,
+}
+
 #[tauri::command]
-async fn cancel_job(
+async fn read_file_range(path: String, offset: u64, length: u64) -> Result<FileRange, AppError> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+    
+    let mut file = tokio::fs::File::open(&path)
+        .await
+        .map_err(|e| error::io_error("Failed to read file", Path::new(&path), e))?;
+    let total_size = file.metadata()
+        .await
+        .map_err(|e| error::io_error("Failed to read file", Path::new(&path), e))?
+        .len();
+    
+    let offset = offset.min(total_size);
+    let length = length.min(MAX_READ_RANGE_BYTES).min(total_size - offset);
+    
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(|e| error::io_error("Failed to read file", Path::new(&path), e))?;
+    let mut buffer = vec![0u8; length as usize];
+    file.read_exact(&mut buffer)
+        .await
+        .map_err(|e| error::io_error("Failed to read file", Path::new(&path), e))?;
+    
+    // Leave a character split by the page boundary for the next page
+    if offset + length < total_size {
+        if let Err(e) = std::str::from_utf8(&buffer) {
+            if e.error_len().is_none() && e.valid_up_to() > 0 {
+                buffer.truncate(e.valid_up_to());
+            }
+        }
+    }
+    
+    let bytes_read = buffer.len() as u64;
+    let (content, lossy) = match String::from_utf8(buffer) {
+        Ok(content) => (content, false),
+        Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), true),
+    };
+    
+    Ok(FileRange {
+        content,
+        offset,
+        bytes_read,
+        total_size,
+        lossy,
+    })
+}
+
+// Whether a Content-Type header describes text the UI can display
+fn is_text_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime.as_str(),
+            "application/json" | "application/xml" | "application/x-subrip" | "application/yaml"
+        )
+}
+
+#[tauri::command]
+async fn read_url_text(
     state: State<'_, AppState>,
-    job_id: String,
-) -> Result<bool, String> {
-    // Send request to backend API
-    let response = state.api_client.delete(&format!("{}/job/{}", API_URL, job_id))
+    url: String,
+) -> Result<String, AppError> {
+    let parsed = reqwest::Url::parse(&url)
+        .map_err(|e| format!("Invalid URL: {}", e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()).into());
+    }
+    
+    let mut response = state.client().get(parsed)
         .send()
         .await
         .map_err(|e| format!("Failed to send request: {}", e))?;
     
-    // Check if request was successful
     if !response.status().is_success() {
-        return Err(format!("Failed to cancel job: {}", response.status()));
+        return Err(format!("Failed to fetch URL: {}", response.status()).into());
+    }
+    
+    // Reject binary content before reading the body
+    let content_type = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !is_text_content_type(&content_type) {
+        return Err(format!("URL does not point to text content ({})", content_type).into());
+    }
+    
+    if response.content_length().is_some_and(|len| len > MAX_REMOTE_TEXT_BYTES as u64) {
+        return Err(format!("Remote file is larger than {} bytes", MAX_REMOTE_TEXT_BYTES).into());
+    }
+    
+    // Read in chunks so a missing or wrong Content-Length can't exceed the cap
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?
+    {
+        if body.len() + chunk.len() > MAX_REMOTE_TEXT_BYTES {
+            return Err(format!("Remote file is larger than {} bytes", MAX_REMOTE_TEXT_BYTES).into());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    
+    let text = String::from_utf8(body)
+        .map_err(|_| "Remote file is not valid UTF-8 text".to_string())?;
+    Ok(text)
+}
+
+// Cancel a job on the backend and stop tracking it
+async fn cancel_tracked_job(state: &AppState, job_id: &str) -> Result<(), AppError> {
+    if state.status_cache.lock().await.is_known_missing(job_id) {
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
+    
+    // Send request to backend API
+    let status = send_cancel(state, job_id).await?;
+    
+    // Check if request was successful
+    if status == reqwest::StatusCode::NOT_FOUND {
+        state.status_cache.lock().await.mark_missing(job_id);
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
+    if !status.is_success() {
+        return Err(format!("Failed to cancel job: {}", status).into());
     }
     
     // Remove job ID from app state
-    let mut jobs = state.processing_jobs.lock().await;
-    if let Some(index) = jobs.iter().position(|id| id == &job_id) {
-        jobs.remove(index);
+    state.processing_jobs.lock().await.remove(job_id);
+    if let Some(record) = state.job_records.lock().await.get_mut(job_id) {
+        record.status = "cancelled".to_string();
+    }
+    state.status_cache.lock().await.invalidate(job_id);
+    persist_records(state).await;
+    
+    Ok(())
+}
+
+// Fetch whatever transcript the backend has produced so far, if any
+async fn fetch_partial_result(state: &AppState, job_id: &str) -> Result<Option<Vec<u8>>, String> {
+    let request = state.client().get(format!("{}/partial/{}", state.api_url(), job_id));
+    let response = api::send(state, request).await?;
+    
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::NO_CONTENT {
+        return Ok(None);
     }
+    if !status.is_success() {
+        return Err(format!("Failed to fetch partial result: {}", status));
+    }
+    
+    let bytes = response.bytes()
+        .await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    
+    Ok(if bytes.is_empty() { None } else { Some(bytes.to_vec()) })
+}
+
+#[tauri::command]
+async fn cancel_job(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<bool, AppError> {
+    cancel_tracked_job(&state, &job_id).await?;
     
     // Return success
     Ok(true)
 }
 
-fn main() {
-    // Initialize application state
-    let app_state = AppState {
-        api_client: reqwest::Client::new(),
-        processing_jobs: Arc::new(Mutex::new(Vec::new())),
+#[derive(Debug, Serialize)]
+struct CancelOutcome {
+    job_id: String,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+const SOURCE_TYPES: &[&str] = &["file", "segment", "url", "fetched", "remote"];
+
+// Cancel every unfinished tracked job matching the filter
+#[tauri::command]
+async fn cancel_jobs_where(
+    state: State<'_, AppState>,
+    filter: search::JobQuery,
+    confirm_all: Option<bool>,
+) -> Result<Vec<CancelOutcome>, AppError> {
+    if let Some(unknown) = filter.source_types.iter().flatten().find(|t| !SOURCE_TYPES.contains(&t.as_str())) {
+        return Err(format!("Unknown source type '{}'", unknown).into());
+    }
+    
+    let (mut matched, active) = {
+        let records = state.job_records.lock().await;
+        let active: Vec<&JobRecord> = records.values().filter(|record| !record.is_finished()).collect();
+        let matched: Vec<(u64, String)> = active.iter()
+            .filter(|record| filter.matches(record))
+            .map(|record| (record.submitted_at, record.job_id.clone()))
+            .collect();
+        (matched, active.len())
+    };
+    // A filter that catches everything is more likely a mistake than a choice
+    if (filter.is_empty() || matched.len() == active) && matched.len() > 1 && !confirm_all.unwrap_or(false) {
+        return Err(format!(
+            "Filter matches all {} active jobs; pass confirm_all to cancel them all",
+            matched.len()
+        ).into());
+    }
+    matched.sort();
+    
+    let mut outcomes = Vec::with_capacity(matched.len());
+    for (_, job_id) in matched {
+        let result = cancel_tracked_job(&state, &job_id).await;
+        outcomes.push(CancelOutcome {
+            job_id,
+            cancelled: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+    Ok(outcomes)
+}
+
+#[derive(Debug, Serialize)]
+struct CancelKeepPartialResponse {
+    cancelled: bool,
+    partial_path: Option<String>,
+}
+
+#[tauri::command]
+async fn cancel_job_keep_partial(
+    state: State<'_, AppState>,
+    job_id: String,
+    dest_path: String,
+) -> Result<CancelKeepPartialResponse, AppError> {
+    // Save the partial transcript before cancelling discards it
+    let partial_path = match fetch_partial_result(&state, &job_id).await? {
+        Some(bytes) => {
+            tokio::fs::write(&dest_path, &bytes)
+                .await
+                .map_err(|e| error::io_error("Failed to write file", Path::new(&dest_path), e))?;
+            Some(dest_path)
+        }
+        None => None,
     };
     
+    cancel_tracked_job(&state, &job_id).await?;
+    
+    Ok(CancelKeepPartialResponse {
+        cancelled: true,
+        partial_path,
+    })
+}
+
+#[tauri::command]
+async fn restart_job(
+    state: State<'_, AppState>,
+    job_id: String,
+    options: Option<ProcessOptions>,
+    stall_progress: Option<f32>,
+    stall_secs: Option<u64>,
+    force: Option<bool>,
+) -> Result<String, AppError> {
+    // Refresh the record so the stall check sees the latest progress
+    let _ = fetch_status(&state, &job_id).await;
+    
+    let record = state.job_records.lock().await.get(&job_id).cloned()
+        .ok_or_else(|| format!("No tracked source for job {}", job_id))?;
+    
+    if !force.unwrap_or(false) {
+        let stall_progress = stall_progress.unwrap_or(DEFAULT_STALL_PROGRESS);
+        let stall_secs = stall_secs.unwrap_or(DEFAULT_STALL_SECS);
+        if record.progress >= stall_progress || record.stalled_secs() < stall_secs {
+            return Err(format!(
+                "Job {} is not stuck (progress {:.0}%, unchanged for {}s)",
+                job_id,
+                record.progress * 100.0,
+                record.stalled_secs()
+            ).into());
+        }
+    }
+    
+    let options = options.unwrap_or(record.options);
+    options.validate()?;
+    // The old job keeps running unless its source can still be submitted
+    if let JobSource::File { path } | JobSource::Segment { path, .. } = &record.source {
+        tokio::fs::metadata(path)
+            .await
+            .map_err(|e| error::io_error("Source file is no longer available", Path::new(path), e))?;
+    }
+    
+    // Resubmit the original source as a fresh job, then cancel the old one
+    let api_response = submit_source(&state, &record.source, &options).await?;
+    if api_response.job_id != job_id {
+        // A job the backend no longer knows is fine to replace
+        let cancelled = match send_cancel(&state, &job_id).await {
+            Ok(status) if status.is_success() || status == reqwest::StatusCode::NOT_FOUND => Ok(()),
+            Ok(status) => Err(format!("Failed to cancel job: {}", status)),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = cancelled {
+            // Leave things as they were rather than run both jobs
+            if let Err(e) = send_cancel(&state, &api_response.job_id).await {
+                eprintln!("Failed to cancel replacement job {}: {}", api_response.job_id, e);
+            }
+            return Err(e.into());
+        }
+    }
+    
+    // Replace the old job in app state
+    state.processing_jobs.lock().await.remove(&job_id);
+    state.job_records.lock().await.remove(&job_id);
+    state.status_cache.lock().await.invalidate(&job_id);
+    result_cache::invalidate(&state, &job_id).await;
+    track_job(&state, &api_response.job_id, record.source, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, record.max_duration_secs).await;
+    // The replacement stays protected like the original
+    if record.pinned {
+        pins::set_pinned(&state, api_response.job_id.clone(), true).await?;
+    }
+    
+    Ok(api_response.job_id)
+}
+
+// Restore settings and job history saved by the previous session
+fn load_state(config_dir: PathBuf, data_dir: PathBuf) -> AppState {
+    let settings: Settings = persist::load_json(&config_dir.join(settings::SETTINGS_FILE))
+        .unwrap_or_default();
+    // A damaged file is salvaged rather than dropped, so one bad write can't wipe the history
+    let records = repair::load_records(&data_dir.join(records::RECORDS_FILE));
+    let saved_profiles: profiles::Profiles = persist::load_json(&config_dir.join(profiles::PROFILES_FILE))
+        .unwrap_or_default();
+    #[cfg(feature = "local-engine")]
+    let local_jobs: local_engine::LocalJobs = persist::load_json(&data_dir.join(local_engine::LOCAL_JOBS_FILE))
+        .unwrap_or_default();
+    let saved_sessions: submission_sessions::Sessions = persist::load_json(&data_dir.join(submission_sessions::SESSIONS_FILE))
+        .unwrap_or_default();
+    let queued: Vec<queue::QueueItem> = persist::load_json(&data_dir.join(queue::QUEUE_FILE))
+        .unwrap_or_default();
+    let job_groups: Vec<groups::JobGroup> = persist::load_json(&data_dir.join(groups::GROUPS_FILE))
+        .unwrap_or_default();
+    let processing_jobs = records.iter()
+        .filter(|record| !record.is_finished())
+        .map(|record| record.job_id.clone())
+        .collect();
+    let job_records = records.into_iter()
+        .map(|record| (record.job_id.clone(), record))
+        .collect();
+    
+    let findings = settings.check();
+    if !findings.is_empty() {
+        eprintln!("Found {} problem(s) in the settings:", findings.len());
+        for finding in &findings {
+            eprintln!("  {}", finding);
+        }
+    }
+    
+    // Fall back to a default client rather than refusing to start over bad settings
+    let connection = network::connect(&settings).unwrap_or_else(|e| {
+        eprintln!("{}, using defaults", e);
+        network::connect(&Settings::default()).unwrap_or_default()
+    });
+    let transfers = transfers::TransferLimiter::new(settings.transfer_priority, settings.transfer_buffer_budget_bytes);
+    let queue_concurrency = concurrency::AdaptiveLimit::new("queue", settings.min_concurrency, settings.max_concurrency);
+    
+    AppState {
+        connection: std::sync::RwLock::new(connection),
+        processing_jobs: Arc::new(Mutex::new(processing_jobs)),
+        job_records: Arc::new(Mutex::new(job_records)),
+        status_cache: Arc::new(Mutex::new(StatusCache::new(settings.status_cache_ttl()))),
+        cache_entries: result_cache::EntryLocks::default(),
+        settings: Arc::new(Mutex::new(settings)),
+        config_dir,
+        data_dir,
+        clock_offset_secs: Arc::new(Mutex::new(0)),
+        subscriptions: Arc::new(Mutex::new(HashMap::new())),
+        tasks: Arc::default(),
+        capabilities: Arc::new(Mutex::new(None)),
+        callbacks: Arc::new(Mutex::new(None)),
+        auth_refresh: Arc::new(Mutex::new(())),
+        app_handle: std::sync::OnceLock::new(),
+        queue: Arc::new(Mutex::new(queue::restore(queued))),
+        queue_concurrency: Arc::new(Mutex::new(queue_concurrency)),
+        groups: Arc::new(Mutex::new(
+            job_groups.into_iter().map(|group| (group.group_id.clone(), group)).collect(),
+        )),
+        log_feed: Arc::default(),
+        operations: Arc::default(),
+        transfers,
+        viewer_mode: viewer::enabled_at_startup(),
+        timings: timing::TimingLog::default(),
+        profiles: Arc::new(Mutex::new(saved_profiles)),
+        result_server: Arc::default(),
+        #[cfg(feature = "local-engine")]
+        local_jobs: Arc::new(Mutex::new(local_jobs)),
+        power: power::PowerMonitor::default(),
+        backend_status: Arc::default(),
+        progress_events: progress_events::ProgressEvents::default(),
+        sessions: Arc::new(Mutex::new(submission_sessions::restore(saved_sessions))),
+        shutting_down: std::sync::atomic::AtomicBool::new(false),
+    }
+}
+
+// Commands from optional features, which generate_handler! can't gate entry by entry
+#[cfg(feature = "local-engine")]
+fn with_feature_commands<R: tauri::Runtime>(
+    handler: impl Fn(tauri::Invoke<R>) + Send + Sync + 'static,
+) -> impl Fn(tauri::Invoke<R>) + Send + Sync + 'static {
+    local_engine::with_commands(handler)
+}
+
+#[cfg(not(feature = "local-engine"))]
+fn with_feature_commands<R: tauri::Runtime>(
+    handler: impl Fn(tauri::Invoke<R>) + Send + Sync + 'static,
+) -> impl Fn(tauri::Invoke<R>) + Send + Sync + 'static {
+    handler
+}
+
+fn main() {
+    let context = tauri::generate_context!();
+    
+    let fallback_dir = std::env::temp_dir().join("quickscript");
+    let config_dir = tauri::api::path::app_config_dir(context.config())
+        .unwrap_or_else(|| fallback_dir.clone());
+    let data_dir = tauri::api::path::app_data_dir(context.config())
+        .unwrap_or(fallback_dir);
+    
+    let app_state = load_state(config_dir, data_dir);
+    
     // Build Tauri application
     tauri::Builder::default()
         .manage(app_state)
-        .invoke_handler(tauri::generate_handler![
+        .setup(|app| {
+            let _ = app.state::<AppState>().app_handle.set(app.handle());
+            diagnostics::prewarm_in_background(app.handle());
+            clock::sync_in_background(app.handle());
+            storage::enforce_in_background(app.handle());
+            timeouts::watch_in_background(app.handle());
+            expiry::watch_in_background(app.handle());
+            idle::sweep_in_background(app.handle());
+            result_cache::verify_in_background(app.handle());
+            power::watch_in_background(app.handle());
+            progress_events::flush_in_background(app.handle());
+            // Queued items would otherwise still be submitted
+            if !app.state::<AppState>().viewer_mode {
+                queue::run_in_background(app.handle());
+            }
+            Ok(())
+        })
+        .invoke_handler(viewer::guard(with_feature_commands(tauri::generate_handler![
             upload_file,
+            upload_from_pipe,
+            upload_file_segment,
             process_url,
+            fetch::fetch_and_upload,
+            list_jobs,
+            validate_options,
+            reconcile::diff_with_backend,
+            reconcile::sync_jobs,
+            manifest::export_batch_manifest,
+            language::detect_language,
+            expiry::get_expiring_jobs,
+            idle::set_idle_expiry,
+            integrity::redownload_result,
+            logs::tail_all_logs,
+            logs::get_batch_log,
+            operations::cancel_download,
+            result_cache::cache_full_result,
+            result_cache::get_cached_result,
+            edits::save_transcript_edits,
+            edits::get_edited_transcript,
+            edits::revert_transcript_edits,
+            result_cache::verify_cache,
+            editors::export_to_format,
+            html::export_html,
+            improve::auto_improve,
+            clipboard::copy_transcript_to_clipboard,
+            repair::repair_records,
+            result_server::serve_result,
+            translation::get_job_outputs,
+            words::export_word_timings,
+            archive::download_and_extract_result,
+            pins::pin_job,
+            pins::unpin_job,
+            transfers::set_transfer_priority,
+            transfers::set_transfer_buffer_budget,
+            power::get_power_state,
+            power::override_transfer_pause,
+            backend_status::get_backend_status,
+            transcode::upload_file_transcoded,
+            silence::upload_file_trimmed,
             get_job_status,
+            get_last_progress,
+            job_exists,
+            get_job_warnings,
+            set_status_cache_ttl,
+            set_signing_key,
+            set_multipart_file_position,
+            shutdown::quit_gracefully,
+            result_versions::list_result_versions,
+            result_versions::get_best_result,
+            result_versions::set_result_ranking,
+            settings::validate_settings,
+            network::set_network_settings,
+            network::set_user_agent_suffix,
+            network::set_backend_url,
+            network::set_ca_bundle,
+            network::reload_tls_certs,
+            profiles::save_profile,
+            profiles::switch_profile,
+            profiles::list_profiles,
+            auth::set_credentials,
             download_result,
             read_file,
+            read_file_range,
+            read_url_text,
             cancel_job,
-        ])
-        .run(tauri::generate_context!())
-        .expect("Error while running Tauri application");
-}
\ No newline at end of file
+            cancel_jobs_where,
+            cancel_job_keep_partial,
+            restart_job,
+            queue::enqueue_job,
+            queue::list_queue,
+            queue::remove_from_queue,
+            queue::reorder_queue,
+            polling::subscribe_job,
+            polling::unsubscribe_job,
+            polling::set_poll_interval_bounds,
+            progress_events::set_progress_event_rate,
+            tasks::list_background_tasks,
+            tasks::kill_background_task,
+            timeouts::set_default_max_duration,
+            batch::upload_directory,
+            batch::download_results,
+            batch::download_results_zip,
+            batch::estimate_download_size,
+            batch::resume_batch_download,
+            postprocess::set_post_process,
+            postprocess::allow_post_process_program,
+            speed::get_realtime_factor,
+            speed::get_average_realtime_factor,
+            groups::create_job_group,
+            groups::list_job_groups,
+            groups::download_group_result,
+            presets::save_preset,
+            presets::list_presets,
+            presets::delete_preset,
+            capabilities::get_capabilities,
+            capabilities::supports_feature,
+            callbacks::enable_callbacks,
+            callbacks::disable_callbacks,
+            share::create_share_link,
+            share::revoke_share_link,
+            streaming::stream_transcript_to_file,
+            streaming::preview_transcript,
+            thumbnails::generate_thumbnail,
+            probe::probe_media,
+            transcript::get_confidence_summary,
+            search::search_transcript,
+            merge::merge_transcripts,
+            storage::get_storage_usage,
+            storage::set_storage_budget,
+            storage::enforce_storage_budget,
+            clock::sync_clock,
+            diagnostics::run_self_test,
+            diagnostics::measure_throughput,
+            diagnostics::benchmark_upload,
+            diagnostics::prewarm,
+            diagnostics::health_check,
+            diagnostics::test_backend,
+            timing::set_diagnostics,
+            timing::get_recent_timings,
+            session::save_session,
+            session::load_session,
+            submission_sessions::begin_session,
+            submission_sessions::list_sessions,
+            submission_sessions::get_session_jobs,
+            backup::export_state,
+            backup::import_state,
+            viewer::get_viewer_mode,
+        ])))
+        .build(context)
+        .expect("Error while building Tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                callbacks::shutdown(app);
+                result_server::shutdown(app);
+            }
+        });
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // State backed by its own temp directories; remove the returned directory when done
+    fn test_state(name: &str) -> (AppState, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("quickscript-test-{}-{}", std::process::id(), name));
+        (load_state(dir.join("config"), dir.join("data")), dir)
+    }
+
+    // Point the state at a local listener standing in for the backend
+    async fn mock_backend(state: &AppState) -> tokio::net::TcpListener {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut settings = state.settings.lock().await;
+        settings.api_url = format!("http://{}", listener.local_addr().unwrap());
+        state.set_connection(network::connect(&settings).unwrap());
+        listener
+    }
+
+    // Accept one request, answer it with a JSON body, and return what was sent
+    async fn serve_once(listener: tokio::net::TcpListener, body: &str) -> Vec<u8> {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 16 * 1024];
+        let (head_end, length) = loop {
+            let read = socket.read(&mut buffer).await.unwrap();
+            assert!(read > 0, "connection closed before the headers ended");
+            request.extend_from_slice(&buffer[..read]);
+            if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                let head = String::from_utf8_lossy(&request[..end]).to_ascii_lowercase();
+                let length = head.lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|value| value.trim().parse::<usize>().unwrap())
+                    .expect("uploads are sent with a length");
+                break (end + 4, length);
+            }
+        };
+        while request.len() < head_end + length {
+            let read = socket.read(&mut buffer).await.unwrap();
+            assert!(read > 0, "connection closed before the body ended");
+            request.extend_from_slice(&buffer[..read]);
+        }
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(), body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        request
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    // Field names of the multipart parts, in the order they were sent
+    fn part_names(request: &[u8]) -> Vec<String> {
+        let request = String::from_utf8_lossy(request);
+        request.split("Content-Disposition: form-data; name=\"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    // The file part goes where file_position says, with the text fields always in the same order
+    #[tokio::test]
+    async fn multipart_fields_keep_their_order() {
+        let fields = HashMap::from([
+            ("project".to_string(), "demo".to_string()),
+            ("batch".to_string(), "7".to_string()),
+        ]);
+        let form = UploadForm::new(Some("media".to_string()), Some(fields), None).unwrap();
+
+        for (position, expected) in [
+            (FilePosition::First, ["media", "options", "batch", "project"]),
+            (FilePosition::Last, ["options", "batch", "project", "media"]),
+        ] {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/process/file", listener.local_addr().unwrap());
+            let backend = tokio::spawn(async move { serve_once(listener, QUEUED).await });
+
+            let file_part = reqwest::multipart::Part::bytes(b"RIFF".to_vec()).file_name("clip.wav");
+            let multipart = form.build(file_part, "{}".to_string(), position);
+            reqwest::Client::new().post(url).multipart(multipart).send().await.unwrap();
+
+            assert_eq!(part_names(&backend.await.unwrap()), expected, "{:?}", position);
+        }
+    }
+
+    const QUEUED: &str = r#"{"job_id":"job-7","status":"queued","progress":0.0}"#;
+
+    // A cursor-backed reader is streamed to the backend whole, as the file part of the form
+    #[tokio::test]
+    async fn upload_stream_sends_reader() {
+        let (state, dir) = test_state("upload-stream");
+        let listener = mock_backend(&state).await;
+        let backend = tokio::spawn(async move { serve_once(listener, QUEUED).await });
+
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i % 241) as u8).collect();
+        let response = upload_stream(
+            &state,
+            std::io::Cursor::new(content.clone()),
+            None,
+            "pipe.wav".to_string(),
+            Some(content.len() as u64),
+            &ProcessOptions::default(),
+            &UploadForm::default(),
+        ).await.unwrap();
+        assert_eq!(response.job_id, "job-7");
+
+        let request = backend.await.unwrap();
+        assert!(request.starts_with(b"POST /process/file "));
+        assert!(contains(&request, b"filename=\"pipe.wav\""));
+        assert!(contains(&request, &content));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn status(job_id: &str, status: &str) -> JobStatusResponse {
+        JobStatusResponse {
+            job_id: job_id.to_string(),
+            status: status.to_string(),
+            progress: 1.0,
+            message: None,
+            result_url: None,
+            warnings: Vec::new(),
+            result_expires_at: None,
+        }
+    }
+
+    // A reprocess the backend answers with the same job ID leaves one job, and it stops
+    // counting as processing once it finishes
+    #[tokio::test]
+    async fn reused_job_id_is_tracked_once_until_finished() {
+        let (state, dir) = test_state("track");
+        let source = || JobSource::File { path: "/tmp/interview.wav".to_string() };
+
+        track_job(&state, "job-1", source(), ProcessOptions::default()).await;
+        let options = ProcessOptions { language: Some("de".to_string()), ..ProcessOptions::default() };
+        track_job(&state, "job-1", source(), options).await;
+
+        assert_eq!(state.processing_jobs.lock().await.len(), 1);
+        {
+            let records = state.job_records.lock().await;
+            assert_eq!(records.len(), 1);
+            assert_eq!(records["job-1"].options.language.as_deref(), Some("de"));
+        }
+
+        apply_status(&state, "job-1", &status("job-1", "processing")).await;
+        assert!(state.processing_jobs.lock().await.contains("job-1"));
+        apply_status(&state, "job-1", &status("job-1", "complete")).await;
+        assert!(state.processing_jobs.lock().await.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,139 @@
+// Signed manifests recording which inputs and options produced which results
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::records::{JobRecord, JobSource};
+use crate::{api, persist, AppError, AppState, ProcessOptions};
+
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct ManifestEntry {
+    job_id: String,
+    source: JobSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_hash: Option<String>,
+    options: ProcessOptions,
+    // SHA-256 of the JSON result
+    result_sha256: String,
+    // Model the backend reports in the result, else the one requested
+    model: Option<String>,
+    completed_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedJob {
+    job_id: String,
+    reason: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestBody {
+    version: u32,
+    generated_at: u64,
+    jobs: Vec<ManifestEntry>,
+    skipped: Vec<SkippedJob>,
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest {
+    #[serde(flatten)]
+    body: ManifestBody,
+    // HMAC-SHA256 over the compact JSON of everything else, when a signing key is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestReport {
+    path: String,
+    included: usize,
+    skipped: Vec<SkippedJob>,
+    signed: bool,
+}
+
+async fn entry_for(state: &AppState, job_id: &str) -> Result<ManifestEntry, String> {
+    let mut record: Option<JobRecord> = state.job_records.lock().await.get(job_id).cloned();
+
+    // Confirm with the backend rather than trusting a possibly stale record
+    if !record.as_ref().is_some_and(|record| record.status == "complete") {
+        let status = crate::fetch_status(state, job_id).await.map_err(|e| e.to_string())?;
+        if status.status != "complete" {
+            return Err(format!("Job is {}, not complete", status.status));
+        }
+        record = state.job_records.lock().await.get(job_id).cloned();
+    }
+    let record = record.ok_or_else(|| "Job is not tracked locally, so its source is unknown".to_string())?;
+
+    let result = crate::fetch_result(state, job_id, "json", &[]).await.map_err(|e| e.to_string())?;
+    let model = serde_json::from_slice::<serde_json::Value>(&result)
+        .ok()
+        .and_then(|value| value.get("model").and_then(|model| model.as_str()).map(str::to_string))
+        .or_else(|| record.options.model.clone());
+
+    Ok(ManifestEntry {
+        job_id: record.job_id,
+        source: record.source,
+        source_hash: record.source_hash,
+        options: record.options,
+        result_sha256: hex::encode(Sha256::digest(&result)),
+        model,
+        completed_at: record.completed_at,
+    })
+}
+
+// Write a manifest of the given jobs; jobs that aren't complete are listed as skipped
+#[tauri::command]
+pub async fn export_batch_manifest(
+    state: State<'_, AppState>,
+    job_ids: Vec<String>,
+    dest_path: String,
+    sign: Option<bool>,
+) -> Result<ManifestReport, AppError> {
+    let mut jobs = Vec::new();
+    let mut skipped = Vec::new();
+    for job_id in job_ids {
+        match entry_for(&state, &job_id).await {
+            Ok(entry) => jobs.push(entry),
+            Err(reason) => skipped.push(SkippedJob { job_id, reason }),
+        }
+    }
+    if jobs.is_empty() {
+        return Err("None of the jobs could be included in the manifest".to_string().into());
+    }
+
+    let body = ManifestBody {
+        version: MANIFEST_VERSION,
+        generated_at: crate::records::unix_now(),
+        jobs,
+        skipped: skipped.clone(),
+    };
+    // Signed whenever a key is set, unless the caller opts out
+    let key = match sign {
+        Some(false) => None,
+        _ => state.settings.lock().await.signing_key.clone(),
+    };
+    if sign == Some(true) && key.is_none() {
+        return Err("No signing key is set".to_string().into());
+    }
+    let signature = match key {
+        Some(key) => {
+            let bytes = serde_json::to_vec(&body).map_err(|e| format!("Failed to encode manifest: {}", e))?;
+            Some(api::hmac_hex(key.as_bytes(), &bytes))
+        }
+        None => None,
+    };
+
+    let included = body.jobs.len();
+    let signed = signature.is_some();
+    persist::save_json(std::path::Path::new(&dest_path), &Manifest { body, signature }).await?;
+
+    Ok(ManifestReport {
+        path: dest_path,
+        included,
+        skipped,
+        signed,
+    })
+}
@@ -0,0 +1,330 @@
+// Local media helpers built on ffmpeg/ffprobe
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+
+// Resolve a media tool, preferring a copy bundled next to the executable
+pub fn tool_path(name: &str) -> PathBuf {
+    let file_name = if cfg!(windows) {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    };
+
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        let bundled = dir.join(&file_name);
+        if bundled.exists() {
+            return bundled;
+        }
+    }
+
+    PathBuf::from(file_name)
+}
+
+// Temporary file that is removed when dropped
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    pub fn new(prefix: &str, extension: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let name = format!("quickscript-{}-{}-{}.{}", prefix, std::process::id(), nanos, extension);
+
+        TempFile {
+            path: std::env::temp_dir().join(name),
+        }
+    }
+
+    // Track an existing path so it is removed unless kept
+    pub fn at(path: PathBuf) -> Self {
+        TempFile { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// Probe the duration of a media file in seconds
+pub async fn probe_duration(path: &Path) -> Result<f64, String> {
+    let output = Command::new(tool_path("ffprobe"))
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=noprint_wrappers=1:nokey=1"])
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to probe media: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| "Media duration is unknown".to_string())
+}
+
+// Copy the [start, end) time range of a media file into dest
+pub async fn extract_segment(source: &Path, start_secs: f64, end_secs: f64, dest: &Path) -> Result<(), String> {
+    let output = Command::new(tool_path("ffmpeg"))
+        .args(["-v", "error", "-y", "-ss", &start_secs.to_string(), "-to", &end_secs.to_string(), "-i"])
+        .arg(source)
+        .args(["-c", "copy"])
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract segment: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+// Whether a media file has a video stream
+pub async fn has_video(path: &Path) -> Result<bool, String> {
+    let output = Command::new(tool_path("ffprobe"))
+        .args(["-v", "error", "-select_streams", "v", "-show_entries", "stream=codec_type", "-of", "csv=p=0"])
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to probe media: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(!output.stdout.trim_ascii().is_empty())
+}
+
+// Write a PNG of the video frame at at_secs, scaled to width
+pub async fn extract_frame(source: &Path, at_secs: f64, width: u32, dest: &Path) -> Result<(), String> {
+    let output = Command::new(tool_path("ffmpeg"))
+        .args(["-v", "error", "-y", "-ss", &at_secs.to_string(), "-i"])
+        .arg(source)
+        .args(["-frames:v", "1", "-vf", &format!("scale={}:-2", width), "-c:v", "png"])
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract frame: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+// Write a PNG waveform of the whole audio track
+pub async fn render_waveform(source: &Path, width: u32, height: u32, dest: &Path) -> Result<(), String> {
+    let output = Command::new(tool_path("ffmpeg"))
+        .args(["-v", "error", "-y", "-i"])
+        .arg(source)
+        .args(["-filter_complex", &format!("showwavespic=s={}x{}", width, height), "-frames:v", "1", "-c:v", "png"])
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to render waveform: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+// Re-encode the audio track of source into dest with the given ffmpeg codec arguments
+pub async fn transcode_audio(source: &Path, codec_args: &[String], dest: &Path) -> Result<(), String> {
+    let output = Command::new(tool_path("ffmpeg"))
+        .args(["-v", "error", "-y", "-i"])
+        .arg(source)
+        .arg("-vn")
+        .args(codec_args)
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to transcode audio: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+// Silent stretches of the audio track as (start, end) seconds; a silence still running
+// at the end of the media has no end
+pub async fn detect_silences(source: &Path, threshold_db: f64, min_secs: f64) -> Result<Vec<(f64, Option<f64>)>, String> {
+    let output = Command::new(tool_path("ffmpeg"))
+        .args(["-v", "info", "-nostats", "-i"])
+        .arg(source)
+        .args(["-vn", "-af", &format!("silencedetect=noise={}dB:d={}", threshold_db, min_secs), "-f", "null", "-"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    let log = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!("Failed to detect silence: {}", log.lines().last().unwrap_or_default().trim()));
+    }
+
+    // silencedetect logs "silence_start: 1.5" and "silence_end: 3.2 | silence_duration: 1.7"
+    let value = |line: &str, key: &str| -> Option<f64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        rest.split_whitespace().next()?.parse().ok()
+    };
+    let mut silences = Vec::new();
+    for line in log.lines() {
+        if let Some(start) = value(line, "silence_start: ") {
+            silences.push((start.max(0.0), None));
+        } else if let Some(end) = value(line, "silence_end: ") {
+            if let Some((_, open @ None)) = silences.last_mut() {
+                *open = Some(end);
+            }
+        }
+    }
+    Ok(silences)
+}
+
+// Write only the given (start, end) ranges of the audio track to dest, joined end to end
+pub async fn keep_audio_ranges(source: &Path, ranges: &[(f64, f64)], dest: &Path) -> Result<(), String> {
+    let selection = ranges.iter()
+        .map(|(start, end)| format!("between(t,{:.3},{:.3})", start, end))
+        .collect::<Vec<_>>()
+        .join("+");
+    let output = Command::new(tool_path("ffmpeg"))
+        .args(["-v", "error", "-y", "-i"])
+        .arg(source)
+        .args(["-vn", "-af", &format!("aselect='{}',asetpts=N/SR/TB", selection), "-c:a", "flac"])
+        .arg(dest)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to trim audio: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+// Chapter marker carried by the container
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Chapter {
+    pub start_secs: f64,
+    pub end_secs: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+// Container metadata; every field is optional since most files carry little of it
+#[derive(Debug, Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub creation_time: Option<String>,
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(serde::Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    format: ProbeFormat,
+    #[serde(default)]
+    chapters: Vec<ProbeChapter>,
+}
+
+#[derive(Default, serde::Deserialize)]
+struct ProbeFormat {
+    #[serde(default)]
+    tags: std::collections::HashMap<String, String>,
+}
+
+#[derive(serde::Deserialize)]
+struct ProbeChapter {
+    start_time: String,
+    end_time: String,
+    #[serde(default)]
+    tags: std::collections::HashMap<String, String>,
+}
+
+// Tag lookup ignoring case, since containers disagree on it
+fn tag(tags: &std::collections::HashMap<String, String>, name: &str) -> Option<String> {
+    tags.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// Read the title, creation time and chapters of a media file
+pub async fn probe_metadata(path: &Path) -> Result<Metadata, String> {
+    let output = Command::new(tool_path("ffprobe"))
+        .args(["-v", "error", "-show_entries", "format_tags", "-show_chapters", "-of", "json"])
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to probe media: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let probe: ProbeOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+
+    // Skip markers with unparseable or empty ranges rather than failing the probe
+    let chapters = probe
+        .chapters
+        .iter()
+        .filter_map(|chapter| {
+            let start_secs = chapter.start_time.parse::<f64>().ok()?;
+            let end_secs = chapter.end_time.parse::<f64>().ok()?;
+            (start_secs.is_finite() && end_secs > start_secs).then(|| Chapter {
+                start_secs: start_secs.max(0.0),
+                end_secs,
+                title: tag(&chapter.tags, "title"),
+            })
+        })
+        .collect();
+
+    Ok(Metadata {
+        title: tag(&probe.format.tags, "title"),
+        creation_time: tag(&probe.format.tags, "creation_time"),
+        chapters,
+    })
+}
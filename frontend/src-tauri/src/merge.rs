@@ -0,0 +1,139 @@
+// Joining transcripts of a split recording into one document, entirely client-side
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::transcript::{Segment, Transcript};
+use crate::{subtitles, AppError};
+
+// Formats with timestamps that can be read back, and those that can be written
+const INPUT_FORMATS: &[&str] = &["json", "srt", "vtt"];
+const MERGE_FORMATS: &[&str] = &["json", "srt", "vtt", "txt", "md"];
+
+// What to do where one part's segments run into the next part's
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryPolicy {
+    // Use the offsets as given, overlaps and gaps included
+    #[default]
+    Keep,
+    // Clip segments that start before the previous part ended, dropping any left empty
+    Trim,
+    // Push a part later so it starts no earlier than the previous part ended
+    Shift,
+    // Butt each part against the previous one, removing gaps as well as overlaps
+    Close,
+}
+
+fn format_of(path: &str) -> Result<String, String> {
+    let format = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .ok_or_else(|| format!("Can't tell the format of {}", path))?;
+    if !INPUT_FORMATS.contains(&format.as_str()) {
+        return Err(format!("Can't merge {} files; expected one of: {}", format, INPUT_FORMATS.join(", ")));
+    }
+    Ok(format)
+}
+
+async fn read_segments(path: &str, format: &str) -> Result<(Vec<Segment>, Option<String>), String> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if format == "json" {
+        let transcript: Transcript = serde_json::from_str(&text)
+            .map_err(|e| format!("Failed to parse {}: {}", path, e))?;
+        Ok((transcript.segments, transcript.language))
+    } else {
+        Ok((subtitles::parse_cues(&text).map_err(|e| format!("{}: {}", path, e))?, None))
+    }
+}
+
+pub fn render(segments: Vec<Segment>, language: Option<String>, format: &str) -> Result<String, String> {
+    let text_only = || segments.iter().map(|segment| segment.text.trim()).collect::<Vec<_>>();
+    Ok(match format {
+        "json" => {
+            let transcript = Transcript {
+                text: text_only().join(" "),
+                segments: segments.clone(),
+                language,
+            };
+            serde_json::to_string_pretty(&transcript).map_err(|e| format!("Failed to encode transcript: {}", e))?
+        }
+        "srt" => subtitles::write_srt(&segments),
+        "vtt" => subtitles::write_vtt(&segments),
+        "txt" => text_only().join("\n") + "\n",
+        "md" => segments
+            .iter()
+            .map(|segment| format!("**[{}]** {}", subtitles::format_timestamp(segment.start, '.'), segment.text.trim()))
+            .collect::<Vec<_>>()
+            .join("\n\n") + "\n",
+        other => return Err(format!("Unsupported output format '{}'", other)),
+    })
+}
+
+// Shift each part by its offset and join them in order, resolving boundaries per the policy
+#[tauri::command]
+pub async fn merge_transcripts(
+    inputs: Vec<(String, f64)>,
+    output_format: String,
+    dest_path: String,
+    policy: Option<BoundaryPolicy>,
+) -> Result<usize, AppError> {
+    if inputs.is_empty() {
+        return Err("No transcripts to merge".to_string().into());
+    }
+    if !MERGE_FORMATS.contains(&output_format.as_str()) {
+        return Err(format!("Unsupported output format '{}'; expected one of: {}", output_format, MERGE_FORMATS.join(", ")).into());
+    }
+    let policy = policy.unwrap_or_default();
+
+    let input_format = format_of(&inputs[0].0)?;
+    let mut merged: Vec<Segment> = Vec::new();
+    let mut language = None;
+    for (path, offset_secs) in &inputs {
+        let format = format_of(path)?;
+        if format != input_format {
+            return Err(format!("All inputs must be {} files, but {} is {}", input_format, path, format).into());
+        }
+        if !offset_secs.is_finite() || *offset_secs < 0.0 {
+            return Err(format!("Invalid offset {} for {}", offset_secs, path).into());
+        }
+
+        let (segments, part_language) = read_segments(path, &format).await?;
+        language = language.or(part_language);
+        let previous_end = merged.last().map(|segment| segment.end).unwrap_or(0.0);
+        let first_start = segments.first().map(|segment| segment.start).unwrap_or(0.0);
+        let offset = match policy {
+            BoundaryPolicy::Keep | BoundaryPolicy::Trim => *offset_secs,
+            BoundaryPolicy::Shift => offset_secs.max(previous_end - first_start),
+            BoundaryPolicy::Close if merged.is_empty() => *offset_secs,
+            BoundaryPolicy::Close => previous_end - first_start,
+        };
+
+        for mut segment in segments {
+            segment.start += offset;
+            segment.end += offset;
+            for word in &mut segment.words {
+                word.start += offset;
+                word.end += offset;
+            }
+            if matches!(policy, BoundaryPolicy::Trim) && segment.start < previous_end {
+                if segment.end <= previous_end {
+                    continue;
+                }
+                segment.start = previous_end;
+                segment.words.retain(|word| word.start >= previous_end);
+            }
+            merged.push(segment);
+        }
+    }
+
+    let count = merged.len();
+    let contents = render(merged, language, &output_format)?;
+    tokio::fs::write(&dest_path, contents)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(count)
+}
@@ -0,0 +1,199 @@
+// HTTP client construction from the user's network settings
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::settings::Settings;
+use crate::{AppError, AppState};
+
+// Which address family outgoing connections use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpFamily {
+    // Let the resolver's order decide, falling back across families
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+impl IpFamily {
+    pub fn matches(self, address: &IpAddr) -> bool {
+        match self {
+            IpFamily::Auto => true,
+            IpFamily::Ipv4 => address.is_ipv4(),
+            IpFamily::Ipv6 => address.is_ipv6(),
+        }
+    }
+}
+
+// Source address to bind, which also pins the family when only a family is chosen
+fn bind_address(settings: &Settings) -> Option<IpAddr> {
+    settings.local_address.or(match settings.ip_family {
+        IpFamily::Auto => None,
+        IpFamily::Ipv4 => Some(Ipv4Addr::UNSPECIFIED.into()),
+        IpFamily::Ipv6 => Some(Ipv6Addr::UNSPECIFIED.into()),
+    })
+}
+
+// Identifies the app and version in backend logs, plus any user-chosen suffix
+fn user_agent(settings: &Settings) -> String {
+    let base = format!("QuickScript/{} ({})", env!("CARGO_PKG_VERSION"), std::env::consts::OS);
+    match &settings.user_agent_suffix {
+        Some(suffix) => format!("{} {}", base, suffix),
+        None => base,
+    }
+}
+
+// Extra root certificates from the configured PEM bundle, read fresh each time
+pub fn load_ca_bundle(settings: &Settings) -> Result<Vec<reqwest::Certificate>, String> {
+    let Some(path) = &settings.ca_bundle_path else {
+        return Ok(Vec::new());
+    };
+    let pem = std::fs::read(path).map_err(|e| format!("Failed to read CA bundle {}: {}", path, e))?;
+    let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("Invalid CA bundle {}: {}", path, e))?;
+    if certificates.is_empty() {
+        return Err(format!("CA bundle {} contains no certificates", path));
+    }
+    Ok(certificates)
+}
+
+// The client and how many custom root certificates it trusts
+fn build_client(settings: &Settings) -> Result<(reqwest::Client, usize), String> {
+    let certificates = load_ca_bundle(settings)?;
+    let count = certificates.len();
+    let builder = certificates.into_iter().fold(reqwest::Client::builder(), |builder, certificate| {
+        builder.add_root_certificate(certificate)
+    });
+    let client = builder
+        .user_agent(user_agent(settings))
+        .local_address(bind_address(settings))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    Ok((client, count))
+}
+
+// A client together with the backend it talks to
+#[derive(Debug, Clone, Default)]
+pub struct Connection {
+    pub client: reqwest::Client,
+    pub api_url: String,
+    // Certificates loaded from the custom CA bundle
+    pub ca_certificates: usize,
+}
+
+// Backend base URL from the settings, checked and without a trailing slash
+pub fn api_url(settings: &Settings) -> Result<String, String> {
+    let url = reqwest::Url::parse(settings.api_url.trim())
+        .map_err(|e| format!("Invalid backend URL '{}': {}", settings.api_url, e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!("Backend URL '{}' must be an http or https address", settings.api_url));
+    }
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+pub fn connect(settings: &Settings) -> Result<Connection, String> {
+    let (client, ca_certificates) = build_client(settings)?;
+    Ok(Connection {
+        client,
+        api_url: api_url(settings)?,
+        ca_certificates,
+    })
+}
+
+pub fn address_family(address: &IpAddr) -> &'static str {
+    if address.is_ipv4() {
+        "ipv4"
+    } else {
+        "ipv6"
+    }
+}
+
+// Validate and apply changed settings, keeping the old ones if the client can't be built.
+// Requests already sent hold their own handle to the old client and finish on it.
+async fn apply(state: &AppState, update: impl FnOnce(&mut Settings)) -> Result<usize, AppError> {
+    let ca_certificates = {
+        let mut settings = state.settings.lock().await;
+        let mut updated = settings.clone();
+        update(&mut updated);
+        let connection = connect(&updated)?;
+        let ca_certificates = connection.ca_certificates;
+        *settings = updated;
+        state.set_connection(connection);
+        ca_certificates
+    };
+    crate::persist_settings(state).await?;
+    Ok(ca_certificates)
+}
+
+#[tauri::command]
+pub async fn set_network_settings(
+    state: State<'_, AppState>,
+    local_address: Option<String>,
+    ip_family: Option<IpFamily>,
+) -> Result<(), AppError> {
+    let local_address = local_address
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            address.parse::<IpAddr>()
+                .map_err(|_| format!("Invalid local address: '{}'", address))
+        })
+        .transpose()?;
+    let ip_family = ip_family.unwrap_or_default();
+    if let Some(address) = &local_address {
+        if !ip_family.matches(address) {
+            return Err(format!("Local address {} is not an {:?} address", address, ip_family).into());
+        }
+    }
+
+    apply(&state, |settings| {
+        settings.local_address = local_address;
+        settings.ip_family = ip_family;
+    })
+    .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_user_agent_suffix(
+    state: State<'_, AppState>,
+    suffix: Option<String>,
+) -> Result<(), AppError> {
+    let suffix = suffix.map(|suffix| suffix.trim().to_string()).filter(|suffix| !suffix.is_empty());
+    if let Some(suffix) = &suffix {
+        let valid = suffix.len() <= 64 && suffix.chars().all(|c| c.is_ascii_graphic() || c == ' ');
+        if !valid {
+            return Err(format!("Invalid user agent suffix: '{}'", suffix).into());
+        }
+    }
+
+    apply(&state, |settings| settings.user_agent_suffix = suffix).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_backend_url(state: State<'_, AppState>, url: String) -> Result<(), AppError> {
+    apply(&state, |settings| settings.api_url = url.trim().to_string()).await?;
+    // The cached load belongs to the previous backend
+    *state.backend_status.lock().await = None;
+    Ok(())
+}
+
+// Trust the certificates in a PEM bundle in addition to the system roots; None removes it
+#[tauri::command]
+pub async fn set_ca_bundle(state: State<'_, AppState>, path: Option<String>) -> Result<usize, AppError> {
+    let path = path.map(|path| path.trim().to_string()).filter(|path| !path.is_empty());
+    apply(&state, |settings| settings.ca_bundle_path = path).await
+}
+
+// Re-read the configured CA bundle, e.g. after the PKI rotated it, without restarting
+#[tauri::command]
+pub async fn reload_tls_certs(state: State<'_, AppState>) -> Result<usize, AppError> {
+    if state.settings.lock().await.ca_bundle_path.is_none() {
+        return Err("No CA bundle is configured".to_string().into());
+    }
+    apply(&state, |_| {}).await
+}
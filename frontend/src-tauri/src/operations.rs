@@ -0,0 +1,51 @@
+// Foreground operations the UI can cancel, identified by a token it picks
+
+use std::future::Future;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::{AppError, AppState};
+
+// Run future as a background task the UI can cancel through op_id.
+// Without an op_id it just runs inline.
+pub async fn run<T, F>(
+    app: &AppHandle,
+    op_id: Option<String>,
+    kind: TaskKind,
+    job_id: Option<String>,
+    future: F,
+) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T, AppError>> + Send + 'static,
+{
+    let Some(op_id) = op_id else {
+        return future.await;
+    };
+    let state = app.state::<AppState>();
+
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    {
+        let mut operations = state.operations.lock().await;
+        if operations.contains_key(&op_id) {
+            return Err(format!("Operation {} is already running", op_id).into());
+        }
+        let id = tasks::spawn(&state.tasks, kind, job_id, async move {
+            let _ = sender.send(future.await);
+        });
+        operations.insert(op_id.clone(), id);
+    }
+
+    // The sender is dropped unsent when the task is aborted
+    let result = receiver.await;
+    state.operations.lock().await.remove(&op_id);
+    result.unwrap_or(Err(AppError::Cancelled { op_id }))
+}
+
+// Abort a download started with this op_id; its partial file is removed and the job is left alone
+#[tauri::command]
+pub async fn cancel_download(state: State<'_, AppState>, op_id: String) -> Result<bool, AppError> {
+    let id = state.operations.lock().await.get(&op_id).copied();
+    Ok(id.is_some_and(|id| tasks::kill(&state.tasks, id)))
+}
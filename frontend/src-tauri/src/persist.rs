@@ -0,0 +1,38 @@
+// JSON files kept in the app config and data directories
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::{io_error, AppError};
+
+// Load a JSON file, treating a missing or unreadable file as absent
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("Ignoring unreadable {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// Write a JSON file atomically so a crash never leaves it half-written
+pub async fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| io_error("Failed to create directory", parent, e))?;
+    }
+
+    let contents = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Failed to encode {}: {}", path.display(), e))?;
+    let temp_path = path.with_extension("json.tmp");
+    tokio::fs::write(&temp_path, contents)
+        .await
+        .map_err(|e| io_error("Failed to write file", path, e))?;
+    tokio::fs::rename(&temp_path, path)
+        .await
+        .map_err(|e| io_error("Failed to write file", path, e))
+}
@@ -0,0 +1,35 @@
+// Pinned jobs, whose results are kept out of automatic cleanup
+
+use std::collections::HashSet;
+
+use tauri::State;
+
+use crate::{AppError, AppState};
+
+// Jobs whose cached results and downloads must never be evicted
+pub async fn pinned_jobs(state: &AppState) -> HashSet<String> {
+    state.job_records.lock().await
+        .values()
+        .filter(|record| record.pinned)
+        .map(|record| record.job_id.clone())
+        .collect()
+}
+
+pub async fn set_pinned(state: &AppState, job_id: String, pinned: bool) -> Result<(), AppError> {
+    match state.job_records.lock().await.get_mut(&job_id) {
+        Some(record) => record.pinned = pinned,
+        None => return Err(AppError::JobNotFound { job_id }),
+    }
+    crate::persist_records(state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pin_job(state: State<'_, AppState>, job_id: String) -> Result<(), AppError> {
+    set_pinned(&state, job_id, true).await
+}
+
+#[tauri::command]
+pub async fn unpin_job(state: State<'_, AppState>, job_id: String) -> Result<(), AppError> {
+    set_pinned(&state, job_id, false).await
+}
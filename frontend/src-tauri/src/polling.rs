@@ -0,0 +1,122 @@
+// Background pollers that push job status to the UI as events
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::{progress_events, records, timeouts, AppError, AppState, JobStatusResponse};
+
+// Progress at which a job is close enough to done to poll at full speed
+const NEAR_COMPLETE_PROGRESS: f32 = 0.9;
+
+// Poll interval that speeds up while a job moves and backs off while it stalls
+struct AdaptiveInterval {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+    last: Option<(String, f32)>,
+}
+
+impl AdaptiveInterval {
+    fn new(min: Duration, max: Duration) -> Self {
+        AdaptiveInterval {
+            min,
+            max,
+            current: min,
+            last: None,
+        }
+    }
+
+    fn next(&mut self, status: &JobStatusResponse) -> Duration {
+        self.current = match &self.last {
+            // Status changes are the moments the UI cares about most
+            Some((last_status, _)) if *last_status != status.status => self.min,
+            _ if status.progress >= NEAR_COMPLETE_PROGRESS => self.min,
+            Some((_, last_progress)) if status.progress > *last_progress => self.current / 2,
+            Some(_) => self.current * 2,
+            None => self.min,
+        }
+        .clamp(self.min, self.max);
+
+        self.last = Some((status.status.clone(), status.progress));
+        self.current
+    }
+
+    // Back off after a failed poll
+    fn backoff(&mut self) -> Duration {
+        self.current = (self.current * 2).clamp(self.min, self.max);
+        self.current
+    }
+}
+
+async fn poll_job(app: &AppHandle, job_id: &str) {
+    let state = app.state::<AppState>();
+    let (min, max) = state.settings.lock().await.poll_interval_bounds();
+    let mut interval = AdaptiveInterval::new(min, max);
+
+    loop {
+        let delay = match crate::fetch_status(&state, job_id).await {
+            Ok(status) => {
+                progress_events::emit(app, &state, &status).await;
+                if records::is_finished_status(&status.status) || timeouts::check(app, &state, job_id, &status).await {
+                    return;
+                }
+                interval.next(&status)
+            }
+            Err(e @ AppError::JobNotFound { .. }) => {
+                let _ = app.emit_all("job-poll-error", &e);
+                return;
+            }
+            Err(_) => interval.backoff(),
+        };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[tauri::command]
+pub async fn subscribe_job(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<(), AppError> {
+    let mut subscriptions = state.subscriptions.lock().await;
+    if subscriptions.get(&job_id).is_some_and(|id| tasks::is_running(&state.tasks, *id)) {
+        return Ok(());
+    }
+
+    let task_job_id = job_id.clone();
+    let task_id = tasks::spawn(&state.tasks, TaskKind::Poller, Some(job_id.clone()), async move {
+        poll_job(&app, &task_job_id).await;
+        app.state::<AppState>().subscriptions.lock().await.remove(&task_job_id);
+    });
+    subscriptions.insert(job_id, task_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unsubscribe_job(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<bool, AppError> {
+    let task_id = state.subscriptions.lock().await.remove(&job_id);
+    Ok(task_id.is_some_and(|id| tasks::kill(&state.tasks, id)))
+}
+
+#[tauri::command]
+pub async fn set_poll_interval_bounds(
+    state: State<'_, AppState>,
+    min_ms: u64,
+    max_ms: u64,
+) -> Result<(), AppError> {
+    if min_ms == 0 || min_ms > max_ms {
+        return Err(format!("Invalid poll interval bounds: {}ms to {}ms", min_ms, max_ms).into());
+    }
+
+    {
+        let mut settings = state.settings.lock().await;
+        settings.poll_min_interval_ms = min_ms;
+        settings.poll_max_interval_ms = max_ms;
+    }
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
@@ -0,0 +1,250 @@
+// Opt-in local script run on each completed result
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::api::process::{Command, CommandEvent};
+use tauri::{AppHandle, Manager, State, Window};
+
+use crate::tasks::{self, TaskKind};
+use crate::{audit, integrity, AppError, AppState};
+
+// Where results are saved for the script when no folder is configured
+const POSTPROCESS_DIR: &str = "postprocess";
+const DEFAULT_FORMAT: &str = "md";
+
+// A hung script is killed rather than left running unseen
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// Output kept from each stream for the event and the audit log
+const MAX_OUTPUT_CHARS: usize = 8 * 1024;
+
+// Placeholders substituted in the argument template
+const PATH_PLACEHOLDER: &str = "{path}";
+const JOB_ID_PLACEHOLDER: &str = "{job_id}";
+const FORMAT_PLACEHOLDER: &str = "{format}";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostProcessConfig {
+    // Absolute path of the executable; it must also be on the allowlist
+    pub program: String,
+    // Argument template; the result path is appended when no argument uses {path}
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub dest_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PostProcessEvent {
+    job_id: String,
+    program: String,
+    path: Option<String>,
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    error: Option<String>,
+}
+
+// Canonical form used for allowlist comparisons, so symlinks and ".." can't slip past
+pub fn canonical_program(program: &str) -> Result<PathBuf, AppError> {
+    let path = Path::new(program);
+    if !path.is_absolute() {
+        return Err(format!("Post-processing program '{}' must be an absolute path", program).into());
+    }
+    let canonical = path.canonicalize()
+        .map_err(|e| crate::error::io_error("Failed to find post-processing program", path, e))?;
+    if !canonical.is_file() {
+        return Err(format!("Post-processing program '{}' is not a file", program).into());
+    }
+    Ok(canonical)
+}
+
+pub fn is_allowed(allowlist: &[String], program: &Path) -> bool {
+    allowlist.iter().any(|allowed| Path::new(allowed) == program)
+}
+
+// Add a line of output, keeping at most MAX_OUTPUT_CHARS
+fn push_line(output: &mut String, line: &str) {
+    let room = MAX_OUTPUT_CHARS.saturating_sub(output.chars().count());
+    output.extend(line.trim_end_matches(['\r', '\n']).chars().chain(std::iter::once('\n')).take(room));
+}
+
+fn expand_args(template: &[String], path: &Path, job_id: &str, format: &str) -> Vec<String> {
+    let path = path.to_string_lossy();
+    let mut args: Vec<String> = template
+        .iter()
+        .map(|arg| {
+            arg.replace(PATH_PLACEHOLDER, &path)
+                .replace(JOB_ID_PLACEHOLDER, job_id)
+                .replace(FORMAT_PLACEHOLDER, format)
+        })
+        .collect();
+    if !template.iter().any(|arg| arg.contains(PATH_PLACEHOLDER)) {
+        args.push(path.to_string());
+    }
+    args
+}
+
+// Download the result and run the configured program on it
+async fn run(app: &AppHandle, job_id: &str, config: PostProcessConfig, event: &mut PostProcessEvent) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let program = canonical_program(&config.program)?;
+    // Re-checked at run time in case the allowlist changed since the config was saved
+    if !is_allowed(&state.settings.lock().await.post_process_allowlist, &program) {
+        return Err(format!("{} is not on the post-processing allowlist", program.display()).into());
+    }
+
+    let format = config.format.unwrap_or_else(|| DEFAULT_FORMAT.to_string());
+    let dest_dir = config.dest_dir
+        .map(PathBuf::from)
+        .unwrap_or_else(|| state.data_dir.join(POSTPROCESS_DIR));
+    tokio::fs::create_dir_all(&dest_dir)
+        .await
+        .map_err(|e| crate::error::io_error("Failed to create folder", &dest_dir, e))?;
+    let path = dest_dir.join(format!("{}.{}", job_id, format));
+    let download = integrity::download_verified(&state, job_id, &format, &[], &path).await?;
+    if !download.verified {
+        return Err(format!(
+            "Result for job {} failed verification: {}",
+            job_id,
+            download.problem.unwrap_or_default()
+        ).into());
+    }
+    crate::record_download(&state, job_id, &path.to_string_lossy()).await;
+    event.path = Some(path.to_string_lossy().to_string());
+
+    // Arguments are passed directly, never through a shell
+    let (mut events, child) = Command::new(program.to_string_lossy())
+        .args(expand_args(&config.args, &path, job_id, &format))
+        .current_dir(dest_dir.clone())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", program.display(), e))?;
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    let finished = async {
+        while let Some(event) = events.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => push_line(&mut stdout, &line),
+                CommandEvent::Stderr(line) => push_line(&mut stderr, &line),
+                // Output that isn't valid UTF-8, or a failure waiting on the process
+                CommandEvent::Error(e) => push_line(&mut stderr, &e),
+                CommandEvent::Terminated(exit) => return Some(exit),
+                _ => {}
+            }
+        }
+        None
+    };
+    let exit = match tokio::time::timeout(SCRIPT_TIMEOUT, finished).await {
+        Ok(Some(exit)) => exit,
+        Ok(None) => return Err(format!("Lost track of {} before it exited", program.display()).into()),
+        Err(_) => {
+            let _ = child.kill();
+            return Err(format!("{} did not finish within {}s", program.display(), SCRIPT_TIMEOUT.as_secs()).into());
+        }
+    };
+
+    event.exit_code = exit.code;
+    event.stdout = stdout;
+    event.stderr = stderr;
+    match (exit.code, exit.signal) {
+        (Some(0), _) => Ok(()),
+        (Some(code), _) => Err(format!("{} exited with code {}", program.display(), code).into()),
+        (None, signal) => Err(format!("{} was killed by signal {}", program.display(), signal.unwrap_or_default()).into()),
+    }
+}
+
+// Start the post-processing script for a job that just completed, if one is configured.
+// Not async, so the status path that calls it doesn't wrap the download it triggers.
+pub fn on_complete(state: &AppState, job_id: &str) {
+    let Some(app) = state.app_handle.get().cloned() else {
+        return;
+    };
+
+    let job_id = job_id.to_string();
+    tasks::spawn(&state.tasks, TaskKind::PostProcess, Some(job_id.clone()), async move {
+        let state = app.state::<AppState>();
+        let Some(config) = state.settings.lock().await.post_process.clone() else {
+            return;
+        };
+        let mut event = PostProcessEvent {
+            job_id: job_id.clone(),
+            program: config.program.clone(),
+            path: None,
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+        };
+        let result = run(&app, &job_id, config, &mut event).await;
+        let name = match result {
+            Ok(()) => "post-process-finished",
+            Err(e) => {
+                event.error = Some(e.to_string());
+                "post-process-failed"
+            }
+        };
+        audit::append(&state, name, &event).await;
+        let _ = app.emit_all(name, event);
+    });
+}
+
+// Add a program to the allowlist after the user confirms it in a native dialog,
+// which a script in the webview can't answer on their behalf
+#[tauri::command]
+pub async fn allow_post_process_program(
+    window: Window,
+    state: State<'_, AppState>,
+    program: String,
+) -> Result<bool, AppError> {
+    let program = canonical_program(&program)?;
+    let message = format!(
+        "Allow QuickScript to run {} on every completed transcript?",
+        program.display()
+    );
+    let confirmed = tauri::async_runtime::spawn_blocking(move || {
+        tauri::api::dialog::blocking::confirm(Some(&window), "Allow post-processing program", message)
+    })
+    .await
+    .map_err(|e| format!("Failed to show confirmation: {}", e))?;
+    if !confirmed {
+        return Ok(false);
+    }
+
+    let program = program.to_string_lossy().to_string();
+    {
+        let mut settings = state.settings.lock().await;
+        if !settings.post_process_allowlist.contains(&program) {
+            settings.post_process_allowlist.push(program);
+        }
+    }
+    crate::persist_settings(&state).await?;
+    Ok(true)
+}
+
+// Configure the post-processing script, or turn it off with None
+#[tauri::command]
+pub async fn set_post_process(
+    state: State<'_, AppState>,
+    config: Option<PostProcessConfig>,
+) -> Result<(), AppError> {
+    if let Some(config) = &config {
+        let program = canonical_program(&config.program)?;
+        if !is_allowed(&state.settings.lock().await.post_process_allowlist, &program) {
+            return Err(format!(
+                "{} is not on the post-processing allowlist; allow it first",
+                program.display()
+            ).into());
+        }
+        // The format names the saved file, so it may not carry path characters
+        if let Some(format) = config.format.as_ref().filter(|format| format.is_empty() || !format.chars().all(|c| c.is_ascii_alphanumeric())) {
+            return Err(format!("Invalid result format '{}'", format).into());
+        }
+    }
+    state.settings.lock().await.post_process = config;
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
@@ -0,0 +1,232 @@
+// Pausing transfers while on battery or a metered connection
+
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tokio::process::Command;
+
+use crate::tasks::{self, TaskKind};
+use crate::{AppError, AppState};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    Battery,
+    Metered,
+}
+
+// What the OS reports; None where it can't tell
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PowerState {
+    pub on_battery: Option<bool>,
+    pub metered: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PausedEvent {
+    reason: PauseReason,
+}
+
+// Tracks why transfers were paused, so a manual resume doesn't get undone
+#[derive(Default)]
+pub struct PowerMonitor {
+    // Guarded by a std mutex; never held across an await
+    inner: Mutex<MonitorState>,
+}
+
+#[derive(Default)]
+struct MonitorState {
+    // Reason the monitor paused transfers for, if it did
+    paused_for: Option<PauseReason>,
+    // Reason the user chose to ignore; cleared once it no longer applies
+    overridden: Option<PauseReason>,
+    last: PowerState,
+}
+
+impl PowerMonitor {
+    fn lock(&self) -> std::sync::MutexGuard<'_, MonitorState> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PowerStatus {
+    #[serde(flatten)]
+    power: PowerState,
+    paused: bool,
+    reason: Option<PauseReason>,
+    overridden: bool,
+}
+
+async fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().await.ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+async fn on_battery() -> Option<bool> {
+    let mut entries = tokio::fs::read_dir("/sys/class/power_supply").await.ok()?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let read = |name: &str| tokio::fs::read_to_string(entry.path().join(name));
+        let kind = read("type").await.unwrap_or_default();
+        match kind.trim() {
+            "Mains" | "USB" if read("online").await.is_ok_and(|online| online.trim() == "1") => {
+                return Some(false);
+            }
+            "Battery" if read("status").await.is_ok_and(|status| status.trim() == "Discharging") => {
+                return Some(true);
+            }
+            _ => {}
+        }
+    }
+    // No discharging battery, including machines without one
+    Some(false)
+}
+
+#[cfg(target_os = "linux")]
+async fn metered() -> Option<bool> {
+    // NetworkManager's Metered property: 1/3 yes or guessed yes, 2/4 no or guessed no
+    let output = run("busctl", &[
+        "get-property",
+        "org.freedesktop.NetworkManager",
+        "/org/freedesktop/NetworkManager",
+        "org.freedesktop.NetworkManager",
+        "Metered",
+    ]).await?;
+    match output.strip_prefix("u ")?.trim() {
+        "1" | "3" => Some(true),
+        "2" | "4" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn on_battery() -> Option<bool> {
+    let output = run("pmset", &["-g", "batt"]).await?;
+    Some(output.contains("'Battery Power'"))
+}
+
+#[cfg(target_os = "macos")]
+async fn metered() -> Option<bool> {
+    // Low Data Mode isn't exposed to command-line tools
+    None
+}
+
+#[cfg(windows)]
+async fn on_battery() -> Option<bool> {
+    // BatteryStatus 1 is discharging; no output means there is no battery
+    let output = run("powershell", &["-NoProfile", "-Command", "(Get-CimInstance Win32_Battery).BatteryStatus"]).await?;
+    Some(output.lines().any(|line| line.trim() == "1"))
+}
+
+#[cfg(windows)]
+async fn metered() -> Option<bool> {
+    let script = "[Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile().GetConnectionCost().NetworkCostType";
+    let output = run("powershell", &["-NoProfile", "-Command", script]).await?;
+    match output.as_str() {
+        "Fixed" | "Variable" => Some(true),
+        "Unrestricted" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+async fn on_battery() -> Option<bool> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+async fn metered() -> Option<bool> {
+    None
+}
+
+pub async fn detect() -> PowerState {
+    let (on_battery, metered) = tokio::join!(on_battery(), metered());
+    PowerState { on_battery, metered }
+}
+
+// Why transfers should be paused right now under the user's settings
+async fn pause_reason(state: &AppState, power: PowerState) -> Option<PauseReason> {
+    let settings = state.settings.lock().await;
+    if settings.pause_on_metered && power.metered == Some(true) {
+        Some(PauseReason::Metered)
+    } else if settings.pause_on_battery && power.on_battery == Some(true) {
+        Some(PauseReason::Battery)
+    } else {
+        None
+    }
+}
+
+// Pause or resume to match the power state; an overridden reason is skipped until it changes
+async fn apply(app: &AppHandle, state: &AppState, power: PowerState) {
+    let reason = pause_reason(state, power).await;
+    let (pause, resume) = {
+        let mut monitor = state.power.lock();
+        monitor.last = power;
+        if monitor.overridden != reason {
+            monitor.overridden = None;
+        }
+        let wanted = reason.filter(|reason| monitor.overridden != Some(*reason));
+        let previous = std::mem::replace(&mut monitor.paused_for, wanted);
+        (wanted.filter(|_| previous.is_none()), previous.is_some() && wanted.is_none())
+    };
+
+    if let Some(reason) = pause {
+        if state.transfers.set_paused(true) {
+            let _ = app.emit_all("transfers-paused-metered", PausedEvent { reason });
+        }
+    } else if resume && state.transfers.set_paused(false) {
+        let _ = app.emit_all("transfers-resumed", ());
+    }
+}
+
+pub fn watch_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::PowerWatcher, None, async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            let watching = {
+                let settings = state.settings.lock().await;
+                settings.pause_on_battery || settings.pause_on_metered
+            };
+            // Skip the OS queries entirely unless the user opted in
+            let power = if watching { detect().await } else { PowerState::default() };
+            apply(&app, &state, power).await;
+        }
+    });
+}
+
+fn status(state: &AppState) -> PowerStatus {
+    let monitor = state.power.lock();
+    PowerStatus {
+        power: monitor.last,
+        paused: state.transfers.is_paused(),
+        reason: monitor.paused_for,
+        overridden: monitor.overridden.is_some(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_power_state(state: State<'_, AppState>) -> Result<PowerStatus, AppError> {
+    Ok(status(&state))
+}
+
+// Resume despite the current battery or metered state until it changes, or undo that
+#[tauri::command]
+pub async fn override_transfer_pause(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    resume: bool,
+) -> Result<PowerStatus, AppError> {
+    let power = detect().await;
+    let reason = pause_reason(&state, power).await;
+    state.power.lock().overridden = if resume { reason } else { None };
+    apply(&app, &state, power).await;
+    Ok(status(&state))
+}
@@ -0,0 +1,103 @@
+// Named processing presets saved in settings
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{capabilities, groups, AppError, AppState, ProcessOptions};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub options: ProcessOptions,
+    // Output format the UI downloads by default for jobs using this preset
+    pub default_format: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PresetStaleEvent {
+    preset: String,
+    problems: Vec<String>,
+}
+
+// Expand a preset into request options, letting explicitly passed fields win
+pub async fn resolve_options(
+    app: &AppHandle,
+    state: &AppState,
+    preset: Option<String>,
+    options: Option<ProcessOptions>,
+) -> Result<ProcessOptions, AppError> {
+    let options = options.unwrap_or_default();
+    let resolved = match preset {
+        Some(name) => expand(app, state, name, options).await?,
+        None => options,
+    };
+
+    resolved.validate()?;
+    if let Some(group_id) = &resolved.group_id {
+        groups::ensure_exists(state, group_id).await?;
+    }
+    Ok(resolved)
+}
+
+async fn expand(
+    app: &AppHandle,
+    state: &AppState,
+    name: String,
+    options: ProcessOptions,
+) -> Result<ProcessOptions, AppError> {
+    let preset = state.settings.lock().await.presets.get(&name).cloned()
+        .ok_or_else(|| format!("Unknown preset '{}'", name))?;
+    let resolved = options.merged_over(&preset.options);
+
+    // Warn rather than fail: the backend has the final say on what it accepts
+    match capabilities::fetch(state, false).await {
+        Ok(Some(capabilities)) => {
+            let problems = capabilities.problems_with(&resolved, preset.default_format.as_deref());
+            if !problems.is_empty() {
+                eprintln!("Preset '{}' may be stale: {}", name, problems.join("; "));
+                let _ = app.emit_all("preset-stale", PresetStaleEvent { preset: name, problems });
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("Could not check preset '{}' against backend capabilities: {}", name, e),
+    }
+
+    Ok(resolved)
+}
+
+#[tauri::command]
+pub async fn save_preset(
+    state: State<'_, AppState>,
+    name: String,
+    options: ProcessOptions,
+    default_format: Option<String>,
+) -> Result<(), AppError> {
+    options.validate()?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string().into());
+    }
+
+    let preset = Preset {
+        name: name.clone(),
+        options,
+        default_format: default_format.filter(|format| !format.is_empty()),
+    };
+    state.settings.lock().await.presets.insert(name, preset);
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_presets(state: State<'_, AppState>) -> Result<Vec<Preset>, AppError> {
+    Ok(state.settings.lock().await.presets.values().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn delete_preset(state: State<'_, AppState>, name: String) -> Result<bool, AppError> {
+    let removed = state.settings.lock().await.presets.remove(&name).is_some();
+    if removed {
+        crate::persist_settings(&state).await?;
+    }
+    Ok(removed)
+}
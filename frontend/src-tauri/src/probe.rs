@@ -0,0 +1,33 @@
+// Media details shown before a file is submitted
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{media, AppError};
+
+#[derive(Debug, Serialize)]
+pub struct MediaInfo {
+    duration_secs: f64,
+    has_video: bool,
+    title: Option<String>,
+    creation_time: Option<String>,
+    // Can be passed back as ProcessOptions::chapters to guide segmentation
+    chapters: Vec<media::Chapter>,
+}
+
+#[tauri::command]
+pub async fn probe_media(path: String) -> Result<MediaInfo, AppError> {
+    let source = Path::new(&path);
+    let duration_secs = media::probe_duration(source).await?;
+    let has_video = media::has_video(source).await?;
+    let metadata = media::probe_metadata(source).await?;
+
+    Ok(MediaInfo {
+        duration_secs,
+        has_video,
+        title: metadata.title,
+        creation_time: metadata.creation_time,
+        chapters: metadata.chapters,
+    })
+}
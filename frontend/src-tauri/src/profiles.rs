@@ -0,0 +1,210 @@
+// Named settings snapshots for switching between backends, e.g. a local dev server and production
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::cache::StatusCache;
+use crate::records::JobRecord;
+use crate::settings::Settings;
+use crate::{network, persist, repair, tasks, AppError, AppState};
+
+pub const PROFILES_FILE: &str = "profiles.json";
+// Credentials per profile, kept apart so profiles.json can be shared or backed up safely
+const SECRETS_FILE: &str = "profile_secrets.json";
+// Job history of the profiles not in use, one file each
+const PARKED_RECORDS_DIR: &str = "profile_records";
+// Parked name for the history from before any profile was switched to; not a valid profile name
+const UNNAMED_PROFILE: &str = "(unnamed)";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    // Profile the current settings were last switched to
+    pub active: Option<String>,
+    pub profiles: BTreeMap<String, Settings>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Credentials {
+    signing_key: Option<String>,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+impl Credentials {
+    fn of(settings: &Settings) -> Self {
+        Credentials {
+            signing_key: settings.signing_key.clone(),
+            access_token: settings.access_token.clone(),
+            refresh_token: settings.refresh_token.clone(),
+        }
+    }
+
+    fn apply(self, settings: Settings) -> Settings {
+        Settings {
+            signing_key: self.signing_key,
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            ..settings
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileSummary {
+    name: String,
+    api_url: String,
+    active: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ProfileSwitchedEvent {
+    name: String,
+    api_url: String,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'));
+    if !valid {
+        return Err(format!("Invalid profile name: '{}'", name));
+    }
+    Ok(())
+}
+
+fn load_secrets(state: &AppState) -> BTreeMap<String, Credentials> {
+    persist::load_json(&state.config_dir.join(SECRETS_FILE)).unwrap_or_default()
+}
+
+async fn save_secrets(state: &AppState, secrets: &BTreeMap<String, Credentials>) -> Result<(), AppError> {
+    persist::save_json(&state.config_dir.join(SECRETS_FILE), secrets).await
+}
+
+async fn save(state: &AppState) -> Result<(), AppError> {
+    let mut profiles = state.profiles.lock().await.clone();
+    // Older versions stored credentials inline; move them out before writing
+    if profiles.profiles.values().any(Settings::has_secrets) {
+        let mut secrets = load_secrets(state);
+        for (name, settings) in &mut profiles.profiles {
+            if settings.has_secrets() {
+                secrets.entry(name.clone()).or_insert_with(|| Credentials::of(settings));
+                *settings = settings.without_secrets();
+            }
+        }
+        save_secrets(state, &secrets).await?;
+        *state.profiles.lock().await = profiles.clone();
+    }
+    persist::save_json(&state.config_dir.join(PROFILES_FILE), &profiles).await
+}
+
+fn parked_records_path(state: &AppState, profile: Option<&str>) -> PathBuf {
+    state.data_dir
+        .join(PARKED_RECORDS_DIR)
+        .join(format!("{}.json", profile.unwrap_or(UNNAMED_PROFILE)))
+}
+
+// Set the outgoing profile's jobs aside and bring back the incoming one's, since job IDs
+// only mean something to the backend that issued them
+async fn swap_records(state: &AppState, outgoing: Option<&str>, incoming: &str) -> Result<(), AppError> {
+    // Their polls would ask the new backend about jobs it never saw
+    let pollers: Vec<tasks::TaskId> = state.subscriptions.lock().await.drain().map(|(_, id)| id).collect();
+    for task_id in pollers {
+        tasks::kill(&state.tasks, task_id);
+    }
+
+    let incoming_path = parked_records_path(state, Some(incoming));
+    let restored = repair::load_records(&incoming_path);
+    {
+        let mut records = state.job_records.lock().await;
+        let mut parked: Vec<JobRecord> = records.values().cloned().collect();
+        parked.sort_by_key(|record| record.submitted_at);
+        persist::save_json(&parked_records_path(state, outgoing), &parked).await?;
+
+        *state.processing_jobs.lock().await = restored.iter()
+            .filter(|record| !record.is_finished())
+            .map(|record| record.job_id.clone())
+            .collect();
+        *records = restored.into_iter()
+            .map(|record| (record.job_id.clone(), record))
+            .collect();
+    }
+    crate::persist_records(state).await;
+    // The records are live again, so the parked copy would only go stale
+    let _ = tokio::fs::remove_file(&incoming_path).await;
+    Ok(())
+}
+
+// Store the current settings under name; its credentials go to a separate file
+#[tauri::command]
+pub async fn save_profile(state: State<'_, AppState>, name: String) -> Result<(), AppError> {
+    let name = name.trim().to_string();
+    validate_name(&name)?;
+    let settings = state.settings.lock().await.clone();
+    let mut secrets = load_secrets(&state);
+    secrets.insert(name.clone(), Credentials::of(&settings));
+    save_secrets(&state, &secrets).await?;
+    {
+        let mut profiles = state.profiles.lock().await;
+        profiles.profiles.insert(name.clone(), settings.without_secrets());
+        profiles.active = Some(name);
+    }
+    save(&state).await?;
+    Ok(())
+}
+
+// Replace the settings with a saved profile and reconnect; nothing changes if its settings are invalid
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, state: State<'_, AppState>, name: String) -> Result<(), AppError> {
+    let profile = state.profiles.lock().await
+        .profiles
+        .get(&name)
+        .cloned()
+        .ok_or_else(|| format!("No profile named '{}'", name))?;
+    let profile = match load_secrets(&state).remove(&name) {
+        Some(credentials) => credentials.apply(profile),
+        None => profile,
+    };
+    let connection = network::connect(&profile)?;
+    let api_url = connection.api_url.clone();
+    let outgoing = state.profiles.lock().await.active.clone();
+
+    {
+        // Settings and connection change together so no request mixes the two environments
+        let mut settings = state.settings.lock().await;
+        *settings = profile.with_post_process_from(&settings);
+        state.set_connection(connection);
+        *state.status_cache.lock().await = StatusCache::new(settings.status_cache_ttl());
+        state.transfers.configure(settings.transfer_priority, settings.transfer_buffer_budget_bytes);
+    }
+    if outgoing.as_deref() != Some(name.as_str()) {
+        swap_records(&state, outgoing.as_deref(), &name).await?;
+    }
+    // What one backend supports says nothing about another
+    *state.capabilities.lock().await = None;
+    *state.backend_status.lock().await = None;
+    state.profiles.lock().await.active = Some(name.clone());
+
+    crate::persist_settings(&state).await?;
+    save(&state).await?;
+    let _ = app.emit_all("profile-switched", ProfileSwitchedEvent { name, api_url });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_profiles(state: State<'_, AppState>) -> Result<Vec<ProfileSummary>, AppError> {
+    let profiles = state.profiles.lock().await;
+    Ok(profiles
+        .profiles
+        .iter()
+        .map(|(name, settings)| ProfileSummary {
+            name: name.clone(),
+            api_url: settings.api_url.clone(),
+            active: profiles.active.as_ref() == Some(name),
+        })
+        .collect())
+}
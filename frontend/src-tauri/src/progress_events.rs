@@ -0,0 +1,139 @@
+// Coalescing of job-progress events, so fast polling can't flood the webview
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::{records, AppError, AppState, JobStatusResponse};
+
+pub const DEFAULT_EVENTS_PER_SEC: u32 = 4;
+pub const MAX_EVENTS_PER_SEC: u32 = 60;
+
+// How long a finished job is remembered, so late updates for it are dropped
+const FINISHED_RETENTION: Duration = Duration::from_secs(60);
+
+struct JobEvents {
+    last_emitted_at: Instant,
+    last_status: String,
+    // Newest update held back since the last emit
+    pending: Option<JobStatusResponse>,
+    // The final state has gone out, so nothing else may follow it
+    finished: bool,
+}
+
+impl JobEvents {
+    fn new(status: &str) -> Self {
+        JobEvents {
+            last_emitted_at: Instant::now(),
+            last_status: status.to_string(),
+            pending: None,
+            finished: false,
+        }
+    }
+}
+
+// Guarded by a std mutex; never held across an await. Events are emitted while it is
+// held, so an update read before a job finished can't be sent after its final state.
+#[derive(Default)]
+pub struct ProgressEvents {
+    jobs: Mutex<HashMap<String, JobEvents>>,
+}
+
+impl ProgressEvents {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, JobEvents>> {
+        self.jobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Mark a job finished and drop anything held back for it. Call this before emitting
+    // the final state yourself.
+    pub fn discard(&self, job_id: &str) {
+        Self::finish(&mut self.lock(), job_id);
+    }
+
+    fn finish(jobs: &mut HashMap<String, JobEvents>, job_id: &str) {
+        let job = jobs.entry(job_id.to_string()).or_insert_with(|| JobEvents::new(""));
+        job.finished = true;
+        job.pending = None;
+        job.last_emitted_at = Instant::now();
+    }
+
+    // Send held-back updates whose job has waited at least interval
+    fn flush(&self, app: &AppHandle, interval: Duration) {
+        let mut jobs = self.lock();
+        jobs.retain(|_, job| !job.finished || job.last_emitted_at.elapsed() < FINISHED_RETENTION);
+        for job in jobs.values_mut().filter(|job| job.last_emitted_at.elapsed() >= interval) {
+            if let Some(status) = job.pending.take() {
+                job.last_emitted_at = Instant::now();
+                let _ = app.emit_all("job-progress", status);
+            }
+        }
+    }
+}
+
+fn interval(events_per_sec: u32) -> Duration {
+    Duration::from_secs(1) / events_per_sec.clamp(1, MAX_EVENTS_PER_SEC)
+}
+
+async fn current_interval(state: &AppState) -> Duration {
+    interval(state.settings.lock().await.progress_events_per_sec)
+}
+
+// Emit a job-progress event now, or hold it for the flusher if the job was updated
+// too recently. Status changes and terminal states always go out at once, and updates
+// arriving after the terminal state are dropped.
+pub async fn emit(app: &AppHandle, state: &AppState, status: &JobStatusResponse) {
+    let interval = current_interval(state).await;
+    let mut jobs = state.progress_events.lock();
+    if records::is_finished_status(&status.status) {
+        if jobs.get(&status.job_id).is_some_and(|job| job.finished) {
+            return;
+        }
+        ProgressEvents::finish(&mut jobs, &status.job_id);
+    } else if let Some(job) = jobs.get_mut(&status.job_id) {
+        if job.finished {
+            return;
+        }
+        if job.last_status == status.status && job.last_emitted_at.elapsed() < interval {
+            job.pending = Some(status.clone());
+            return;
+        }
+        job.last_emitted_at = Instant::now();
+        job.last_status = status.status.clone();
+        job.pending = None;
+    } else {
+        jobs.insert(status.job_id.clone(), JobEvents::new(&status.status));
+    }
+    let _ = app.emit_all("job-progress", status);
+}
+
+pub fn flush_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::EventFlusher, None, async move {
+        loop {
+            let state = app.state::<AppState>();
+            // Re-read every round so a new rate applies without a restart
+            let interval = current_interval(&state).await;
+            state.progress_events.flush(&app, interval);
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn set_progress_event_rate(
+    state: State<'_, AppState>,
+    events_per_sec: u32,
+) -> Result<(), AppError> {
+    if !(1..=MAX_EVENTS_PER_SEC).contains(&events_per_sec) {
+        return Err(format!(
+            "Progress event rate must be between 1 and {} per second",
+            MAX_EVENTS_PER_SEC
+        ).into());
+    }
+    state.settings.lock().await.progress_events_per_sec = events_per_sec;
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
@@ -0,0 +1,278 @@
+// Client-side queue of sources waiting to be submitted to the backend
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::records::{unix_now, JobSource};
+use crate::tasks::{self, TaskKind};
+use crate::{concurrency, persist, presets, AppError, AppState, ProcessOptions};
+
+pub const QUEUE_FILE: &str = "queue.json";
+
+const RUN_INTERVAL: Duration = Duration::from_secs(2);
+
+pub type QueueId = String;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStatus {
+    Pending,
+    Running,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueItem {
+    pub id: QueueId,
+    pub source: JobSource,
+    pub options: ProcessOptions,
+    pub added_at: u64,
+    pub status: QueueStatus,
+    // Set once the item has been submitted
+    pub job_id: Option<String>,
+    pub error: Option<String>,
+}
+
+fn next_id() -> QueueId {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("q-{}-{}", unix_now(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+// Items left mid-submission by a previous session go back to pending
+pub fn restore(mut items: Vec<QueueItem>) -> Vec<QueueItem> {
+    for item in &mut items {
+        if item.status == QueueStatus::Running && item.job_id.is_none() {
+            item.status = QueueStatus::Pending;
+        }
+    }
+    items
+}
+
+// Save the queue and tell every view about it
+async fn publish(app: &AppHandle, state: &AppState) {
+    let items = state.queue.lock().await.clone();
+    if let Err(e) = persist::save_json(&state.data_dir.join(QUEUE_FILE), &items).await {
+        eprintln!("Failed to save queue: {}", e);
+    }
+    let _ = app.emit_all("queue-updated", items);
+}
+
+// Drop items whose job finished, then submit pending items while there is room
+async fn advance(app: &AppHandle, state: &AppState) {
+    let running: Vec<String> = state.queue.lock().await
+        .iter()
+        .filter_map(|item| item.job_id.clone())
+        .collect();
+    let mut finished = HashSet::new();
+    for job_id in running {
+        match crate::fetch_status(state, &job_id).await {
+            Ok(status) if matches!(status.status.as_str(), "complete" | "error") => {
+                finished.insert(job_id);
+            }
+            Err(AppError::JobNotFound { .. }) => {
+                finished.insert(job_id);
+            }
+            _ => {}
+        }
+    }
+    // Cancelled jobs are finished too, though the backend may still report them
+    {
+        let records = state.job_records.lock().await;
+        let queue = state.queue.lock().await;
+        for job_id in queue.iter().filter_map(|item| item.job_id.as_ref()) {
+            if records.get(job_id).is_some_and(|record| record.is_finished()) {
+                finished.insert(job_id.clone());
+            }
+        }
+    }
+    let mut changed = false;
+    if !finished.is_empty() {
+        state.queue.lock().await.retain(|item| !item.job_id.as_ref().is_some_and(|id| finished.contains(id)));
+        changed = true;
+    }
+
+    // Nothing new is submitted while transfers are paused or the app is quitting
+    if state.transfers.is_paused() || crate::shutdown::in_progress(state) {
+        if changed {
+            publish(app, state).await;
+        }
+        return;
+    }
+
+    // Bounds may have changed in the settings since the last run
+    let (min, max) = {
+        let settings = state.settings.lock().await;
+        (settings.min_concurrency, settings.max_concurrency)
+    };
+    state.queue_concurrency.lock().await.set_bounds(min, max);
+
+    loop {
+        // Claim the next pending item under the lock, then submit without holding it
+        let max_running = state.queue_concurrency.lock().await.current();
+        let next = {
+            let mut queue = state.queue.lock().await;
+            let running = queue.iter().filter(|item| item.status == QueueStatus::Running).count();
+            if running >= max_running {
+                None
+            } else {
+                queue.iter_mut()
+                    .find(|item| item.status == QueueStatus::Pending)
+                    .map(|item| {
+                        item.status = QueueStatus::Running;
+                        item.clone()
+                    })
+            }
+        };
+        let Some(item) = next else {
+            break;
+        };
+        changed = true;
+
+        let started = Instant::now();
+        let result = crate::submit_source(state, &item.source, &item.options).await;
+        {
+            let mut limit = state.queue_concurrency.lock().await;
+            if let Some(concurrency) = limit.observe(started.elapsed(), result.as_ref().map(|_| ())) {
+                concurrency::announce(state, &limit, concurrency);
+            }
+        }
+        if let Ok(api_response) = &result {
+            crate::track_job(state, &api_response.job_id, item.source.clone(), item.options.clone()).await;
+        }
+
+        let mut queue = state.queue.lock().await;
+        if let Some(queued) = queue.iter_mut().find(|queued| queued.id == item.id) {
+            match result {
+                Ok(api_response) => queued.job_id = Some(api_response.job_id),
+                Err(e) => {
+                    queued.status = QueueStatus::Failed;
+                    queued.error = Some(e.to_string());
+                }
+            }
+        }
+    }
+
+    if changed {
+        publish(app, state).await;
+    }
+}
+
+pub fn run_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::QueueRunner, None, async move {
+        let mut interval = tokio::time::interval(RUN_INTERVAL);
+        loop {
+            interval.tick().await;
+            advance(&app, &app.state::<AppState>()).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn enqueue_job(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    source: JobSource,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+) -> Result<QueueId, AppError> {
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+    let item = QueueItem {
+        id: next_id(),
+        source,
+        options,
+        added_at: unix_now(),
+        status: QueueStatus::Pending,
+        job_id: None,
+        error: None,
+    };
+    let id = item.id.clone();
+    state.queue.lock().await.push(item);
+    publish(&app, &state).await;
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn list_queue(state: State<'_, AppState>) -> Result<Vec<QueueItem>, AppError> {
+    Ok(state.queue.lock().await.clone())
+}
+
+// Remove a pending or failed item; running items are cancelled through their job instead
+#[tauri::command]
+pub async fn remove_from_queue(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: QueueId,
+) -> Result<bool, AppError> {
+    let removed = {
+        let mut queue = state.queue.lock().await;
+        match queue.iter().position(|item| item.id == id) {
+            Some(index) if queue[index].status == QueueStatus::Running => {
+                return Err(format!("Queue item {} is already running", id).into());
+            }
+            Some(index) => {
+                queue.remove(index);
+                true
+            }
+            None => false,
+        }
+    };
+    if removed {
+        publish(&app, &state).await;
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn reorder_queue(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    new_order: Vec<QueueId>,
+) -> Result<(), AppError> {
+    {
+        let mut queue = state.queue.lock().await;
+
+        let mut seen = HashSet::new();
+        for id in &new_order {
+            match queue.iter().find(|item| &item.id == id) {
+                None => return Err(format!("Unknown queue item {}", id).into()),
+                Some(item) if item.status != QueueStatus::Pending => {
+                    return Err(format!("Queue item {} is no longer pending", id).into());
+                }
+                Some(_) if !seen.insert(id.as_str()) => {
+                    return Err(format!("Queue item {} is listed twice", id).into());
+                }
+                Some(_) => {}
+            }
+        }
+        let pending = queue.iter().filter(|item| item.status == QueueStatus::Pending).count();
+        if new_order.len() != pending {
+            return Err(format!(
+                "New order lists {} items but {} are pending",
+                new_order.len(),
+                pending
+            )
+            .into());
+        }
+
+        // Pending items trade places among the slots they already occupy
+        let mut reordered = new_order.iter()
+            .filter_map(|id| queue.iter().find(|item| &item.id == id).cloned())
+            .collect::<Vec<_>>()
+            .into_iter();
+        for item in queue.iter_mut() {
+            if item.status == QueueStatus::Pending {
+                if let Some(next) = reordered.next() {
+                    *item = next;
+                }
+            }
+        }
+    }
+
+    publish(&app, &state).await;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// Comparing local job records with the jobs the backend actually has
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::{JobRecord, JobSource};
+use crate::{api, result_cache, AppError, AppState, ProcessOptions};
+
+#[derive(Debug, Deserialize)]
+struct RemoteJob {
+    job_id: String,
+    status: String,
+    #[serde(default)]
+    progress: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusMismatch {
+    job_id: String,
+    local_status: String,
+    remote_status: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackendDiff {
+    Compared {
+        // Tracked here but unknown to the backend, e.g. expired or deleted server-side
+        local_only: Vec<String>,
+        // On the backend but not tracked here
+        remote_only: Vec<String>,
+        status_mismatches: Vec<StatusMismatch>,
+    },
+    // The backend has no job list to compare against
+    Unsupported { feature: String },
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    removed: Vec<String>,
+    added: Vec<String>,
+    updated: Vec<String>,
+    // Local-only jobs kept because they're pinned
+    kept_pinned: Vec<String>,
+}
+
+// Every job the backend knows, or None when it has no job list endpoint
+async fn remote_jobs(state: &AppState) -> Result<Option<HashMap<String, RemoteJob>>, AppError> {
+    let request = state.client().get(format!("{}/jobs", state.api_url()));
+    let response = api::send(state, request).await?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND
+        | reqwest::StatusCode::METHOD_NOT_ALLOWED
+        | reqwest::StatusCode::NOT_IMPLEMENTED => return Ok(None),
+        status if !status.is_success() => return Err(api::error_from_response(response).await),
+        _ => {}
+    }
+
+    // Either a bare list or { "jobs": [...] }
+    let body: serde_json::Value = api::parse_json(response).await?;
+    let list = match body {
+        serde_json::Value::Object(mut object) => object.remove("jobs").unwrap_or_default(),
+        other => other,
+    };
+    let jobs: Vec<RemoteJob> = serde_json::from_value(list)
+        .map_err(|e| format!("Failed to parse job list: {}", e))?;
+    Ok(Some(jobs.into_iter().map(|job| (job.job_id.clone(), job)).collect()))
+}
+
+fn compare(records: &HashMap<String, JobRecord>, remote: &HashMap<String, RemoteJob>) -> BackendDiff {
+    let mut local_only: Vec<String> = records.keys().filter(|id| !remote.contains_key(*id)).cloned().collect();
+    let mut remote_only: Vec<String> = remote.keys().filter(|id| !records.contains_key(*id)).cloned().collect();
+    let mut status_mismatches: Vec<StatusMismatch> = records
+        .values()
+        .filter_map(|record| {
+            let remote = remote.get(&record.job_id)?;
+            (remote.status != record.status).then(|| StatusMismatch {
+                job_id: record.job_id.clone(),
+                local_status: record.status.clone(),
+                remote_status: remote.status.clone(),
+            })
+        })
+        .collect();
+    local_only.sort();
+    remote_only.sort();
+    status_mismatches.sort_by(|a, b| a.job_id.cmp(&b.job_id));
+
+    BackendDiff::Compared {
+        local_only,
+        remote_only,
+        status_mismatches,
+    }
+}
+
+// What differs between the local records and the backend, without changing anything
+#[tauri::command]
+pub async fn diff_with_backend(state: State<'_, AppState>) -> Result<BackendDiff, AppError> {
+    let Some(remote) = remote_jobs(&state).await? else {
+        return Ok(BackendDiff::Unsupported { feature: "job_list".to_string() });
+    };
+    let records = state.job_records.lock().await;
+    Ok(compare(&records, &remote))
+}
+
+// Reconcile the local records with the backend, applying only the chosen fixes
+#[tauri::command]
+pub async fn sync_jobs(
+    state: State<'_, AppState>,
+    remove_local_only: bool,
+    track_remote_only: bool,
+    update_statuses: bool,
+) -> Result<SyncReport, AppError> {
+    let remote = remote_jobs(&state)
+        .await?
+        .ok_or_else(|| AppError::Unsupported { feature: "job_list".to_string() })?;
+
+    let mut report = SyncReport::default();
+    {
+        let mut records = state.job_records.lock().await;
+        let mut processing = state.processing_jobs.lock().await;
+
+        if remove_local_only {
+            records.retain(|job_id, record| {
+                if remote.contains_key(job_id) {
+                    return true;
+                }
+                if record.pinned {
+                    report.kept_pinned.push(job_id.clone());
+                    return true;
+                }
+                report.removed.push(job_id.clone());
+                false
+            });
+            processing.retain(|job_id| records.contains_key(job_id));
+        }
+
+        for job in remote.values() {
+            match records.get_mut(&job.job_id) {
+                Some(record) => {
+                    if update_statuses && record.observe(&job.status, job.progress) {
+                        report.updated.push(job.job_id.clone());
+                        if record.is_finished() {
+                            processing.remove(&job.job_id);
+                        }
+                    }
+                }
+                None if track_remote_only => {
+                    // Its source lives elsewhere, so it can be viewed and downloaded but not resubmitted
+                    let mut record = JobRecord::new(job.job_id.clone(), JobSource::Remote, ProcessOptions::default());
+                    record.observe(&job.status, job.progress);
+                    if !record.is_finished() {
+                        processing.insert(job.job_id.clone());
+                    }
+                    records.insert(job.job_id.clone(), record);
+                    report.added.push(job.job_id.clone());
+                }
+                None => {}
+            }
+        }
+    }
+
+    for job_id in &report.removed {
+        result_cache::invalidate(&state, job_id).await;
+    }
+    if !report.removed.is_empty() || !report.added.is_empty() || !report.updated.is_empty() {
+        crate::persist_records(&state).await;
+    }
+    report.removed.sort();
+    report.added.sort();
+    report.updated.sort();
+    Ok(report)
+}
@@ -0,0 +1,158 @@
+// Client-side records of submitted jobs
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::silence::KeptRange;
+use crate::ProcessOptions;
+
+pub const RECORDS_FILE: &str = "records.json";
+
+// Where a job's media came from, so it can be resubmitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobSource {
+    File { path: String },
+    Segment { path: String, start_secs: f64, end_secs: f64 },
+    Url { url: String },
+    // Downloaded client-side and uploaded as a file
+    Fetched { url: String },
+    // Found on the backend by a sync; submitted from elsewhere
+    Remote,
+    // Streamed from a named pipe as it was written; it can only be read once
+    Pipe { path: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub job_id: String,
+    pub source: JobSource,
+    pub options: ProcessOptions,
+    pub submitted_at: u64,
+    pub status: String,
+    pub progress: f32,
+    // When the observed progress last changed
+    pub progress_changed_at: u64,
+    // Result files saved for this job, counted against the storage budget
+    #[serde(default)]
+    pub downloads: Vec<String>,
+    // Directory a batch upload was rooted at, for mirroring the source tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_root: Option<String>,
+    // Auto-cancel after this long; None falls back to the global default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_secs: Option<u64>,
+    // Warnings the backend reported for the job
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    // When the backend will delete the result, as Unix seconds on its clock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_expires_at: Option<u64>,
+    // SHA-256 and modification time of an uploaded source file, for spotting re-uploads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_mtime: Option<u64>,
+    // Earlier job for the same path whose content has since changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision_of: Option<String>,
+    // When the job was first seen complete
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<u64>,
+    // Kept out of storage-budget eviction and sync cleanup
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    // Job this one reprocessed with a fallback model after low confidence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalated_from: Option<String>,
+    // Length of the source media, probed once when first needed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_duration_secs: Option<f64>,
+    // Submission session the job was sent in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    // Mean result confidence, worked out once the result is first ranked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub average_confidence: Option<f64>,
+    // Parts of the source kept when silence was trimmed before upload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trimmed_ranges: Option<Vec<KeptRange>>,
+    // When a result was last saved, which keeps the job from counting as idle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_accessed_at: Option<u64>,
+}
+
+// Statuses the backend never moves a job on from
+pub fn is_finished_status(status: &str) -> bool {
+    matches!(status, "complete" | "error" | "cancelled")
+}
+
+impl JobRecord {
+    pub fn new(job_id: String, source: JobSource, options: ProcessOptions) -> Self {
+        let now = unix_now();
+        JobRecord {
+            job_id,
+            source,
+            options,
+            submitted_at: now,
+            status: "queued".to_string(),
+            progress: 0.0,
+            progress_changed_at: now,
+            downloads: Vec::new(),
+            input_root: None,
+            max_duration_secs: None,
+            warnings: Vec::new(),
+            result_expires_at: None,
+            source_hash: None,
+            source_mtime: None,
+            revision_of: None,
+            completed_at: None,
+            pinned: false,
+            escalated_from: None,
+            source_duration_secs: None,
+            session_id: None,
+            average_confidence: None,
+            trimmed_ranges: None,
+            last_accessed_at: None,
+        }
+    }
+
+    // Update the record from a status response, reporting whether the status changed
+    pub fn observe(&mut self, status: &str, progress: f32) -> bool {
+        if progress != self.progress {
+            self.progress = progress;
+            self.progress_changed_at = unix_now();
+        }
+        if self.status == status {
+            return false;
+        }
+        self.status = status.to_string();
+        if status == "complete" && self.completed_at.is_none() {
+            self.completed_at = Some(unix_now());
+        }
+        true
+    }
+
+    // Whether the backend is done with this job
+    pub fn is_finished(&self) -> bool {
+        is_finished_status(&self.status)
+    }
+
+    // Seconds since the job was submitted
+    pub fn elapsed_secs(&self) -> u64 {
+        unix_now().saturating_sub(self.submitted_at)
+    }
+
+    // Seconds since the progress last moved
+    pub fn stalled_secs(&self) -> u64 {
+        unix_now().saturating_sub(self.progress_changed_at)
+    }
+}
+
+// Current time as seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
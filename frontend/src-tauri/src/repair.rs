@@ -0,0 +1,153 @@
+// Salvaging job records from a damaged records file
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::records::{self, JobRecord};
+use crate::{AppError, AppState};
+
+#[derive(Debug, Default)]
+struct Salvage {
+    records: Vec<JobRecord>,
+    lost: usize,
+}
+
+// Top-level objects of a JSON array, even when the array is cut short or some objects are mangled.
+// A trailing object that never closes is returned as None.
+fn array_objects(contents: &str) -> Vec<Option<&str>> {
+    let mut objects = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in contents.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '{' => {
+                depth += 1;
+                if depth == 2 && c == '{' {
+                    start = Some(index);
+                }
+            }
+            ']' | '}' => {
+                if depth == 2 && c == '}' {
+                    if let Some(start) = start.take() {
+                        objects.push(Some(&contents[start..=index]));
+                    }
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+    if start.is_some() {
+        objects.push(None);
+    }
+    objects
+}
+
+// Keep every record that still parses on its own
+fn salvage(contents: &str) -> Salvage {
+    let mut salvage = Salvage::default();
+    for object in array_objects(contents) {
+        match object.and_then(|object| serde_json::from_str::<JobRecord>(object).ok()) {
+            Some(record) => salvage.records.push(record),
+            None => salvage.lost += 1,
+        }
+    }
+    salvage
+}
+
+// Copy the damaged file aside so nothing is lost for good
+fn back_up(path: &Path) -> std::io::Result<PathBuf> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".corrupt-{}", records::unix_now()));
+    let backup = PathBuf::from(backup);
+    std::fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+// Records from the file at startup, salvaging what it can when the file doesn't parse
+pub fn load_records(path: &Path) -> Vec<JobRecord> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let e = match serde_json::from_str(&contents) {
+        Ok(records) => return records,
+        Err(e) => e,
+    };
+
+    let salvage = salvage(&contents);
+    match back_up(path) {
+        Ok(backup) => eprintln!(
+            "{} is damaged ({}); recovered {} records, lost {}, original kept at {}",
+            path.display(),
+            e,
+            salvage.records.len(),
+            salvage.lost,
+            backup.display()
+        ),
+        Err(backup_error) => eprintln!("{} is damaged ({}) and couldn't be backed up: {}", path.display(), e, backup_error),
+    }
+    salvage.records
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepairReport {
+    // False when the file parsed and nothing needed doing
+    repaired: bool,
+    recovered: usize,
+    lost: usize,
+    backup_path: Option<String>,
+}
+
+// Check the records file and rewrite it from whatever can be salvaged
+#[tauri::command]
+pub async fn repair_records(state: State<'_, AppState>) -> Result<RepairReport, AppError> {
+    let path = state.data_dir.join(records::RECORDS_FILE);
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::from("[]"),
+        Err(e) => return Err(format!("Failed to read records: {}", e).into()),
+    };
+    if let Ok(records) = serde_json::from_str::<Vec<JobRecord>>(&contents) {
+        return Ok(RepairReport { repaired: false, recovered: records.len(), lost: 0, backup_path: None });
+    }
+
+    let salvage = salvage(&contents);
+    let backup_path = tauri::async_runtime::spawn_blocking({
+        let path = path.clone();
+        move || back_up(&path)
+    })
+    .await
+    .map_err(|e| format!("Failed to back up records: {}", e))?
+    .map_err(|e| format!("Failed to back up records: {}", e))?;
+
+    // Records already loaded this session are newer than anything salvaged from disk
+    let recovered = salvage.records.len();
+    {
+        let mut job_records = state.job_records.lock().await;
+        for record in salvage.records {
+            job_records.entry(record.job_id.clone()).or_insert(record);
+        }
+    }
+    crate::persist_records(&state).await;
+
+    Ok(RepairReport {
+        repaired: true,
+        recovered,
+        lost: salvage.lost,
+        backup_path: Some(backup_path.to_string_lossy().to_string()),
+    })
+}
@@ -0,0 +1,307 @@
+// Full JSON results kept on disk for offline viewing
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::{clock, persist, result_server, storage, AppError, AppState};
+
+const RESULT_SUFFIX: &str = ".result.json";
+const CHECKSUM_SUFFIX: &str = ".result.sha256";
+// A user's corrected copy, kept next to the untouched original
+pub const EDITED_SUFFIX: &str = ".edited.json";
+
+#[derive(Debug, Serialize)]
+pub struct CachedResult {
+    job_id: String,
+    // Size of this result on disk
+    bytes: u64,
+    // How much the cache grew; negative when a smaller copy replaced a larger one
+    added_bytes: i64,
+    // Whole cache directory after storing it
+    cache_bytes: u64,
+}
+
+// Named after the job so the storage budget knows whose result it is
+fn cache_path(state: &AppState, job_id: &str) -> PathBuf {
+    state.data_dir.join(storage::CACHE_DIR).join(format!("{}{}", job_id, RESULT_SUFFIX))
+}
+
+// SHA-256 of the cached result, written alongside it
+fn checksum_path(state: &AppState, job_id: &str) -> PathBuf {
+    state.data_dir.join(storage::CACHE_DIR).join(format!("{}{}", job_id, CHECKSUM_SUFFIX))
+}
+
+pub fn edited_path(state: &AppState, job_id: &str) -> PathBuf {
+    state.data_dir.join(storage::CACHE_DIR).join(format!("{}{}", job_id, EDITED_SUFFIX))
+}
+
+pub fn sha256_hex(contents: &[u8]) -> String {
+    hex::encode(Sha256::digest(contents))
+}
+
+// One lock per cached job, so verify never reads a result while its checksum is being replaced
+#[derive(Default)]
+pub struct EntryLocks(StdMutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>);
+
+impl EntryLocks {
+    async fn lock(&self, job_id: &str) -> tokio::sync::OwnedMutexGuard<()> {
+        let entry = {
+            let mut locks = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            // Locks nobody holds or waits on are dropped rather than kept for every job ever cached
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(job_id.to_string()).or_default().clone()
+        };
+        entry.lock_owned().await
+    }
+}
+
+async fn remove_entry(state: &AppState, job_id: &str) {
+    let _ = tokio::fs::remove_file(cache_path(state, job_id)).await;
+    let _ = tokio::fs::remove_file(checksum_path(state, job_id)).await;
+}
+
+async fn remove_edit(state: &AppState, job_id: &str) {
+    let _ = tokio::fs::remove_file(edited_path(state, job_id)).await;
+}
+
+// Drop everything cached for a job that is being forgotten, the user's edits included
+pub async fn invalidate(state: &AppState, job_id: &str) {
+    remove_entry(state, job_id).await;
+    remove_edit(state, job_id).await;
+    result_server::invalidate(state, job_id).await;
+}
+
+// Replace the checksum in one step, so it never reads as half-written
+async fn write_checksum(state: &AppState, job_id: &str, contents: &[u8]) -> Result<(), String> {
+    let path = checksum_path(state, job_id);
+    let temp_path = path.with_extension("sha256.tmp");
+    tokio::fs::write(&temp_path, sha256_hex(contents))
+        .await
+        .map_err(|e| format!("Failed to write cache checksum: {}", e))?;
+    tokio::fs::rename(&temp_path, &path)
+        .await
+        .map_err(|e| format!("Failed to write cache checksum: {}", e))
+}
+
+async fn store(state: &AppState, job_id: &str) -> Result<CachedResult, AppError> {
+    let bytes = crate::fetch_result(state, job_id, "json", &[]).await?;
+    let result: serde_json::Value = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Failed to parse result: {}", e))?;
+
+    let path = cache_path(state, job_id);
+    let (bytes, previous_bytes) = {
+        let _entry = state.cache_entries.lock(job_id).await;
+        let previous_bytes = tokio::fs::metadata(&path).await.map(|m| m.len()).unwrap_or(0);
+        persist::save_json(&path, &result).await?;
+        let saved = tokio::fs::read(&path)
+            .await
+            .map_err(|e| format!("Failed to read cached result: {}", e))?;
+        write_checksum(state, job_id, &saved).await?;
+        (saved.len() as u64, previous_bytes)
+    };
+    // The cached copy is served from now on, not one fetched before it
+    result_server::invalidate(state, job_id).await;
+
+    Ok(CachedResult {
+        job_id: job_id.to_string(),
+        bytes,
+        added_bytes: bytes as i64 - previous_bytes as i64,
+        cache_bytes: storage::cache_bytes(state).await,
+    })
+}
+
+// Download the complete JSON result, with word timings, speakers and confidence, and keep it
+#[tauri::command]
+pub async fn cache_full_result(state: State<'_, AppState>, job_id: String) -> Result<CachedResult, AppError> {
+    store(&state, &job_id).await
+}
+
+// Where a job's result is cached, if it is
+pub fn cached_path(state: &AppState, job_id: &str) -> Option<PathBuf> {
+    Some(cache_path(state, job_id)).filter(|path| path.is_file())
+}
+
+// Raw bytes of a cached result, or None when it isn't cached
+pub async fn read(state: &AppState, job_id: &str) -> Result<Option<Vec<u8>>, String> {
+    match tokio::fs::read(cache_path(state, job_id)).await {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Failed to read cached result: {}", e)),
+    }
+}
+
+// A cached result, without touching the network
+#[tauri::command]
+pub async fn get_cached_result(state: State<'_, AppState>, job_id: String) -> Result<serde_json::Value, AppError> {
+    let contents = read(&state, &job_id)
+        .await?
+        .ok_or_else(|| format!("No cached result for job {}; cache it while online first", job_id))?;
+    let result = serde_json::from_slice(&contents)
+        .map_err(|e| format!("Cached result for job {} is unreadable: {}", job_id, e))?;
+    crate::note_access(&state, &job_id).await;
+    Ok(result)
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CacheReport {
+    checked: usize,
+    // Contents didn't match the stored checksum or weren't valid JSON
+    corrupt: usize,
+    // Cached for jobs no longer in the records
+    orphaned: usize,
+    // Older entries without a checksum that were valid and got one
+    backfilled: usize,
+    freed_bytes: u64,
+    // Pinned results fetched again because they were missing or just removed as corrupt
+    warmed: usize,
+}
+
+// Cached job ids, including ones with only a leftover checksum or edit file
+fn cached_job_ids(cache_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    let mut job_ids: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_suffix(RESULT_SUFFIX)
+                .or_else(|| name.strip_suffix(CHECKSUM_SUFFIX))
+                .or_else(|| name.strip_suffix(EDITED_SUFFIX))
+                .map(str::to_string)
+        })
+        .collect();
+    job_ids.sort();
+    job_ids.dedup();
+    job_ids
+}
+
+// What the cache holds for a job, as listed in state archives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub job_id: String,
+    pub bytes: u64,
+    // Stored checksum; None for entries that predate checksums
+    pub sha256: Option<String>,
+    // Whether a local edit is kept next to the result
+    pub edited: bool,
+}
+
+// Every cached result in cache_dir; reads the disk, so call it off the async runtime
+pub fn entries(cache_dir: &Path) -> Vec<CacheEntry> {
+    cached_job_ids(cache_dir)
+        .into_iter()
+        .filter_map(|job_id| {
+            let bytes = std::fs::metadata(cache_dir.join(format!("{}{}", job_id, RESULT_SUFFIX))).ok()?.len();
+            let sha256 = std::fs::read_to_string(cache_dir.join(format!("{}{}", job_id, CHECKSUM_SUFFIX)))
+                .ok()
+                .map(|checksum| checksum.trim().to_string());
+            let edited = cache_dir.join(format!("{}{}", job_id, EDITED_SUFFIX)).is_file();
+            Some(CacheEntry { job_id, bytes, sha256, edited })
+        })
+        .collect()
+}
+
+// Check every cached result against its checksum, removing corrupt entries and ones for forgotten jobs
+pub async fn verify(state: &AppState) -> CacheReport {
+    let cache_dir = state.data_dir.join(storage::CACHE_DIR);
+    let job_ids = tauri::async_runtime::spawn_blocking(move || cached_job_ids(&cache_dir))
+        .await
+        .unwrap_or_default();
+    let mut report = CacheReport::default();
+
+    for job_id in job_ids {
+        // Held while the entry is checked, so a result being cached right now isn't taken for corrupt
+        let _entry = state.cache_entries.lock(&job_id).await;
+        let path = cache_path(state, &job_id);
+        let known = state.job_records.lock().await.contains_key(&job_id);
+        let contents = tokio::fs::read(&path).await.ok();
+        let stored = tokio::fs::read_to_string(checksum_path(state, &job_id)).await.ok();
+        let size = contents.as_ref().map_or(0, |contents| contents.len() as u64);
+
+        if !known {
+            remove_edit(state, &job_id).await;
+        }
+        let Some(contents) = contents else {
+            // Only a checksum or an edit is left behind; a known job's edit stays
+            remove_entry(state, &job_id).await;
+            continue;
+        };
+        report.checked += 1;
+
+        if !known {
+            remove_entry(state, &job_id).await;
+            report.orphaned += 1;
+            report.freed_bytes += size;
+            continue;
+        }
+
+        let valid = match &stored {
+            Some(stored) => stored.trim() == sha256_hex(&contents),
+            None => serde_json::from_slice::<serde_json::Value>(&contents).is_ok(),
+        };
+        if !valid {
+            remove_entry(state, &job_id).await;
+            report.corrupt += 1;
+            report.freed_bytes += size;
+        } else if stored.is_none() && write_checksum(state, &job_id, &contents).await.is_ok() {
+            report.backfilled += 1;
+        }
+    }
+    report.warmed = warm(state).await;
+    report
+}
+
+// Cache the results of pinned, completed jobs that aren't cached, so they stay viewable
+// offline. Stops at the first failure, which usually means the backend is out of reach.
+async fn warm(state: &AppState) -> usize {
+    let now = clock::server_now(state).await;
+    let missing: Vec<String> = state.job_records.lock().await
+        .values()
+        .filter(|record| record.pinned && record.status == "complete")
+        .filter(|record| record.result_expires_at.is_none_or(|expires_at| expires_at > now))
+        .filter(|record| !cache_path(state, &record.job_id).is_file())
+        .map(|record| record.job_id.clone())
+        .collect();
+
+    let mut warmed = 0;
+    for job_id in missing {
+        match store(state, &job_id).await {
+            Ok(_) => warmed += 1,
+            Err(e) => {
+                eprintln!("Stopped warming the result cache at job {}: {}", job_id, e);
+                break;
+            }
+        }
+    }
+    warmed
+}
+
+// Verify the cache once after startup without holding it up
+pub fn verify_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::CacheVerify, None, async move {
+        let state = app.state::<AppState>();
+        if !state.settings.lock().await.verify_cache_on_startup {
+            return;
+        }
+        let report = verify(&state).await;
+        if report.corrupt > 0 || report.orphaned > 0 || report.warmed > 0 {
+            eprintln!(
+                "Result cache: removed {} corrupt and {} orphaned entries of {}, warmed {}",
+                report.corrupt, report.orphaned, report.checked, report.warmed
+            );
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn verify_cache(state: State<'_, AppState>) -> Result<CacheReport, AppError> {
+    Ok(verify(&state).await)
+}
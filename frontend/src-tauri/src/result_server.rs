@@ -0,0 +1,243 @@
+// Localhost HTTP server for previewing results where file:// URLs are blocked, e.g. a VTT track in a video element
+
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex as StdMutex, PoisonError};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::tasks::{self, TaskId, TaskKind};
+use crate::{callbacks, result_cache, storage, AppError, AppState};
+
+const MAX_REQUEST_HEADER_BYTES: usize = 16 * 1024;
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+// Copies fetched just for serving are named {job_id}.served.{format}
+const SERVED_INFIX: &str = ".served.";
+
+// Served files keyed by their URL path below the token
+type ServedFiles = Arc<StdMutex<HashMap<String, PathBuf>>>;
+
+pub struct ResultServer {
+    port: u16,
+    token: String,
+    task_id: TaskId,
+    files: ServedFiles,
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).as_deref() {
+        Some("vtt") => "text/vtt; charset=utf-8",
+        Some("srt") | Some("txt") => "text/plain; charset=utf-8",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("json") => "application/json",
+        Some("html") => "text/html; charset=utf-8",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+// Method, path and requested byte range of one request
+async fn read_head(stream: &mut TcpStream) -> Option<(String, String, Option<String>)> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        if buffer.len() > MAX_REQUEST_HEADER_BYTES {
+            return None;
+        }
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&buffer).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let range = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("range"))
+        .map(|(_, value)| value.trim().to_string());
+    Some((method, path, range))
+}
+
+// A single "bytes=start-end" range within size; anything fancier gets the whole file
+fn parse_range(range: &str, size: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => (size.saturating_sub(suffix.parse().ok()?), size.checked_sub(1)?),
+        (start, "") => (start.parse().ok()?, size.checked_sub(1)?),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(size.checked_sub(1)?)),
+    };
+    (start <= end).then_some((start, end))
+}
+
+async fn reply_status(stream: &mut TcpStream, status: &str) {
+    let reply = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+    let _ = stream.write_all(reply.as_bytes()).await;
+}
+
+async fn handle_request(stream: &mut TcpStream, token: &str, files: &ServedFiles) {
+    let Ok(Some((method, path, range))) = tokio::time::timeout(REQUEST_READ_TIMEOUT, read_head(stream)).await else {
+        return reply_status(stream, "400 Bad Request").await;
+    };
+    if method != "GET" && method != "HEAD" {
+        return reply_status(stream, "405 Method Not Allowed").await;
+    }
+
+    // Paths look like /{token}/{job_id}.{format}, maybe with a cache-busting query
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let mut parts = path.trim_start_matches('/').splitn(2, '/');
+    let (request_token, key) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    if !callbacks::constant_time_eq(request_token.as_bytes(), token.as_bytes()) {
+        return reply_status(stream, "403 Forbidden").await;
+    }
+    let file_path = files.lock().unwrap_or_else(PoisonError::into_inner).get(key).cloned();
+    let Some(file_path) = file_path else {
+        return reply_status(stream, "404 Not Found").await;
+    };
+    let Ok(mut file) = tokio::fs::File::open(&file_path).await else {
+        return reply_status(stream, "404 Not Found").await;
+    };
+    let size = file.metadata().await.map(|metadata| metadata.len()).unwrap_or(0);
+
+    let range = range.and_then(|range| parse_range(&range, size));
+    let (status, start, length, content_range) = match range {
+        Some((start, end)) => (
+            "206 Partial Content",
+            start,
+            end - start + 1,
+            format!("Content-Range: bytes {}-{}/{}\r\n", start, end, size),
+        ),
+        None => ("200 OK", 0, size, String::new()),
+    };
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Accept-Ranges: bytes\r\nAccess-Control-Allow-Origin: *\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type(&file_path),
+        length,
+        content_range
+    );
+    if stream.write_all(head.as_bytes()).await.is_err() || method == "HEAD" {
+        return;
+    }
+    if file.seek(SeekFrom::Start(start)).await.is_ok() {
+        let _ = tokio::io::copy(&mut file.take(length), stream).await;
+    }
+}
+
+async fn serve(listener: TcpListener, token: String, files: ServedFiles) {
+    loop {
+        let mut stream = callbacks::accept(&listener, "Result server").await;
+        let token = token.clone();
+        let files = files.clone();
+        tauri::async_runtime::spawn(async move {
+            handle_request(&mut stream, &token, &files).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+// Stop serving; called when the app exits
+pub fn shutdown(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let server = state.result_server.try_lock().ok().and_then(|mut server| server.take());
+    if let Some(server) = server {
+        tasks::kill(&state.tasks, server.task_id);
+    }
+}
+
+fn served_copy_path(state: &AppState, job_id: &str, format: &str) -> PathBuf {
+    state.data_dir.join(storage::CACHE_DIR).join(format!("{}{}{}", job_id, SERVED_INFIX, format))
+}
+
+// Stop serving a job's current copies and delete the ones fetched for serving, so the next
+// request for it gets the result as it is now
+pub async fn invalidate(state: &AppState, job_id: &str) {
+    if let Some(server) = state.result_server.lock().await.as_ref() {
+        server.files
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|key, _| key.rsplit_once('.').map(|(served_id, _)| served_id) != Some(job_id));
+    }
+
+    let prefix = format!("{}{}", job_id, SERVED_INFIX);
+    let Ok(mut entries) = tokio::fs::read_dir(state.data_dir.join(storage::CACHE_DIR)).await else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.strip_prefix(&prefix).is_some_and(|format| !format.contains('.')) {
+            let _ = tokio::fs::remove_file(entry.path()).await;
+        }
+    }
+}
+
+// A local copy of the result: a saved download, the cached JSON, or a fresh download into the cache
+async fn local_copy(state: &AppState, job_id: &str, format: &str) -> Result<PathBuf, AppError> {
+    let suffix = format!(".{}", format);
+    let downloads = state.job_records.lock().await
+        .get(job_id)
+        .map(|record| record.downloads.clone())
+        .ok_or_else(|| AppError::JobNotFound { job_id: job_id.to_string() })?;
+    if let Some(download) = downloads.iter().rev().find(|path| path.ends_with(&suffix) && Path::new(path).is_file()) {
+        return Ok(PathBuf::from(download));
+    }
+    if format == "json" {
+        if let Some(path) = result_cache::cached_path(state, job_id) {
+            return Ok(path);
+        }
+    }
+
+    // Named after the job so the storage budget can evict it
+    let path = served_copy_path(state, job_id, format);
+    let bytes = crate::fetch_result(state, job_id, format, &[]).await?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    tokio::fs::write(&path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    Ok(path)
+}
+
+// Serve a job's result over http://127.0.0.1, returning its URL
+#[tauri::command]
+pub async fn serve_result(state: State<'_, AppState>, job_id: String, format: String) -> Result<String, AppError> {
+    if format.is_empty() || !format.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid format '{}'", format).into());
+    }
+    let path = local_copy(&state, &job_id, &format).await?;
+
+    let mut server = state.result_server.lock().await;
+    if server.is_none() {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| format!("Failed to start result server: {}", e))?;
+        let port = listener.local_addr()
+            .map_err(|e| format!("Failed to start result server: {}", e))?
+            .port();
+        let token = callbacks::generate_secret()?;
+        let files = ServedFiles::default();
+        let task_id = tasks::spawn(&state.tasks, TaskKind::ResultServer, None, serve(listener, token.clone(), files.clone()));
+        *server = Some(ResultServer { port, token, task_id, files });
+    }
+    let Some(server) = server.as_ref() else {
+        return Err("Result server is not running".to_string().into());
+    };
+
+    let key = format!("{}.{}", job_id, format);
+    server.files.lock().unwrap_or_else(PoisonError::into_inner).insert(key.clone(), path);
+    Ok(format!("http://127.0.0.1:{}/{}/{}", server.port, server.token, key))
+}
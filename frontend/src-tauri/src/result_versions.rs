@@ -0,0 +1,157 @@
+// Picking between the results of a job that was reprocessed one or more times
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::JobRecord;
+use crate::{transcript, AppError, AppState};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultRanking {
+    // Highest average confidence, newest first among equals
+    #[default]
+    Confidence,
+    Newest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultVersion {
+    job_id: String,
+    model: Option<String>,
+    status: String,
+    submitted_at: u64,
+    completed_at: Option<u64>,
+    // Only known once the version has completed
+    average_confidence: Option<f64>,
+    // The version this one reprocessed
+    reprocessed_from: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BestResult {
+    #[serde(flatten)]
+    version: ResultVersion,
+    // How the version was chosen; recency when some completed version lacks confidence data
+    ranked_by: ResultRanking,
+    completed_versions: usize,
+}
+
+// The original job a reprocessing chain started from
+fn root_of<'a>(records: &'a HashMap<String, JobRecord>, job_id: &'a str) -> &'a str {
+    let mut current = job_id;
+    // Bounded so a corrupt record pointing back into its own chain can't loop forever
+    for _ in 0..records.len() {
+        match records.get(current).and_then(|record| record.escalated_from.as_deref()) {
+            Some(parent) if records.contains_key(parent) => current = parent,
+            _ => break,
+        }
+    }
+    current
+}
+
+// Every job sharing a reprocessing chain with job_id, oldest first
+async fn chain(state: &AppState, job_id: &str) -> Result<Vec<JobRecord>, AppError> {
+    let records = state.job_records.lock().await;
+    if !records.contains_key(job_id) {
+        return Err(AppError::JobNotFound { job_id: job_id.to_string() });
+    }
+    let root = root_of(&records, job_id);
+    let mut versions: Vec<JobRecord> = records.values()
+        .filter(|record| root_of(&records, &record.job_id) == root)
+        .cloned()
+        .collect();
+    versions.sort_by_key(|record| record.submitted_at);
+    Ok(versions)
+}
+
+// Average confidence of a completed version, computed once and kept on its record
+async fn confidence(state: &AppState, record: &JobRecord) -> Option<f64> {
+    if record.status != "complete" {
+        return None;
+    }
+    if record.average_confidence.is_some() {
+        return record.average_confidence;
+    }
+    let average = match transcript::load(state, &record.job_id).await {
+        Ok((transcript, _)) => transcript.average_confidence()?,
+        Err(e) => {
+            eprintln!("Failed to load result for job {}: {}", record.job_id, e);
+            return None;
+        }
+    };
+    if let Some(stored) = state.job_records.lock().await.get_mut(&record.job_id) {
+        stored.average_confidence = Some(average);
+    }
+    crate::persist_records(state).await;
+    Some(average)
+}
+
+async fn versions(state: &AppState, job_id: &str) -> Result<Vec<ResultVersion>, AppError> {
+    let mut versions = Vec::new();
+    for record in chain(state, job_id).await? {
+        let average_confidence = confidence(state, &record).await;
+        versions.push(ResultVersion {
+            job_id: record.job_id,
+            model: record.options.model,
+            status: record.status,
+            submitted_at: record.submitted_at,
+            completed_at: record.completed_at,
+            average_confidence,
+            reprocessed_from: record.escalated_from,
+        });
+    }
+    Ok(versions)
+}
+
+#[tauri::command]
+pub async fn list_result_versions(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<Vec<ResultVersion>, AppError> {
+    versions(&state, &job_id).await
+}
+
+#[tauri::command]
+pub async fn get_best_result(
+    state: State<'_, AppState>,
+    job_id: String,
+    ranking: Option<ResultRanking>,
+) -> Result<BestResult, AppError> {
+    let ranking = match ranking {
+        Some(ranking) => ranking,
+        None => state.settings.lock().await.result_ranking,
+    };
+    let completed: Vec<ResultVersion> = versions(&state, &job_id).await?
+        .into_iter()
+        .filter(|version| version.status == "complete")
+        .collect();
+    let completed_versions = completed.len();
+
+    // Scores are only comparable when every candidate has one
+    let ranked_by = match ranking {
+        ResultRanking::Confidence if completed.iter().all(|version| version.average_confidence.is_some()) => ResultRanking::Confidence,
+        _ => ResultRanking::Newest,
+    };
+    let newest = |version: &ResultVersion| (version.completed_at.unwrap_or(version.submitted_at), version.submitted_at);
+    let best = completed.into_iter().max_by(|a, b| match ranked_by {
+        ResultRanking::Confidence => a.average_confidence.unwrap_or_default()
+            .total_cmp(&b.average_confidence.unwrap_or_default())
+            .then_with(|| newest(a).cmp(&newest(b))),
+        ResultRanking::Newest => newest(a).cmp(&newest(b)),
+    });
+    let version = best.ok_or_else(|| format!("No version of job {} has completed", job_id))?;
+    Ok(BestResult { version, ranked_by, completed_versions })
+}
+
+#[tauri::command]
+pub async fn set_result_ranking(
+    state: State<'_, AppState>,
+    ranking: ResultRanking,
+) -> Result<(), AppError> {
+    state.settings.lock().await.result_ranking = ranking;
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
@@ -0,0 +1,111 @@
+// Which backend failures are worth retrying, and a retrying send for idempotent requests
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{api, AppError, AppState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    // Likely to succeed if tried again shortly
+    Retryable,
+    // Will fail the same way however often it is repeated
+    Fatal,
+    // Needs the user to change something first, e.g. free space or sign in
+    NeedsUserAction,
+}
+
+// Backend error codes, checked before the status; add new codes here
+const CODE_CLASSES: &[(&str, ErrorClass)] = &[
+    ("rate_limited", ErrorClass::Retryable),
+    ("model_loading", ErrorClass::Retryable),
+    ("worker_unavailable", ErrorClass::Retryable),
+    ("storage_full", ErrorClass::NeedsUserAction),
+    ("quota_exceeded", ErrorClass::NeedsUserAction),
+    ("file_too_large", ErrorClass::NeedsUserAction),
+    ("invalid_media", ErrorClass::Fatal),
+    ("unsupported_format", ErrorClass::Fatal),
+];
+
+// HTTP statuses, for errors without a known code; unlisted 4xx and 5xx are fatal
+const STATUS_CLASSES: &[(u16, ErrorClass)] = &[
+    (401, ErrorClass::NeedsUserAction),
+    (403, ErrorClass::NeedsUserAction),
+    (408, ErrorClass::Retryable),
+    (413, ErrorClass::NeedsUserAction),
+    (425, ErrorClass::Retryable),
+    (429, ErrorClass::Retryable),
+    (500, ErrorClass::Retryable),
+    (502, ErrorClass::Retryable),
+    (503, ErrorClass::Retryable),
+    (504, ErrorClass::Retryable),
+    (507, ErrorClass::NeedsUserAction),
+];
+
+// Attempts for a retried request, and the backoff before the second one
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(10);
+
+pub fn classify(status: u16, code: Option<&str>) -> ErrorClass {
+    let by_code = code.and_then(|code| CODE_CLASSES.iter().find(|(known, _)| *known == code));
+    let by_status = || STATUS_CLASSES.iter().find(|(known, _)| *known == status);
+    by_code.map(|(_, class)| *class)
+        .or_else(|| by_status().map(|(_, class)| *class))
+        .unwrap_or(ErrorClass::Fatal)
+}
+
+// Whether a status might be retryable; only those responses are read to check their code
+fn may_retry(status: reqwest::StatusCode) -> bool {
+    STATUS_CLASSES.iter().any(|(known, class)| *known == status.as_u16() && *class == ErrorClass::Retryable)
+}
+
+// Wait the backend asked for, if it said and it's reasonable
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let secs = response.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(Duration::from_secs(secs).min(MAX_RETRY_AFTER))
+}
+
+// Send an idempotent request, retrying transport failures and retryable errors with backoff.
+// Other responses, successful or not, are returned for the caller to handle.
+pub async fn send(
+    state: &AppState,
+    builder: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, AppError> {
+    let mut backoff = INITIAL_BACKOFF;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let last = attempt == MAX_ATTEMPTS;
+        // Bodies that can't be cloned can't be replayed either
+        let Some(request) = builder.try_clone().filter(|_| !last) else {
+            return api::send(state, builder).await.map_err(|message| AppError::Network { message });
+        };
+
+        let wait = match api::send(state, request).await {
+            Ok(response) if !may_retry(response.status()) => return Ok(response),
+            Ok(response) => {
+                let wait = retry_after(&response).unwrap_or(backoff);
+                let error = api::error_from_response(response).await;
+                if error.class() != ErrorClass::Retryable {
+                    return Err(error);
+                }
+                eprintln!("Retrying after attempt {} failed: {}", attempt, error);
+                wait
+            }
+            Err(message) => {
+                eprintln!("Retrying after attempt {} failed: {}", attempt, message);
+                backoff
+            }
+        };
+        tokio::time::sleep(wait).await;
+        backoff *= 2;
+    }
+    unreachable!("the last attempt always returns")
+}
@@ -0,0 +1,128 @@
+// Recognizing re-uploads of a source file: unchanged files reuse their job, edited ones become revisions
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use sha2::{Digest, Sha256};
+
+use crate::records::JobSource;
+use crate::{clock, AppError, AppState, ProcessOptions};
+
+// Content hash and modification time of a source file
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    pub hash: String,
+    pub mtime: Option<u64>,
+}
+
+pub enum Upload {
+    // The same content was already submitted with the same options
+    Duplicate(String),
+    // New content; the previous job for the same path, if any
+    New { revision_of: Option<String> },
+}
+
+// Fingerprint a source file. When its modification time matches the last upload from the
+// same path it hasn't been rewritten since, so that upload's hash is reused without reading it.
+pub async fn fingerprint(state: &AppState, path: &Path) -> Result<Fingerprint, String> {
+    let mtime = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_secs());
+    if let Some(hash) = unchanged_hash(state, path, mtime).await {
+        return Ok(Fingerprint { hash, mtime });
+    }
+
+    let path: PathBuf = path.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer).map_err(|e| format!("Failed to read file: {}", e))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(Fingerprint {
+            hash: hex::encode(hasher.finalize()),
+            mtime,
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to hash file: {}", e))?
+}
+
+// Hash of the latest upload from path, if the file still has the modification time it had then
+async fn unchanged_hash(state: &AppState, path: &Path, mtime: Option<u64>) -> Option<String> {
+    let mtime = mtime?;
+    let records = state.job_records.lock().await;
+    let latest = records.values()
+        .filter(|record| matches!(&record.source, JobSource::File { path: previous } if Path::new(previous) == path))
+        .filter(|record| record.source_hash.is_some())
+        .max_by_key(|record| record.submitted_at)?;
+    (latest.source_mtime == Some(mtime)).then(|| latest.source_hash.clone()).flatten()
+}
+
+// Decide whether an upload repeats an earlier job. Content is compared by hash, so a
+// touched but unchanged file still dedupes. Failed and cancelled jobs are never reused,
+// nor are jobs whose result has expired or that the backend no longer has; force skips
+// the check entirely.
+pub async fn classify(
+    state: &AppState,
+    path: &str,
+    fingerprint: &Fingerprint,
+    options: &ProcessOptions,
+    force: bool,
+) -> Upload {
+    let now = clock::server_now(state).await;
+    let options = serde_json::to_value(options).ok();
+    let (mut candidates, revision_of) = {
+        let records = state.job_records.lock().await;
+        let mut candidates: Vec<_> = records.values()
+            .filter(|_| !force)
+            .filter(|record| record.source_hash.as_deref() == Some(fingerprint.hash.as_str()))
+            .filter(|record| !matches!(record.status.as_str(), "error" | "cancelled"))
+            .filter(|record| record.result_expires_at.map_or(true, |expires_at| expires_at > now))
+            .filter(|record| serde_json::to_value(&record.options).ok() == options)
+            .map(|record| (record.submitted_at, record.job_id.clone()))
+            .collect();
+        candidates.sort();
+
+        let revision_of = records.values()
+            .filter(|record| matches!(&record.source, JobSource::File { path: previous } if previous == path))
+            .filter(|record| record.source_hash.is_some())
+            .max_by_key(|record| record.submitted_at)
+            .map(|record| record.job_id.clone());
+        (candidates, revision_of)
+    };
+
+    // Newest first, confirming each is still on the backend
+    while let Some((_, job_id)) = candidates.pop() {
+        if state.status_cache.lock().await.is_known_missing(&job_id) {
+            continue;
+        }
+        match crate::fetch_status(state, &job_id).await {
+            Err(AppError::JobNotFound { .. }) => continue,
+            Ok(status) if matches!(status.status.as_str(), "error" | "cancelled") => continue,
+            // Anything else, such as being offline, is left for the upload itself to hit
+            _ => return Upload::Duplicate(job_id),
+        }
+    }
+    Upload::New { revision_of }
+}
+
+// Store the fingerprint on a newly registered job
+pub async fn record(state: &AppState, job_id: &str, fingerprint: Fingerprint, revision_of: Option<String>) {
+    if let Some(record) = state.job_records.lock().await.get_mut(job_id) {
+        record.source_hash = Some(fingerprint.hash);
+        record.source_mtime = fingerprint.mtime;
+        record.revision_of = revision_of;
+    }
+    crate::persist_records(state).await;
+}
@@ -0,0 +1,179 @@
+// Searching within a transcript, from the offline cache when possible, and
+// selecting tracked jobs by their record
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::{JobRecord, JobSource};
+use crate::{transcript, AppError, AppState};
+
+// Longest pattern accepted, and the most compiled program memory it may use
+const MAX_QUERY_CHARS: usize = 1000;
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+// Matching segments returned; total_matches still counts every match
+const MAX_RESULTS: usize = 500;
+
+// Filter over tracked jobs; every field that is set must match
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JobQuery {
+    // "file", "segment", "url", "fetched" or "remote"
+    pub source_types: Option<Vec<String>>,
+    pub statuses: Option<Vec<String>>,
+    // Age bounds in seconds since submission
+    pub older_than_secs: Option<u64>,
+    pub newer_than_secs: Option<u64>,
+    pub group_id: Option<String>,
+    // Case-insensitive substring of the file path or URL
+    pub source_contains: Option<String>,
+}
+
+fn source_type(source: &JobSource) -> &'static str {
+    match source {
+        JobSource::File { .. } => "file",
+        JobSource::Segment { .. } => "segment",
+        JobSource::Url { .. } => "url",
+        JobSource::Fetched { .. } => "fetched",
+        JobSource::Remote => "remote",
+        JobSource::Pipe { .. } => "pipe",
+    }
+}
+
+impl JobQuery {
+    // Whether no field is set, so every job matches
+    pub fn is_empty(&self) -> bool {
+        self.source_types.is_none()
+            && self.statuses.is_none()
+            && self.older_than_secs.is_none()
+            && self.newer_than_secs.is_none()
+            && self.group_id.is_none()
+            && self.source_contains.is_none()
+    }
+
+    pub fn matches(&self, record: &JobRecord) -> bool {
+        let age = record.elapsed_secs();
+        let source = match &record.source {
+            JobSource::File { path } | JobSource::Segment { path, .. } | JobSource::Pipe { path } => path.as_str(),
+            JobSource::Url { url } | JobSource::Fetched { url } => url.as_str(),
+            JobSource::Remote => "",
+        };
+        self.source_types.as_ref().is_none_or(|types| types.iter().any(|t| t == source_type(&record.source)))
+            && self.statuses.as_ref().is_none_or(|statuses| statuses.contains(&record.status))
+            && self.older_than_secs.is_none_or(|secs| age >= secs)
+            && self.newer_than_secs.is_none_or(|secs| age < secs)
+            && self.group_id.as_ref().is_none_or(|group| record.options.group_id.as_ref() == Some(group))
+            && self.source_contains.as_ref().is_none_or(|needle| {
+                source.to_lowercase().contains(&needle.to_lowercase())
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    #[default]
+    Plain,
+    WholeWord,
+    Regex,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SegmentMatch {
+    segment_index: usize,
+    start: f64,
+    end: f64,
+    text: String,
+    // Character offsets into text, as [start, end) pairs
+    positions: Vec<(usize, usize)>,
+    // Neighbouring segments' text
+    context_before: Option<String>,
+    context_after: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResults {
+    total_matches: usize,
+    matching_segments: usize,
+    matches: Vec<SegmentMatch>,
+    // Whether the cached result was searched instead of downloading it
+    offline: bool,
+}
+
+// Compile the query; the regex engine runs in linear time, so size is the only thing to bound
+fn compile(query: &str, mode: SearchMode, case_sensitive: bool) -> Result<Regex, String> {
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    if query.chars().count() > MAX_QUERY_CHARS {
+        return Err(format!("Search query is longer than {} characters", MAX_QUERY_CHARS));
+    }
+    let pattern = match mode {
+        SearchMode::Plain => regex::escape(query),
+        SearchMode::WholeWord => format!(r"\b{}\b", regex::escape(query)),
+        SearchMode::Regex => query.to_string(),
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))?;
+
+    // A pattern that matches nothing would match between every character
+    if regex.is_match("") {
+        return Err("Search pattern matches empty text".to_string());
+    }
+    Ok(regex)
+}
+
+// Byte ranges to character offsets, which is what the UI indexes by
+fn char_positions(text: &str, regex: &Regex) -> Vec<(usize, usize)> {
+    regex.find_iter(text)
+        .map(|found| (text[..found.start()].chars().count(), text[..found.end()].chars().count()))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn search_transcript(
+    state: State<'_, AppState>,
+    job_id: String,
+    query: String,
+    case_sensitive: Option<bool>,
+    mode: Option<SearchMode>,
+) -> Result<SearchResults, AppError> {
+    let regex = compile(&query, mode.unwrap_or_default(), case_sensitive.unwrap_or(false))?;
+    let (transcript, offline) = transcript::load(&state, &job_id).await?;
+
+    let segments = &transcript.segments;
+    let mut total_matches = 0;
+    let mut matching_segments = 0;
+    let mut matches = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let positions = char_positions(&segment.text, &regex);
+        if positions.is_empty() {
+            continue;
+        }
+        total_matches += positions.len();
+        matching_segments += 1;
+        if matches.len() < MAX_RESULTS {
+            matches.push(SegmentMatch {
+                segment_index: index,
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.clone(),
+                positions,
+                context_before: index.checked_sub(1).map(|before| segments[before].text.clone()),
+                context_after: segments.get(index + 1).map(|after| after.text.clone()),
+            });
+        }
+    }
+
+    Ok(SearchResults {
+        total_matches,
+        matching_segments,
+        matches,
+        offline,
+    })
+}
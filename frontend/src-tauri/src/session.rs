@@ -0,0 +1,57 @@
+// Opaque UI session blob, so the app reopens where the user left off
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::unix_now;
+use crate::{persist, AppError, AppState};
+
+pub const SESSION_FILE: &str = "session.json";
+
+// Larger blobs belong in proper storage, not the session
+const MAX_SESSION_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSession {
+    // Owned by the frontend; bumped whenever its session layout changes
+    version: u32,
+    saved_at: u64,
+    session: String,
+}
+
+#[tauri::command]
+pub async fn save_session(
+    state: State<'_, AppState>,
+    json: String,
+    version: u32,
+) -> Result<(), AppError> {
+    if json.len() > MAX_SESSION_BYTES {
+        return Err(format!("Session is too large ({} bytes, limit {})", json.len(), MAX_SESSION_BYTES).into());
+    }
+    serde_json::from_str::<serde_json::Value>(&json)
+        .map_err(|e| format!("Session is not valid JSON: {}", e))?;
+
+    let stored = StoredSession {
+        version,
+        saved_at: unix_now(),
+        session: json,
+    };
+    persist::save_json(&state.data_dir.join(SESSION_FILE), &stored).await?;
+    Ok(())
+}
+
+// The saved session, or None when there is none or it was saved in another version's format
+#[tauri::command]
+pub async fn load_session(
+    state: State<'_, AppState>,
+    version: u32,
+) -> Result<Option<String>, AppError> {
+    let path = state.data_dir.join(SESSION_FILE);
+    let stored: Option<StoredSession> = tokio::task::spawn_blocking(move || persist::load_json(&path))
+        .await
+        .map_err(|e| format!("Failed to load session: {}", e))?;
+
+    Ok(stored
+        .filter(|stored| stored.version == version)
+        .map(|stored| stored.session))
+}
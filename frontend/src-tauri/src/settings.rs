@@ -0,0 +1,332 @@
+// User settings persisted in the app config directory
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use tauri::State;
+
+use crate::idle::{self, IdleExpiry};
+use crate::network::{self, IpFamily};
+use crate::FilePosition;
+use crate::postprocess::{self, PostProcessConfig};
+use crate::presets::Preset;
+use crate::progress_events::{DEFAULT_EVENTS_PER_SEC, MAX_EVENTS_PER_SEC};
+use crate::result_versions::ResultRanking;
+use crate::transfers::{self, TransferPriority};
+use crate::{AppError, AppState, ProcessOptions};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+const DEFAULT_API_URL: &str = "http://localhost:8000";
+const DEFAULT_STATUS_CACHE_TTL_MS: u64 = 500;
+const DEFAULT_POLL_MIN_INTERVAL_MS: u64 = 500;
+const DEFAULT_POLL_MAX_INTERVAL_MS: u64 = 10_000;
+const DEFAULT_MIN_CONCURRENCY: usize = 1;
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+const DEFAULT_AUTO_IMPROVE_THRESHOLD: f64 = 0.6;
+
+// Values past these still work but are probably typos
+const MAX_SENSIBLE_CACHE_TTL_MS: u64 = 60_000;
+const MAX_SENSIBLE_POLL_INTERVAL_MS: u64 = 10 * 60 * 1000;
+const MAX_SENSIBLE_CONCURRENCY: usize = 64;
+const MIN_SENSIBLE_BUDGET_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    // The setting will make something fail
+    Error,
+    // Allowed, but likely not what was meant
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingFinding {
+    pub field: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl std::fmt::Display for SettingFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} in {}: {}", self.severity, self.field, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Base URL of the backend
+    pub api_url: String,
+    pub status_cache_ttl_ms: u64,
+    // HMAC key for request signing; never logged
+    pub signing_key: Option<String>,
+    // Bearer credentials; never logged
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    // Bounds for the adaptive job poller
+    pub poll_min_interval_ms: u64,
+    pub poll_max_interval_ms: u64,
+    // Named processing presets keyed by name
+    pub presets: BTreeMap<String, Preset>,
+    // Cap on disk used by caches, history and downloads; None means unlimited
+    pub storage_budget_bytes: Option<u64>,
+    // Limit applied to jobs submitted without their own max_duration_secs
+    pub default_max_duration_secs: Option<u64>,
+    // Source address for backend connections, for multi-homed or IPv6-only hosts
+    pub local_address: Option<IpAddr>,
+    pub ip_family: IpFamily,
+    // Appended to the User-Agent, e.g. to tag a deployment
+    pub user_agent_suffix: Option<String>,
+    // PEM bundle of extra root certificates, e.g. an enterprise CA
+    pub ca_bundle_path: Option<String>,
+    // Which direction gets more of the shared transfer capacity
+    pub transfer_priority: TransferPriority,
+    // Memory download chunk buffers may use at once, shared by every transfer
+    pub transfer_buffer_budget_bytes: u64,
+    // Check the result cache for corrupt and orphaned entries after launch
+    pub verify_cache_on_startup: bool,
+    // Bounds for the adaptive number of batch and queue submissions in flight
+    pub min_concurrency: usize,
+    pub max_concurrency: usize,
+    // auto_improve reprocesses with fallback_model when average confidence is below the threshold
+    pub auto_improve_threshold: f64,
+    pub fallback_model: Option<String>,
+    // Opt-in: hold uploads and downloads while on battery or a metered connection
+    pub pause_on_battery: bool,
+    pub pause_on_metered: bool,
+    // Most job-progress events sent per job each second; extra updates are coalesced
+    pub progress_events_per_sec: u32,
+    // Opt-in script run on each completed result, limited to allowlisted executables
+    pub post_process: Option<PostProcessConfig>,
+    pub post_process_allowlist: Vec<String>,
+    // Whether uploads send the media part before or after the text fields
+    pub multipart_file_position: FilePosition,
+    // How get_best_result chooses among a job's reprocessed versions
+    pub result_ranking: ResultRanking,
+    // Opt-in: forget finished jobs left untouched this long, after a warning
+    pub idle_expiry: Option<IdleExpiry>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            api_url: DEFAULT_API_URL.to_string(),
+            status_cache_ttl_ms: DEFAULT_STATUS_CACHE_TTL_MS,
+            signing_key: None,
+            access_token: None,
+            refresh_token: None,
+            poll_min_interval_ms: DEFAULT_POLL_MIN_INTERVAL_MS,
+            poll_max_interval_ms: DEFAULT_POLL_MAX_INTERVAL_MS,
+            presets: BTreeMap::new(),
+            storage_budget_bytes: None,
+            default_max_duration_secs: None,
+            local_address: None,
+            ip_family: IpFamily::Auto,
+            user_agent_suffix: None,
+            ca_bundle_path: None,
+            transfer_priority: TransferPriority::Balanced,
+            transfer_buffer_budget_bytes: transfers::DEFAULT_BUFFER_BUDGET_BYTES,
+            verify_cache_on_startup: true,
+            min_concurrency: DEFAULT_MIN_CONCURRENCY,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            auto_improve_threshold: DEFAULT_AUTO_IMPROVE_THRESHOLD,
+            fallback_model: None,
+            pause_on_battery: false,
+            pause_on_metered: false,
+            progress_events_per_sec: DEFAULT_EVENTS_PER_SEC,
+            post_process: None,
+            post_process_allowlist: Vec::new(),
+            multipart_file_position: FilePosition::First,
+            result_ranking: ResultRanking::Confidence,
+            idle_expiry: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn status_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.status_cache_ttl_ms)
+    }
+
+    pub fn poll_interval_bounds(&self) -> (Duration, Duration) {
+        (
+            Duration::from_millis(self.poll_min_interval_ms),
+            Duration::from_millis(self.poll_max_interval_ms),
+        )
+    }
+
+    // Copy with credentials stripped, for export and display
+    pub fn without_secrets(&self) -> Self {
+        Settings {
+            signing_key: None,
+            access_token: None,
+            refresh_token: None,
+            ..self.clone()
+        }
+    }
+
+    // Every problem with the settings, without changing them
+    pub fn check(&self) -> Vec<SettingFinding> {
+        let mut findings = Vec::new();
+        let mut error = |field: &str, message: String| findings.push(SettingFinding {
+            field: field.to_string(),
+            severity: Severity::Error,
+            message,
+        });
+        // Collected apart so errors are listed first
+        let mut warnings = Vec::new();
+        let mut warning = |field: &str, message: String| warnings.push(SettingFinding {
+            field: field.to_string(),
+            severity: Severity::Warning,
+            message,
+        });
+
+        if let Err(e) = network::api_url(self) {
+            error("api_url", e);
+        }
+        if self.signing_key.as_deref().is_some_and(|key| key.trim().is_empty()) {
+            error("signing_key", "is set but empty".to_string());
+        }
+        if self.status_cache_ttl_ms > MAX_SENSIBLE_CACHE_TTL_MS {
+            warning("status_cache_ttl_ms", format!("{}ms means job status can be over a minute old", self.status_cache_ttl_ms));
+        }
+        if self.poll_min_interval_ms == 0 || self.poll_min_interval_ms > self.poll_max_interval_ms {
+            error("poll_min_interval_ms", format!(
+                "{}ms must be above zero and at most poll_max_interval_ms ({}ms)",
+                self.poll_min_interval_ms, self.poll_max_interval_ms
+            ));
+        } else if self.poll_max_interval_ms > MAX_SENSIBLE_POLL_INTERVAL_MS {
+            warning("poll_max_interval_ms", format!("{}ms between polls is over ten minutes", self.poll_max_interval_ms));
+        }
+
+        for (key, preset) in &self.presets {
+            if preset.name != *key {
+                warning(&format!("presets.{}", key), format!("is stored under a different name ('{}')", preset.name));
+            }
+            for field_error in preset.options.field_errors() {
+                error(&format!("presets.{}.{}", key, field_error.field), field_error.reason);
+            }
+            if let Some(format) = preset.default_format.as_ref().filter(|format| !is_format_name(format)) {
+                error(&format!("presets.{}.default_format", key), format!("'{}' is not a valid format name", format));
+            }
+        }
+
+        if self.storage_budget_bytes.is_some_and(|bytes| bytes < MIN_SENSIBLE_BUDGET_BYTES) {
+            warning("storage_budget_bytes", "is under 1 MiB, so almost nothing will be kept".to_string());
+        }
+        if self.default_max_duration_secs == Some(0) {
+            error("default_max_duration_secs", "must be above zero; leave it unset for no limit".to_string());
+        }
+        if let Some(address) = &self.local_address {
+            if !self.ip_family.matches(address) {
+                error("local_address", format!("{} is not an {:?} address", address, self.ip_family));
+            }
+        }
+        if let Some(suffix) = &self.user_agent_suffix {
+            if suffix.len() > 64 || !suffix.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+                error("user_agent_suffix", format!("'{}' is not a valid user agent suffix", suffix));
+            }
+        }
+        if let Err(e) = network::load_ca_bundle(self) {
+            error("ca_bundle_path", e);
+        }
+
+        if !(transfers::MIN_BUFFER_BUDGET_BYTES..=transfers::MAX_BUFFER_BUDGET_BYTES).contains(&self.transfer_buffer_budget_bytes) {
+            error("transfer_buffer_budget_bytes", format!(
+                "{} must be between {} and {}",
+                self.transfer_buffer_budget_bytes, transfers::MIN_BUFFER_BUDGET_BYTES, transfers::MAX_BUFFER_BUDGET_BYTES
+            ));
+        }
+        if self.min_concurrency == 0 || self.min_concurrency > self.max_concurrency {
+            error("min_concurrency", format!(
+                "{} must be above zero and at most max_concurrency ({})",
+                self.min_concurrency, self.max_concurrency
+            ));
+        } else if self.max_concurrency > MAX_SENSIBLE_CONCURRENCY {
+            warning("max_concurrency", format!("{} submissions at once is likely to overload the backend", self.max_concurrency));
+        }
+        if !(0.0..=1.0).contains(&self.auto_improve_threshold) {
+            error("auto_improve_threshold", format!("{} must be between 0 and 1", self.auto_improve_threshold));
+        }
+        let fallback = ProcessOptions { model: self.fallback_model.clone(), ..Default::default() };
+        for field_error in fallback.field_errors() {
+            error("fallback_model", field_error.reason);
+        }
+        if !(1..=MAX_EVENTS_PER_SEC).contains(&self.progress_events_per_sec) {
+            error("progress_events_per_sec", format!(
+                "{} must be between 1 and {}",
+                self.progress_events_per_sec, MAX_EVENTS_PER_SEC
+            ));
+        }
+
+        if let Some(config) = &self.post_process {
+            match postprocess::canonical_program(&config.program) {
+                Ok(program) if !postprocess::is_allowed(&self.post_process_allowlist, &program) => {
+                    error("post_process.program", format!("{} is not on the post-processing allowlist", program.display()));
+                }
+                Ok(_) => {}
+                Err(e) => error("post_process.program", e.to_string()),
+            }
+            if let Some(format) = config.format.as_ref().filter(|format| !is_format_name(format)) {
+                error("post_process.format", format!("'{}' is not a valid format name", format));
+            }
+            if let Some(dest_dir) = config.dest_dir.as_ref().filter(|dir| Path::new(dir).is_file()) {
+                error("post_process.dest_dir", format!("{} is a file, not a folder", dest_dir));
+            }
+        }
+        for program in &self.post_process_allowlist {
+            if !Path::new(program).is_file() {
+                warning("post_process_allowlist", format!("{} no longer exists", program));
+            }
+        }
+
+        if let Some(expiry) = self.idle_expiry.as_ref().filter(|expiry| expiry.after_secs < idle::MIN_IDLE_SECS) {
+            error("idle_expiry.after_secs", format!("{}s is under the {}s minimum", expiry.after_secs, idle::MIN_IDLE_SECS));
+        }
+
+        findings.extend(warnings);
+        findings
+    }
+
+    pub fn has_secrets(&self) -> bool {
+        self.signing_key.is_some() || self.access_token.is_some() || self.refresh_token.is_some()
+    }
+
+    // Copy with credentials taken from other
+    pub fn with_secrets_from(self, other: &Settings) -> Self {
+        Settings {
+            signing_key: other.signing_key.clone(),
+            access_token: other.access_token.clone(),
+            refresh_token: other.refresh_token.clone(),
+            ..self
+        }
+    }
+
+    // Copy with the post-processing hook and allowlist taken from other. Both are only
+    // set through the confirm dialog, so archives and profiles never bring their own.
+    pub fn with_post_process_from(self, other: &Settings) -> Self {
+        Settings {
+            post_process: other.post_process.clone(),
+            post_process_allowlist: other.post_process_allowlist.clone(),
+            ..self
+        }
+    }
+}
+
+// Format names end up in file names and URLs, so they are kept to letters and digits
+pub fn is_format_name(format: &str) -> bool {
+    !format.is_empty() && format.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[tauri::command]
+pub async fn validate_settings(state: State<'_, AppState>) -> Result<Vec<SettingFinding>, AppError> {
+    let settings = state.settings.lock().await.clone();
+    // Checks read the CA bundle and post-processing paths from disk
+    tauri::async_runtime::spawn_blocking(move || settings.check())
+        .await
+        .map_err(|e| format!("Failed to check settings: {}", e).into())
+}
@@ -0,0 +1,80 @@
+// Temporary share links minted by the backend
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{api, AppError, AppState};
+
+// Bounds the backend accepts for link lifetimes
+const MIN_SHARE_EXPIRY_SECS: u64 = 60;
+const MAX_SHARE_EXPIRY_SECS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShareLink {
+    link_id: String,
+    url: String,
+    expires_at: u64,
+}
+
+fn unsupported() -> AppError {
+    AppError::Unsupported {
+        feature: "share links".to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn create_share_link(
+    state: State<'_, AppState>,
+    job_id: String,
+    format: String,
+    expires_in_secs: u64,
+) -> Result<ShareLink, AppError> {
+    if !(MIN_SHARE_EXPIRY_SECS..=MAX_SHARE_EXPIRY_SECS).contains(&expires_in_secs) {
+        return Err(format!(
+            "Link expiry must be between {} and {} seconds",
+            MIN_SHARE_EXPIRY_SECS, MAX_SHARE_EXPIRY_SECS
+        )
+        .into());
+    }
+
+    let body = serde_json::json!({ "expires_in_secs": expires_in_secs });
+    let request = state.client().post(format!("{}/share/{}/{}", state.api_url(), job_id, format))
+        .json(&body);
+    let response = api::send(&state, request).await?;
+
+    match response.status() {
+        reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED => {
+            return Err(unsupported());
+        }
+        // A 404 is either a missing job or a backend without the endpoint
+        reqwest::StatusCode::NOT_FOUND => {
+            return Err(if crate::check_job_exists(&state, &job_id).await? {
+                unsupported()
+            } else {
+                AppError::JobNotFound { job_id }
+            });
+        }
+        status if !status.is_success() => return Err(api::error_from_response(response).await),
+        _ => {}
+    }
+
+    let link = response.json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(link)
+}
+
+#[tauri::command]
+pub async fn revoke_share_link(
+    state: State<'_, AppState>,
+    link_id: String,
+) -> Result<(), AppError> {
+    let request = state.client().delete(format!("{}/share/{}", state.api_url(), link_id));
+    let response = api::send(&state, request).await?;
+
+    match response.status() {
+        reqwest::StatusCode::METHOD_NOT_ALLOWED | reqwest::StatusCode::NOT_IMPLEMENTED => Err(unsupported()),
+        status if !status.is_success() => Err(api::error_from_response(response).await),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,83 @@
+// Graceful quit: stop new submissions, let transfers drain, save state, then exit
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{persist, queue, AppError, AppState};
+
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+// Lets the reply reach the frontend before the process goes away
+const EXIT_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuitReport {
+    // Every transfer finished before the timeout
+    drained: bool,
+    uploads_in_flight: u32,
+    downloads_in_flight: u32,
+    // Still running on the backend; their records are kept so polling resumes next launch
+    processing_jobs: Vec<String>,
+}
+
+pub fn in_progress(state: &AppState) -> bool {
+    state.shutting_down.load(Ordering::Relaxed)
+}
+
+async fn save_state(state: &AppState) {
+    crate::persist_records(state).await;
+    if let Err(e) = crate::persist_settings(state).await {
+        eprintln!("Failed to save settings: {}", e);
+    }
+    let items = state.queue.lock().await.clone();
+    if let Err(e) = persist::save_json(&state.data_dir.join(queue::QUEUE_FILE), &items).await {
+        eprintln!("Failed to save queue: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn quit_gracefully(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    timeout_secs: Option<u64>,
+    persist_state: Option<bool>,
+) -> Result<QuitReport, AppError> {
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).min(MAX_TIMEOUT_SECS));
+    state.shutting_down.store(true, Ordering::Relaxed);
+    // Transfers held back by a battery or metered pause would never drain otherwise
+    state.transfers.set_paused(false);
+
+    let started = Instant::now();
+    let (uploads_in_flight, downloads_in_flight) = loop {
+        let in_flight = state.transfers.in_flight();
+        if in_flight == (0, 0) || started.elapsed() >= timeout {
+            break in_flight;
+        }
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    };
+
+    if persist_state.unwrap_or(true) {
+        save_state(&state).await;
+    }
+
+    let mut processing_jobs: Vec<String> = state.processing_jobs.lock().await.iter().cloned().collect();
+    processing_jobs.sort();
+    let report = QuitReport {
+        drained: uploads_in_flight == 0 && downloads_in_flight == 0,
+        uploads_in_flight,
+        downloads_in_flight,
+        processing_jobs,
+    };
+    let _ = app.emit_all("app-quitting", report.clone());
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(EXIT_DELAY).await;
+        app.exit(0);
+    });
+    Ok(report)
+}
@@ -0,0 +1,285 @@
+// Trimming silence from uploads, with a map back to the original timeline
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::records::JobSource;
+use crate::transcript::Transcript;
+use crate::{media, merge, presets, timeouts, AppError, AppState, ProcessOptions, UploadForm};
+
+// Silence detected within this of either end counts as leading or trailing
+const EDGE_TOLERANCE_SECS: f64 = 0.05;
+
+// Trimming less than this isn't worth a re-encode
+const MIN_TRIMMED_SECS: f64 = 1.0;
+
+// Energy threshold and guards for silence trimming; the defaults only cut clear silence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SilenceTrim {
+    // Audio quieter than this counts as silence
+    pub threshold_db: f64,
+    // Shorter quiet stretches are left alone
+    pub min_silence_secs: f64,
+    // Silence kept next to speech so word onsets aren't clipped
+    pub padding_secs: f64,
+    // Also shorten internal gaps longer than max_gap_secs
+    pub trim_internal: bool,
+    pub max_gap_secs: f64,
+    // Upload the original instead when trimming would remove more than this fraction
+    pub max_trim_fraction: f64,
+}
+
+impl Default for SilenceTrim {
+    fn default() -> Self {
+        SilenceTrim {
+            threshold_db: -40.0,
+            min_silence_secs: 0.5,
+            padding_secs: 0.25,
+            trim_internal: false,
+            max_gap_secs: 2.0,
+            max_trim_fraction: 0.5,
+        }
+    }
+}
+
+impl SilenceTrim {
+    fn validate(&self) -> Result<(), String> {
+        if !(-90.0..0.0).contains(&self.threshold_db) {
+            return Err(format!("threshold_db must be between -90 and 0, got {}", self.threshold_db));
+        }
+        if !(self.min_silence_secs.is_finite() && self.min_silence_secs > 0.0) {
+            return Err(format!("min_silence_secs must be above zero, got {}", self.min_silence_secs));
+        }
+        if !(self.padding_secs.is_finite() && self.padding_secs >= 0.0) {
+            return Err(format!("padding_secs must not be negative, got {}", self.padding_secs));
+        }
+        if !(self.max_gap_secs.is_finite() && self.max_gap_secs > 2.0 * self.padding_secs) {
+            return Err(format!("max_gap_secs must be more than twice padding_secs, got {}", self.max_gap_secs));
+        }
+        if !(self.max_trim_fraction > 0.0 && self.max_trim_fraction < 1.0) {
+            return Err(format!("max_trim_fraction must be between 0 and 1, got {}", self.max_trim_fraction));
+        }
+        Ok(())
+    }
+}
+
+// A stretch of the original media kept in the upload, in upload order
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct KeptRange {
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+// Map a time in the trimmed upload back to the original recording
+fn to_source_time(kept: &[KeptRange], secs: f64) -> f64 {
+    let mut elapsed = 0.0;
+    for range in kept {
+        let length = range.end_secs - range.start_secs;
+        if secs < elapsed + length {
+            return range.start_secs + (secs - elapsed).max(0.0);
+        }
+        elapsed += length;
+    }
+    // Past the end, e.g. from encoder padding: run on from the last kept stretch
+    kept.last().map_or(secs, |range| range.end_secs + (secs - elapsed))
+}
+
+// Shift a transcript from the trimmed upload onto the original timeline
+fn restore_timeline(kept: &[KeptRange], transcript: &mut Transcript) {
+    for segment in &mut transcript.segments {
+        segment.start = to_source_time(kept, segment.start);
+        segment.end = to_source_time(kept, segment.end);
+        for word in &mut segment.words {
+            word.start = to_source_time(kept, word.start);
+            word.end = to_source_time(kept, word.end);
+        }
+    }
+}
+
+// A trimmed job's result on the original timeline, or None for jobs that weren't trimmed.
+// The backend only knows the trimmed timeline, so its JSON is restored and every format
+// rendered from that here; layout options can't be honoured that way and are refused.
+pub async fn restored_result(
+    state: &AppState,
+    job_id: &str,
+    format: &str,
+    query: &[(String, String)],
+) -> Result<Option<Vec<u8>>, AppError> {
+    let kept = state.job_records.lock().await
+        .get(job_id)
+        .and_then(|record| record.trimmed_ranges.clone());
+    let Some(kept) = kept else {
+        return Ok(None);
+    };
+    if !query.is_empty() {
+        return Err(AppError::Unsupported {
+            feature: format!("{} format options for silence-trimmed jobs", format),
+        });
+    }
+
+    let bytes = crate::fetch_backend_result(state, job_id, "json", &[]).await?;
+    let mut transcript: Transcript = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Failed to parse transcript: {}", e))?;
+    restore_timeline(&kept, &mut transcript);
+    let rendered = match format {
+        "json" => serde_json::to_vec(&transcript).map_err(|e| format!("Failed to encode transcript: {}", e))?,
+        "srt" | "vtt" | "txt" | "md" => merge::render(transcript.segments, transcript.language, format)?.into_bytes(),
+        other => return Err(AppError::Unsupported {
+            feature: format!("{} results for silence-trimmed jobs", other),
+        }),
+    };
+    Ok(Some(rendered))
+}
+
+// Whether any of the jobs had silence trimmed before upload
+pub async fn any_trimmed(state: &AppState, job_ids: &[String]) -> bool {
+    let records = state.job_records.lock().await;
+    job_ids.iter().any(|job_id| records.get(job_id).is_some_and(|record| record.trimmed_ranges.is_some()))
+}
+
+// The parts of [0, duration) to keep once the chosen silences are cut
+fn kept_ranges(silences: &[(f64, Option<f64>)], duration: f64, trim: &SilenceTrim) -> Vec<KeptRange> {
+    let mut cuts = Vec::new();
+    for &(start, end) in silences {
+        let end = end.unwrap_or(duration).min(duration);
+        if start <= EDGE_TOLERANCE_SECS {
+            cuts.push((0.0, end - trim.padding_secs));
+        } else if end >= duration - EDGE_TOLERANCE_SECS {
+            cuts.push((start + trim.padding_secs, duration));
+        } else if trim.trim_internal && end - start > trim.max_gap_secs {
+            cuts.push((start + trim.padding_secs, end - trim.padding_secs));
+        }
+    }
+
+    let mut kept = Vec::new();
+    let mut position = 0.0;
+    for (start, end) in cuts {
+        if end <= start || end <= position {
+            continue;
+        }
+        if start > position {
+            kept.push(KeptRange { start_secs: position, end_secs: start });
+        }
+        position = end;
+    }
+    if position < duration {
+        kept.push(KeptRange { start_secs: position, end_secs: duration });
+    }
+    kept
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrimmedUploadResponse {
+    job_id: String,
+    trimmed: bool,
+    original_secs: f64,
+    uploaded_secs: f64,
+    leading_secs: f64,
+    trailing_secs: f64,
+    internal_secs: f64,
+    // Why the original file was uploaded instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped_reason: Option<String>,
+}
+
+struct Trimmed {
+    temp: media::TempFile,
+    kept: Vec<KeptRange>,
+}
+
+// Cut silence from source into a temp file, or say why it was left alone
+async fn trim(source: &Path, duration: f64, trim: &SilenceTrim) -> Result<Trimmed, String> {
+    let silences = media::detect_silences(source, trim.threshold_db, trim.min_silence_secs).await?;
+    let kept = kept_ranges(&silences, duration, trim);
+    let kept_secs: f64 = kept.iter().map(|range| range.end_secs - range.start_secs).sum();
+    let trimmed_secs = duration - kept_secs;
+
+    // Quiet recordings can read as mostly silence; better to upload them whole
+    if kept.is_empty() || trimmed_secs > duration * trim.max_trim_fraction {
+        return Err(format!(
+            "Trimming would remove {:.1}s of {:.1}s, more than the {:.0}% allowed",
+            trimmed_secs, duration, trim.max_trim_fraction * 100.0
+        ));
+    }
+    if trimmed_secs < MIN_TRIMMED_SECS {
+        return Err(format!("Only {:.1}s of silence found", trimmed_secs));
+    }
+
+    let temp = media::TempFile::new("trimmed", "flac");
+    let ranges: Vec<(f64, f64)> = kept.iter().map(|range| (range.start_secs, range.end_secs)).collect();
+    media::keep_audio_ranges(source, &ranges, temp.path()).await?;
+    Ok(Trimmed { temp, kept })
+}
+
+// Upload a file with its silence cut, falling back to the raw file
+#[tauri::command]
+pub async fn upload_file_trimmed(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    thresholds: Option<SilenceTrim>,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    max_duration_secs: Option<u64>,
+) -> Result<TrimmedUploadResponse, AppError> {
+    let thresholds = thresholds.unwrap_or_default();
+    thresholds.validate()?;
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+
+    let source = PathBuf::from(&path);
+    let stem = source.file_stem()
+        .ok_or_else(|| "Invalid file path".to_string())?
+        .to_string_lossy()
+        .to_string();
+    let original_secs = media::probe_duration(&source).await?;
+
+    // The temp file is removed when it goes out of scope, including on error
+    let (api_response, kept, skipped_reason) = match trim(&source, original_secs, &thresholds).await {
+        Ok(trimmed) => {
+            let file_name = format!("{}.flac", stem);
+            let api_response = crate::send_file(&state, trimmed.temp.path(), file_name, &options, &UploadForm::default()).await?;
+            (api_response, Some(trimmed.kept), None)
+        }
+        Err(reason) => {
+            eprintln!("Not trimming {}: {}", path, reason);
+            let file_name = source.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| stem.clone());
+            let api_response = crate::send_file(&state, &source, file_name, &options, &UploadForm::default()).await?;
+            (api_response, None, Some(reason))
+        }
+    };
+
+    crate::track_job(&state, &api_response.job_id, JobSource::File { path }, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
+
+    let ranges = kept.clone().unwrap_or_default();
+    let leading_secs = ranges.first().map_or(0.0, |range| range.start_secs);
+    let trailing_secs = ranges.last().map_or(0.0, |range| original_secs - range.end_secs);
+    let uploaded_secs = if ranges.is_empty() {
+        original_secs
+    } else {
+        ranges.iter().map(|range| range.end_secs - range.start_secs).sum()
+    };
+    if let Some(kept) = kept {
+        if let Some(record) = state.job_records.lock().await.get_mut(&api_response.job_id) {
+            record.source_duration_secs = Some(original_secs);
+            record.trimmed_ranges = Some(kept);
+        }
+        crate::persist_records(&state).await;
+    }
+
+    Ok(TrimmedUploadResponse {
+        job_id: api_response.job_id,
+        trimmed: skipped_reason.is_none(),
+        original_secs,
+        uploaded_secs,
+        leading_secs,
+        trailing_secs,
+        internal_secs: (original_secs - uploaded_secs - leading_secs - trailing_secs).max(0.0),
+        skipped_reason,
+    })
+}
@@ -0,0 +1,117 @@
+// How fast the backend transcribes relative to the length of the media
+
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::records::{JobRecord, JobSource};
+use crate::{media, AppError, AppState};
+
+// Completed jobs the running average looks back over
+const RECENT_JOBS: usize = 20;
+
+#[derive(Debug, Serialize)]
+pub struct RealtimeFactor {
+    job_id: String,
+    model: Option<String>,
+    source_duration_secs: f64,
+    processing_secs: u64,
+    // Above 1.0 means faster than real time
+    realtime_factor: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AverageRealtimeFactor {
+    // None until at least one completed job has a known duration
+    realtime_factor: Option<f64>,
+    jobs: usize,
+}
+
+// Source length from the record, or probed from the local file and remembered
+async fn source_duration(state: &AppState, record: &JobRecord) -> Option<f64> {
+    if let Some(duration) = record.source_duration_secs {
+        return Some(duration);
+    }
+    let duration = match &record.source {
+        JobSource::Segment { start_secs, end_secs, .. } => end_secs - start_secs,
+        JobSource::File { path } => media::probe_duration(Path::new(path)).await.ok()?,
+        // Remote media was never on this machine to probe, and a pipe can't be read twice
+        JobSource::Url { .. } | JobSource::Fetched { .. } | JobSource::Remote | JobSource::Pipe { .. } => return None,
+    };
+    if !duration.is_finite() || duration <= 0.0 {
+        return None;
+    }
+    if let Some(stored) = state.job_records.lock().await.get_mut(&record.job_id) {
+        stored.source_duration_secs = Some(duration);
+    }
+    Some(duration)
+}
+
+async fn measure(state: &AppState, record: &JobRecord) -> Result<RealtimeFactor, AppError> {
+    if record.status != "complete" {
+        return Err(format!("Job {} has not completed", record.job_id).into());
+    }
+    let completed_at = record.completed_at
+        .ok_or_else(|| format!("Job {} has no recorded completion time", record.job_id))?;
+    let source_duration_secs = source_duration(state, record)
+        .await
+        .ok_or_else(|| format!("Source duration for job {} is unknown", record.job_id))?;
+
+    // Timestamps are whole seconds, so a job done within the same second counts as one
+    let processing_secs = completed_at.saturating_sub(record.submitted_at).max(1);
+    Ok(RealtimeFactor {
+        job_id: record.job_id.clone(),
+        model: record.options.model.clone(),
+        source_duration_secs,
+        processing_secs,
+        realtime_factor: source_duration_secs / processing_secs as f64,
+    })
+}
+
+// Processing time runs from submission to when the job was first seen complete,
+// so it includes queueing and is only as precise as the polling
+#[tauri::command]
+pub async fn get_realtime_factor(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<RealtimeFactor, AppError> {
+    let record = state.job_records.lock().await.get(&job_id).cloned()
+        .ok_or_else(|| format!("No tracked source for job {}", job_id))?;
+    let factor = measure(&state, &record).await;
+    crate::persist_records(&state).await;
+    factor
+}
+
+// Total media length over total processing time for the most recent completed jobs,
+// so a few very short jobs can't dominate the average
+#[tauri::command]
+pub async fn get_average_realtime_factor(state: State<'_, AppState>) -> Result<AverageRealtimeFactor, AppError> {
+    let mut completed: Vec<JobRecord> = state.job_records.lock().await
+        .values()
+        .filter(|record| record.status == "complete" && record.completed_at.is_some())
+        .cloned()
+        .collect();
+    completed.sort_by_key(|record| std::cmp::Reverse(record.completed_at));
+
+    let mut total_duration = 0.0;
+    let mut total_processing = 0;
+    let mut jobs = 0;
+    for record in completed {
+        if jobs == RECENT_JOBS {
+            break;
+        }
+        // Jobs whose duration can't be known are skipped rather than failing the average
+        if let Ok(factor) = measure(&state, &record).await {
+            total_duration += factor.source_duration_secs;
+            total_processing += factor.processing_secs;
+            jobs += 1;
+        }
+    }
+    crate::persist_records(&state).await;
+
+    Ok(AverageRealtimeFactor {
+        realtime_factor: (total_processing > 0).then(|| total_duration / total_processing as f64),
+        jobs,
+    })
+}
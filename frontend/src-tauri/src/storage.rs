@@ -0,0 +1,281 @@
+// Disk usage of caches, history and downloads, and the budget that caps it
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::tasks::{self, TaskKind};
+use crate::{pins, records, result_cache, AppError, AppState};
+
+// Subdirectories of the app data dir
+pub const CACHE_DIR: &str = "cache";
+pub const LOGS_DIR: &str = "logs";
+
+// How often the budget is enforced in the background
+const ENFORCE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+// Bytes free for the current user on the volume holding path, or None if the OS can't say
+pub async fn free_space(path: &Path) -> Option<u64> {
+    // The destination may not exist yet, so ask about its nearest existing ancestor
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    if cfg!(windows) {
+        let script = format!("(Get-Item -LiteralPath '{}').PSDrive.Free", existing.display().to_string().replace('\'', "''"));
+        let output = tokio::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .output()
+            .await
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    } else {
+        // POSIX output: a header, then "filesystem blocks used available capacity mount" in KiB
+        let output = tokio::process::Command::new("df")
+            .arg("-Pk")
+            .arg(existing)
+            .output()
+            .await
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let available: u64 = stdout.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+        Some(available * 1024)
+    }
+}
+
+struct StoredFile {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+// Every file under dir, recursively; missing dirs are empty
+fn scan(dir: &Path) -> Vec<StoredFile> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.push(StoredFile {
+                    path: entry.path(),
+                    size: metadata.len(),
+                    modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+    }
+    files
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+// Job a cached file belongs to: the first path component below the cache dir, up to its first dot
+fn cached_job_id(cache_dir: &Path, path: &Path) -> Option<String> {
+    let first = path.strip_prefix(cache_dir).ok()?.components().next()?;
+    let name = first.as_os_str().to_string_lossy();
+    Some(name.split('.').next().unwrap_or_default().to_string())
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageUsage {
+    cache_bytes: u64,
+    history_bytes: u64,
+    logs_bytes: u64,
+    downloads_bytes: u64,
+    total_bytes: u64,
+    budget_bytes: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BudgetReport {
+    evicted_files: usize,
+    freed_bytes: u64,
+    deleted_downloads: Vec<String>,
+    // Downloaded files that would have to go to meet the budget; confirm to delete them
+    pending_downloads: Vec<String>,
+    total_bytes: u64,
+    within_budget: bool,
+}
+
+async fn usage(state: &AppState) -> StorageUsage {
+    let downloads: Vec<String> = state.job_records.lock().await
+        .values()
+        .flat_map(|record| record.downloads.clone())
+        .collect();
+    let budget_bytes = state.settings.lock().await.storage_budget_bytes;
+    let data_dir = state.data_dir.clone();
+
+    let (cache_bytes, history_bytes, logs_bytes, downloads_bytes) = tauri::async_runtime::spawn_blocking(move || {
+        (
+            scan(&data_dir.join(CACHE_DIR)).iter().map(|file| file.size).sum::<u64>(),
+            file_size(&data_dir.join(records::RECORDS_FILE)),
+            scan(&data_dir.join(LOGS_DIR)).iter().map(|file| file.size).sum::<u64>(),
+            downloads.iter().map(|path| file_size(Path::new(path))).sum::<u64>(),
+        )
+    })
+    .await
+    .unwrap_or_default();
+
+    StorageUsage {
+        cache_bytes,
+        history_bytes,
+        logs_bytes,
+        downloads_bytes,
+        total_bytes: cache_bytes + history_bytes + logs_bytes + downloads_bytes,
+        budget_bytes,
+    }
+}
+
+// Bytes used by the cache directory
+pub async fn cache_bytes(state: &AppState) -> u64 {
+    let cache_dir = state.data_dir.join(CACHE_DIR);
+    tauri::async_runtime::spawn_blocking(move || scan(&cache_dir).iter().map(|file| file.size).sum::<u64>())
+        .await
+        .unwrap_or_default()
+}
+
+// Bring usage under the budget, evicting the oldest cached results first.
+// Downloads are only deleted when the caller has confirmed it.
+async fn enforce(state: &AppState, delete_downloads: bool) -> BudgetReport {
+    let usage = usage(state).await;
+    let mut report = BudgetReport {
+        total_bytes: usage.total_bytes,
+        ..Default::default()
+    };
+    let Some(budget) = usage.budget_bytes else {
+        report.within_budget = true;
+        return report;
+    };
+
+    // Files for jobs still in progress or pinned are never evicted
+    let in_progress = state.processing_jobs.lock().await.clone();
+    let pinned = pins::pinned_jobs(state).await;
+
+    let cache_dir = state.data_dir.join(CACHE_DIR);
+    let scan_dir = cache_dir.clone();
+    let mut cached = tauri::async_runtime::spawn_blocking(move || scan(&scan_dir))
+        .await
+        .unwrap_or_default();
+    cached.sort_by_key(|file| file.modified);
+
+    for file in cached {
+        if report.total_bytes <= budget {
+            break;
+        }
+        let busy = cached_job_id(&cache_dir, &file.path)
+            .is_some_and(|job_id| in_progress.contains(&job_id) || pinned.contains(&job_id));
+        // User edits can't be fetched again, so they are never evicted
+        let edited = file.path.to_string_lossy().ends_with(result_cache::EDITED_SUFFIX);
+        if busy || edited || tokio::fs::remove_file(&file.path).await.is_err() {
+            continue;
+        }
+        report.evicted_files += 1;
+        report.freed_bytes += file.size;
+        report.total_bytes -= file.size;
+    }
+
+    if report.total_bytes > budget {
+        let mut finished: Vec<_> = state.job_records.lock().await
+            .values()
+            .filter(|record| !in_progress.contains(&record.job_id) && !record.pinned)
+            .map(|record| (record.submitted_at, record.job_id.clone(), record.downloads.clone()))
+            .collect();
+        finished.sort_by_key(|(submitted_at, _, _)| *submitted_at);
+
+        let mut removed = Vec::new();
+        'records: for (_, job_id, downloads) in finished {
+            for path in downloads {
+                if report.total_bytes <= budget {
+                    break 'records;
+                }
+                let size = file_size(Path::new(&path));
+                if size == 0 {
+                    continue;
+                }
+                if !delete_downloads {
+                    report.pending_downloads.push(path);
+                    report.total_bytes -= size;
+                    continue;
+                }
+                if tokio::fs::remove_file(&path).await.is_ok() {
+                    report.freed_bytes += size;
+                    report.total_bytes -= size;
+                    removed.push((job_id.clone(), path.clone()));
+                    report.deleted_downloads.push(path);
+                }
+            }
+        }
+
+        // Pending downloads were only counted as if deleted
+        if !delete_downloads {
+            report.total_bytes = usage.total_bytes - report.freed_bytes;
+        }
+
+        if !removed.is_empty() {
+            let mut records = state.job_records.lock().await;
+            for (job_id, path) in &removed {
+                if let Some(record) = records.get_mut(job_id) {
+                    record.downloads.retain(|download| download != path);
+                }
+            }
+            drop(records);
+            crate::persist_records(state).await;
+        }
+    }
+
+    report.within_budget = report.total_bytes <= budget;
+    report
+}
+
+// Periodically evict cached results; downloads are left for the user to confirm
+pub fn enforce_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::StorageWatcher, None, async move {
+        let mut interval = tokio::time::interval(ENFORCE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+            let report = enforce(&state, false).await;
+            if !report.within_budget {
+                eprintln!(
+                    "Storage budget exceeded: {} bytes in use, {} downloads awaiting confirmation",
+                    report.total_bytes,
+                    report.pending_downloads.len()
+                );
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_storage_usage(state: State<'_, AppState>) -> Result<StorageUsage, AppError> {
+    Ok(usage(&state).await)
+}
+
+#[tauri::command]
+pub async fn set_storage_budget(
+    state: State<'_, AppState>,
+    budget_bytes: Option<u64>,
+) -> Result<(), AppError> {
+    // Zero or missing means no budget
+    state.settings.lock().await.storage_budget_bytes = budget_bytes.filter(|bytes| *bytes > 0);
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn enforce_storage_budget(
+    state: State<'_, AppState>,
+    delete_downloads: Option<bool>,
+) -> Result<BudgetReport, AppError> {
+    Ok(enforce(&state, delete_downloads.unwrap_or(false)).await)
+}
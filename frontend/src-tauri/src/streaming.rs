@@ -0,0 +1,211 @@
+// Incremental transcript output for long-running jobs
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::AsyncWriteExt;
+
+use crate::tasks::{self, TaskKind};
+use crate::{records, timeouts, AppError, AppState};
+
+const STREAM_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_STREAM_FORMAT: &str = "md";
+
+const DEFAULT_PREVIEW_CHARS: usize = 500;
+const MAX_PREVIEW_CHARS: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptStreamEvent {
+    job_id: String,
+    dest_path: String,
+    bytes_written: u64,
+    progress: f32,
+    status: String,
+    error: Option<String>,
+}
+
+// Bring the file in line with the latest partial result, appending when it only grew
+async fn sync_partial(dest: &Path, written: &mut Vec<u8>, partial: Vec<u8>) -> Result<(), String> {
+    if partial.len() > written.len() && partial.starts_with(written) {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dest)
+            .await
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+        file.write_all(&partial[written.len()..])
+            .await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+    } else if partial != *written {
+        // The backend revised earlier text, so rewrite from scratch
+        tokio::fs::write(dest, &partial)
+            .await
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+    *written = partial;
+    Ok(())
+}
+
+// Replace the growing file with the authoritative result in one step
+async fn finalize(dest: &Path, result: &[u8]) -> Result<(), String> {
+    let temp_path = dest.with_extension("part");
+    tokio::fs::write(&temp_path, result)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    tokio::fs::rename(&temp_path, dest)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+async fn stream_job(app: &AppHandle, job_id: &str, dest: &Path, format: &str) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let mut written = Vec::new();
+
+    // Start from an empty file so stale content never mixes in
+    tokio::fs::write(dest, b"")
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    loop {
+        let status = crate::fetch_status(&state, job_id).await?;
+
+        if records::is_finished_status(&status.status) {
+            match status.status.as_str() {
+                "complete" => {
+                    let result = crate::fetch_result(&state, job_id, format, &[]).await?;
+                    finalize(dest, &result).await?;
+                    emit_stream_event(app, job_id, dest, result.len() as u64, status.progress, &status.status, None);
+                    return Ok(());
+                }
+                "cancelled" => {
+                    // A partial transcript of a cancelled job would pass for the real thing
+                    let _ = tokio::fs::remove_file(dest).await;
+                    return Err(AppError::Cancelled { op_id: job_id.to_string() });
+                }
+                _ => {
+                    let message = status.message.unwrap_or_else(|| "Processing failed".to_string());
+                    return Err(message.into());
+                }
+            }
+        }
+        // Jobs past their time limit are cancelled here as the poller would, ending the stream
+        if timeouts::check(app, &state, job_id, &status).await {
+            let _ = tokio::fs::remove_file(dest).await;
+            return Err(AppError::Cancelled { op_id: job_id.to_string() });
+        }
+
+        if let Some(partial) = crate::fetch_partial_result(&state, job_id).await? {
+            sync_partial(dest, &mut written, partial).await?;
+        }
+        emit_stream_event(app, job_id, dest, written.len() as u64, status.progress, &status.status, None);
+
+        tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+    }
+}
+
+fn emit_stream_event(
+    app: &AppHandle,
+    job_id: &str,
+    dest: &Path,
+    bytes_written: u64,
+    progress: f32,
+    status: &str,
+    error: Option<String>,
+) {
+    let event = TranscriptStreamEvent {
+        job_id: job_id.to_string(),
+        dest_path: dest.to_string_lossy().to_string(),
+        bytes_written,
+        progress,
+        status: status.to_string(),
+        error,
+    };
+    let _ = app.emit_all("transcript-stream", event);
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptPreview {
+    // Empty until the backend has produced some text
+    text: String,
+    // The job is still running or the text was cut at max_chars
+    more_coming: bool,
+    progress: f32,
+    // When another preview is worth asking for; None once the job is done
+    refresh_after_ms: Option<u64>,
+}
+
+// The first max_chars characters of whatever the job has transcribed so far
+#[tauri::command]
+pub async fn preview_transcript(
+    state: State<'_, AppState>,
+    job_id: String,
+    max_chars: Option<usize>,
+) -> Result<TranscriptPreview, AppError> {
+    let max_chars = max_chars.unwrap_or(DEFAULT_PREVIEW_CHARS).clamp(1, MAX_PREVIEW_CHARS);
+    let cached = state.status_cache.lock().await.get(&job_id);
+    let status = match cached {
+        Some(status) => status,
+        None => crate::fetch_status(&state, &job_id).await?,
+    };
+    let finished = records::is_finished_status(&status.status);
+
+    let partial = match status.status.as_str() {
+        "complete" => Some(crate::fetch_result(&state, &job_id, DEFAULT_STREAM_FORMAT, &[]).await?),
+        _ => crate::fetch_partial_result(&state, &job_id).await?,
+    };
+    let partial = partial.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()).unwrap_or_default();
+    let text: String = partial.chars().take(max_chars).collect();
+    let truncated = text.len() < partial.len();
+
+    // Ask again soon while the job is moving, and back off like the poller while it stalls
+    let refresh_after_ms = if finished {
+        None
+    } else {
+        let (min, max) = state.settings.lock().await.poll_interval_bounds();
+        let stalled = state.job_records.lock().await
+            .get(&job_id)
+            .is_some_and(|record| record.stalled_secs() >= max.as_secs());
+        Some(if stalled { max } else { min }.as_millis() as u64)
+    };
+
+    Ok(TranscriptPreview {
+        text,
+        more_coming: !finished || truncated,
+        progress: status.progress,
+        refresh_after_ms,
+    })
+}
+
+#[tauri::command]
+pub async fn stream_transcript_to_file(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    dest_path: String,
+    format: Option<String>,
+) -> Result<(), AppError> {
+    // Fail fast on a dead job instead of starting a stream that errors immediately
+    if !crate::check_job_exists(&state, &job_id).await? {
+        return Err(AppError::JobNotFound { job_id });
+    }
+
+    let format = format.unwrap_or_else(|| DEFAULT_STREAM_FORMAT.to_string());
+    let dest = PathBuf::from(dest_path);
+
+    tasks::spawn(&state.tasks, TaskKind::TranscriptStream, Some(job_id.clone()), async move {
+        // Write errors stop the stream; the file keeps whatever was written so far, unless cancelled
+        if let Err(e) = stream_job(&app, &job_id, &dest, &format).await {
+            let status = match e {
+                AppError::Cancelled { .. } => "cancelled",
+                _ => "error",
+            };
+            emit_stream_event(&app, &job_id, &dest, 0, 0.0, status, Some(e.to_string()));
+        }
+    });
+
+    Ok(())
+}
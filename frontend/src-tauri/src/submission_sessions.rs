@@ -0,0 +1,135 @@
+// Submission sessions: jobs submitted together, grouped for review. Purely client-side.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::records::{unix_now, JobRecord};
+use crate::{persist, AppError, AppState};
+
+pub const SESSIONS_FILE: &str = "submission_sessions.json";
+
+const MAX_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionSession {
+    pub session_id: String,
+    pub name: Option<String>,
+    pub started_at: u64,
+}
+
+// Every session in start order; the last one is current
+pub type Sessions = Vec<SubmissionSession>;
+
+#[derive(Debug, Serialize)]
+pub struct SessionSummary {
+    #[serde(flatten)]
+    session: SubmissionSession,
+    job_count: usize,
+    current: bool,
+}
+
+fn next_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("s-{}-{}", unix_now(), COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+fn new_session(name: Option<String>) -> SubmissionSession {
+    SubmissionSession {
+        session_id: next_id(),
+        name,
+        started_at: unix_now(),
+    }
+}
+
+// Previous sessions plus a fresh one for this launch
+pub fn restore(mut sessions: Sessions) -> Sessions {
+    sessions.push(new_session(None));
+    sessions
+}
+
+// Session new jobs are tagged with
+pub async fn current_id(state: &AppState) -> Option<String> {
+    state.sessions.lock().await.last().map(|session| session.session_id.clone())
+}
+
+// Save the sessions, dropping earlier ones no job was ever submitted in
+async fn persist_sessions(state: &AppState) -> Result<(), AppError> {
+    let used: std::collections::HashSet<String> = state.job_records.lock().await
+        .values()
+        .filter_map(|record| record.session_id.clone())
+        .collect();
+    let sessions = {
+        let mut sessions = state.sessions.lock().await;
+        let current = sessions.len().saturating_sub(1);
+        let mut index = 0;
+        sessions.retain(|session| {
+            let keep = index == current || used.contains(&session.session_id);
+            index += 1;
+            keep
+        });
+        sessions.clone()
+    };
+    persist::save_json(&state.data_dir.join(SESSIONS_FILE), &sessions).await
+}
+
+// Called once a job is tagged, so its session outlives a restart
+pub async fn note_job(state: &AppState) {
+    if let Err(e) = persist_sessions(state).await {
+        eprintln!("Failed to save submission sessions: {}", e);
+    }
+}
+
+// Start a new session; jobs submitted from now on belong to it
+#[tauri::command]
+pub async fn begin_session(
+    state: State<'_, AppState>,
+    name: Option<String>,
+) -> Result<SubmissionSession, AppError> {
+    let name = name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    if name.as_ref().is_some_and(|name| name.chars().count() > MAX_NAME_CHARS) {
+        return Err(format!("Session name is longer than {} characters", MAX_NAME_CHARS).into());
+    }
+    let session = new_session(name);
+    state.sessions.lock().await.push(session.clone());
+    persist_sessions(&state).await?;
+    Ok(session)
+}
+
+// Newest first, with how many tracked jobs each holds
+#[tauri::command]
+pub async fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionSummary>, AppError> {
+    let records = state.job_records.lock().await;
+    let sessions = state.sessions.lock().await;
+    let current = sessions.last().map(|session| session.session_id.clone());
+    Ok(sessions
+        .iter()
+        .rev()
+        .map(|session| SessionSummary {
+            job_count: records.values()
+                .filter(|record| record.session_id.as_ref() == Some(&session.session_id))
+                .count(),
+            current: current.as_ref() == Some(&session.session_id),
+            session: session.clone(),
+        })
+        .collect())
+}
+
+// A session's jobs in submission order
+#[tauri::command]
+pub async fn get_session_jobs(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<JobRecord>, AppError> {
+    if !state.sessions.lock().await.iter().any(|session| session.session_id == session_id) {
+        return Err(format!("Unknown session {}", session_id).into());
+    }
+    let mut jobs: Vec<JobRecord> = state.job_records.lock().await
+        .values()
+        .filter(|record| record.session_id.as_ref() == Some(&session_id))
+        .cloned()
+        .collect();
+    jobs.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at).then_with(|| a.job_id.cmp(&b.job_id)));
+    Ok(jobs)
+}
@@ -0,0 +1,104 @@
+// Reading and writing SRT and WebVTT cues as transcript segments
+
+use crate::transcript::Segment;
+
+// "HH:MM:SS,mmm", "HH:MM:SS.mmm" or "MM:SS.mmm" to seconds
+fn parse_timestamp(text: &str) -> Option<f64> {
+    let text = text.trim().replace(',', ".");
+    let parts: Vec<&str> = text.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours, minutes, seconds] => (hours.parse::<f64>().ok()?, minutes.parse::<f64>().ok()?, *seconds),
+        [minutes, seconds] => (0.0, minutes.parse::<f64>().ok()?, *seconds),
+        _ => return None,
+    };
+    let seconds = seconds.parse::<f64>().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+// Seconds as "HH:MM:SS" plus milliseconds after the given separator
+pub fn format_timestamp(secs: f64, separator: char) -> String {
+    let millis = (secs.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+// Cue blocks from SRT or VTT text; headers, numbering and cue settings are skipped
+pub fn parse_cues(text: &str) -> Result<Vec<Segment>, String> {
+    let text = text.replace("\r\n", "\n");
+    let mut segments = Vec::new();
+    for block in text.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let (start, end) = timing.split_once("-->").ok_or_else(|| format!("Invalid cue timing: {}", timing))?;
+        // VTT cue settings follow the end time
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let start = parse_timestamp(start).ok_or_else(|| format!("Invalid cue timing: {}", timing))?;
+        let end = parse_timestamp(end).ok_or_else(|| format!("Invalid cue timing: {}", timing))?;
+
+        let mut text = lines.collect::<Vec<_>>().join("\n");
+        // Speakers written as "[Name] text" or VTT voice tags "<v Name>text"
+        let mut speaker = None;
+        if let Some(rest) = text.strip_prefix("<v ") {
+            if let Some((name, body)) = rest.split_once('>') {
+                speaker = Some(name.trim().to_string());
+                text = body.trim_end_matches("</v>").to_string();
+            }
+        } else if let Some(rest) = text.strip_prefix('[') {
+            if let Some((name, body)) = rest.split_once("] ") {
+                speaker = Some(name.to_string());
+                text = body.to_string();
+            }
+        }
+
+        segments.push(Segment {
+            start,
+            end,
+            text,
+            speaker,
+            ..Segment::default()
+        });
+    }
+    Ok(segments)
+}
+
+fn cue_text(segment: &Segment) -> String {
+    match &segment.speaker {
+        Some(speaker) => format!("[{}] {}", speaker, segment.text.trim()),
+        None => segment.text.trim().to_string(),
+    }
+}
+
+pub fn write_srt(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(segment.start, ','),
+            format_timestamp(segment.end, ','),
+            cue_text(segment)
+        ));
+    }
+    out
+}
+
+pub fn write_vtt(segments: &[Segment]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for segment in segments {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(segment.start, '.'),
+            format_timestamp(segment.end, '.'),
+            cue_text(segment)
+        ));
+    }
+    out
+}
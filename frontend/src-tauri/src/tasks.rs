@@ -0,0 +1,143 @@
+// Registry of long-running background tasks, for inspecting and stopping them
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{AppError, AppState};
+
+pub type TaskId = u64;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    Poller,
+    TranscriptStream,
+    CallbackListener,
+    QueueRunner,
+    StorageWatcher,
+    TimeoutWatcher,
+    ExpiryWatcher,
+    ClockSync,
+    Prewarm,
+    LogTail,
+    Download,
+    CacheVerify,
+    ResultServer,
+    PowerWatcher,
+    EventFlusher,
+    PostProcess,
+    IdleSweeper,
+}
+
+struct TaskEntry {
+    kind: TaskKind,
+    job_id: Option<String>,
+    started_at: Instant,
+    handle: tauri::async_runtime::JoinHandle<()>,
+}
+
+// Guarded by a std mutex so entries can be removed from Drop; never held across an await
+#[derive(Default)]
+pub struct TaskRegistry {
+    next_id: TaskId,
+    tasks: HashMap<TaskId, TaskEntry>,
+}
+
+pub type SharedRegistry = Arc<Mutex<TaskRegistry>>;
+
+fn lock(registry: &SharedRegistry) -> MutexGuard<'_, TaskRegistry> {
+    registry.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Removes a task's entry when its future finishes or is dropped on abort
+struct Deregister {
+    registry: SharedRegistry,
+    id: TaskId,
+}
+
+impl Drop for Deregister {
+    fn drop(&mut self) {
+        lock(&self.registry).tasks.remove(&self.id);
+    }
+}
+
+// Spawn a task that stays listed until it ends
+pub fn spawn<F>(registry: &SharedRegistry, kind: TaskKind, job_id: Option<String>, future: F) -> TaskId
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    // Held across the spawn so a task that ends at once can't deregister before it's listed
+    let mut tasks = lock(registry);
+    let id = tasks.next_id;
+    tasks.next_id += 1;
+
+    let deregister = Deregister {
+        registry: registry.clone(),
+        id,
+    };
+    let handle = tauri::async_runtime::spawn(async move {
+        let _deregister = deregister;
+        future.await;
+    });
+    tasks.tasks.insert(
+        id,
+        TaskEntry {
+            kind,
+            job_id,
+            started_at: Instant::now(),
+            handle,
+        },
+    );
+    id
+}
+
+pub fn is_running(registry: &SharedRegistry, id: TaskId) -> bool {
+    lock(registry).tasks.contains_key(&id)
+}
+
+// Abort a task, reporting whether it was still running
+pub fn kill(registry: &SharedRegistry, id: TaskId) -> bool {
+    // Released before aborting, since the abort drops the task's Deregister guard
+    let entry = lock(registry).tasks.remove(&id);
+    match entry {
+        Some(entry) => {
+            entry.handle.abort();
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskInfo {
+    task_id: TaskId,
+    kind: TaskKind,
+    job_id: Option<String>,
+    uptime_secs: u64,
+}
+
+#[tauri::command]
+pub async fn list_background_tasks(state: State<'_, AppState>) -> Result<Vec<TaskInfo>, AppError> {
+    let mut tasks: Vec<TaskInfo> = lock(&state.tasks)
+        .tasks
+        .iter()
+        .map(|(id, entry)| TaskInfo {
+            task_id: *id,
+            kind: entry.kind,
+            job_id: entry.job_id.clone(),
+            uptime_secs: entry.started_at.elapsed().as_secs(),
+        })
+        .collect();
+    tasks.sort_by_key(|task| task.task_id);
+    Ok(tasks)
+}
+
+#[tauri::command]
+pub async fn kill_background_task(state: State<'_, AppState>, task_id: TaskId) -> Result<bool, AppError> {
+    Ok(kill(&state.tasks, task_id))
+}
@@ -0,0 +1,74 @@
+// Preview images for media sources
+
+use std::path::Path;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::{media, storage, AppError, AppState};
+
+const THUMBNAIL_DIR: &str = "thumbnails";
+const THUMBNAIL_WIDTH: u32 = 320;
+const WAVEFORM_HEIGHT: u32 = 80;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailKind {
+    Frame,
+    // Audio-only sources get their waveform instead
+    Waveform,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Thumbnail {
+    path: String,
+    kind: ThumbnailKind,
+    // Position the frame was taken from, after capping to the duration
+    at_secs: Option<f64>,
+}
+
+#[tauri::command]
+pub async fn generate_thumbnail(
+    state: State<'_, AppState>,
+    path: String,
+    at_secs: Option<f64>,
+) -> Result<Thumbnail, AppError> {
+    let source = Path::new(&path);
+    let duration = media::probe_duration(source).await?;
+    let has_video = media::has_video(source).await?;
+
+    // Seeking to the very end yields no frame, so stay just short of it
+    let at_secs = has_video.then(|| {
+        at_secs
+            .filter(|secs| secs.is_finite())
+            .unwrap_or(duration / 2.0)
+            .clamp(0.0, (duration - 0.1).max(0.0))
+    });
+
+    let key = format!("{}\n{:?}", path, at_secs);
+    let name = format!("{}.png", &hex::encode(Sha256::digest(key.as_bytes()))[..16]);
+    // Kept with the other caches so the storage budget can evict it
+    let dir = state.data_dir.join(storage::CACHE_DIR).join(THUMBNAIL_DIR);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    let dest = dir.join(name);
+
+    let kind = match at_secs {
+        Some(at_secs) => {
+            media::extract_frame(source, at_secs, THUMBNAIL_WIDTH, &dest).await?;
+            ThumbnailKind::Frame
+        }
+        None => {
+            media::render_waveform(source, THUMBNAIL_WIDTH, WAVEFORM_HEIGHT, &dest).await?;
+            ThumbnailKind::Waveform
+        }
+    };
+
+    Ok(Thumbnail {
+        path: dest.to_string_lossy().to_string(),
+        kind,
+        at_secs,
+    })
+}
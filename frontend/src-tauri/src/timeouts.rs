@@ -0,0 +1,115 @@
+// Auto-cancelling jobs that run past their time limit
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::records::JobRecord;
+use crate::tasks::{self, TaskKind};
+use crate::{AppError, AppState, JobStatusResponse};
+
+// How often jobs without a poller are checked
+const WATCH_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize)]
+struct JobTimedOutEvent {
+    job_id: String,
+    max_duration_secs: u64,
+    elapsed_secs: u64,
+}
+
+// Limit and elapsed time for a job that has run past its limit
+fn exceeded(record: &JobRecord, default_limit: Option<u64>) -> Option<(u64, u64)> {
+    let limit = record.max_duration_secs.or(default_limit)?;
+    let elapsed = record.elapsed_secs();
+    (!record.is_finished() && elapsed >= limit).then_some((limit, elapsed))
+}
+
+// Set a job's own limit, overriding the global default
+pub async fn set_limit(state: &AppState, job_id: &str, max_duration_secs: Option<u64>) {
+    let Some(limit) = max_duration_secs else {
+        return;
+    };
+    if let Some(record) = state.job_records.lock().await.get_mut(job_id) {
+        record.max_duration_secs = Some(limit);
+    }
+    crate::persist_records(state).await;
+}
+
+// Cancel the job if its latest status shows it still running past its limit.
+// Returns whether it was cancelled.
+pub async fn check(app: &AppHandle, state: &AppState, job_id: &str, status: &JobStatusResponse) -> bool {
+    if matches!(status.status.as_str(), "complete" | "error" | "cancelled") {
+        return false;
+    }
+
+    let default_limit = state.settings.lock().await.default_max_duration_secs;
+    let exceeded = state.job_records.lock().await
+        .get(job_id)
+        .and_then(|record| exceeded(record, default_limit));
+    let Some((max_duration_secs, elapsed_secs)) = exceeded else {
+        return false;
+    };
+
+    match crate::cancel_tracked_job(state, job_id).await {
+        Ok(()) | Err(AppError::JobNotFound { .. }) => {}
+        Err(e) => {
+            eprintln!("Failed to cancel timed-out job {}: {}", job_id, e);
+            return false;
+        }
+    }
+
+    let event = JobTimedOutEvent {
+        job_id: job_id.to_string(),
+        max_duration_secs,
+        elapsed_secs,
+    };
+    let _ = app.emit_all("job-timed-out", event);
+    true
+}
+
+// Check jobs nobody is polling; subscribed jobs are checked by their poller
+pub fn watch_in_background(app: AppHandle) {
+    let registry = app.state::<AppState>().tasks.clone();
+    tasks::spawn(&registry, TaskKind::TimeoutWatcher, None, async move {
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app.state::<AppState>();
+
+            let default_limit = state.settings.lock().await.default_max_duration_secs;
+            let processing = state.processing_jobs.lock().await.clone();
+            let due: Vec<String> = {
+                let records = state.job_records.lock().await;
+                processing.into_iter()
+                    .filter(|job_id| records.get(job_id).and_then(|record| exceeded(record, default_limit)).is_some())
+                    .collect()
+            };
+
+            for job_id in due {
+                let polled = state.subscriptions.lock().await
+                    .get(&job_id)
+                    .is_some_and(|id| tasks::is_running(&state.tasks, *id));
+                if polled {
+                    continue;
+                }
+                // Confirm with the backend so a job that just finished isn't cancelled
+                if let Ok(status) = crate::fetch_status(&state, &job_id).await {
+                    check(&app, &state, &job_id, &status).await;
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn set_default_max_duration(
+    state: State<'_, AppState>,
+    max_duration_secs: Option<u64>,
+) -> Result<(), AppError> {
+    // Zero or missing turns the global limit off
+    state.settings.lock().await.default_max_duration_secs = max_duration_secs.filter(|secs| *secs > 0);
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
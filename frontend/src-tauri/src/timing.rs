@@ -0,0 +1,124 @@
+// Optional per-request timing diagnostics for debugging slow connections
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Manager, State};
+
+use crate::{audit, AppError, AppState};
+
+// Timings kept for get_recent_timings
+const MAX_TIMINGS: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestTiming {
+    method: String,
+    path: String,
+    status: Option<u16>,
+    // Separate lookup of the backend host; the HTTP client resolves on its own and may hit a cache
+    dns_ms: Option<f64>,
+    // The HTTP client doesn't expose connect and TLS handshake times per request
+    connect_ms: Option<f64>,
+    tls_ms: Option<f64>,
+    // Until the response headers arrived
+    time_to_first_byte_ms: f64,
+    // Including signing and any credential refresh and replay
+    total_ms: f64,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct TimingLog {
+    enabled: AtomicBool,
+    recent: Mutex<VecDeque<RequestTiming>>,
+}
+
+impl TimingLog {
+    // A single atomic load, so requests pay nothing when diagnostics are off
+    pub fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// Time a DNS lookup of the request's host
+pub async fn dns_lookup_ms(url: &reqwest::Url) -> Option<f64> {
+    let host = url.host_str()?.to_string();
+    let port = url.port_or_known_default()?;
+    let started = Instant::now();
+    tokio::net::lookup_host((host.as_str(), port)).await.ok()?.next()?;
+    Some(millis(started.elapsed()))
+}
+
+// Measurements for one request, filled in by api::send
+pub struct Timer {
+    method: String,
+    path: String,
+    dns_ms: Option<f64>,
+    started: Instant,
+    first_byte: Option<Duration>,
+}
+
+impl Timer {
+    pub async fn start(request: &reqwest::Request) -> Self {
+        Timer {
+            method: request.method().to_string(),
+            path: request.url().path().to_string(),
+            dns_ms: dns_lookup_ms(request.url()).await,
+            started: Instant::now(),
+            first_byte: None,
+        }
+    }
+
+    pub fn headers_received(&mut self) {
+        self.first_byte.get_or_insert(self.started.elapsed());
+    }
+
+    // Record the finished request, emitting it and writing it to the audit log
+    pub async fn finish(self, state: &AppState, result: &Result<reqwest::Response, String>) {
+        let total = self.started.elapsed();
+        let timing = RequestTiming {
+            method: self.method,
+            path: self.path,
+            status: result.as_ref().ok().map(|response| response.status().as_u16()),
+            dns_ms: self.dns_ms,
+            connect_ms: None,
+            tls_ms: None,
+            time_to_first_byte_ms: millis(self.first_byte.unwrap_or(total)),
+            total_ms: millis(total),
+            error: result.as_ref().err().cloned(),
+        };
+
+        {
+            let mut recent = state.timings.recent.lock().unwrap_or_else(PoisonError::into_inner);
+            if recent.len() >= MAX_TIMINGS {
+                recent.pop_front();
+            }
+            recent.push_back(timing.clone());
+        }
+        if let Some(app) = state.app_handle.get() {
+            let _ = app.emit_all("request-timing", timing.clone());
+        }
+        audit::append(state, "request_timing", &timing).await;
+    }
+}
+
+#[tauri::command]
+pub async fn set_diagnostics(state: State<'_, AppState>, enabled: bool) -> Result<(), AppError> {
+    state.timings.enabled.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
+
+// Most recent timings, oldest first
+#[tauri::command]
+pub async fn get_recent_timings(state: State<'_, AppState>, limit: Option<usize>) -> Result<Vec<RequestTiming>, AppError> {
+    let recent = state.timings.recent.lock().unwrap_or_else(PoisonError::into_inner);
+    let limit = limit.unwrap_or(MAX_TIMINGS);
+    Ok(recent.iter().skip(recent.len().saturating_sub(limit)).cloned().collect())
+}
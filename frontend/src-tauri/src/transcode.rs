@@ -0,0 +1,184 @@
+// Shrinking media before upload by re-encoding it to what the backend actually needs
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::records::JobSource;
+use crate::{media, presets, timeouts, AppError, AppState, ProcessOptions, UploadForm};
+
+// Sample rates libopus accepts
+const OPUS_SAMPLE_RATES: &[u32] = &[8000, 12000, 16000, 24000, 48000];
+
+// Allowed drift between source and transcoded duration, as a fraction of the source
+const DURATION_TOLERANCE: f64 = 0.01;
+const MIN_DURATION_TOLERANCE_SECS: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioCodec {
+    #[default]
+    Opus,
+    Flac,
+}
+
+// Target format for transcoded uploads; the defaults suit speech recognition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioProfile {
+    pub codec: AudioCodec,
+    pub sample_rate: u32,
+    pub channels: u32,
+    // Ignored for lossless codecs
+    pub bitrate_kbps: u32,
+}
+
+impl Default for AudioProfile {
+    fn default() -> Self {
+        AudioProfile {
+            codec: AudioCodec::Opus,
+            sample_rate: 16000,
+            channels: 1,
+            bitrate_kbps: 24,
+        }
+    }
+}
+
+impl AudioProfile {
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=2).contains(&self.channels) {
+            return Err(format!("channels must be 1 or 2, got {}", self.channels));
+        }
+        match self.codec {
+            AudioCodec::Opus => {
+                if !OPUS_SAMPLE_RATES.contains(&self.sample_rate) {
+                    return Err(format!(
+                        "Opus supports sample rates {:?}, got {}",
+                        OPUS_SAMPLE_RATES, self.sample_rate
+                    ));
+                }
+                if !(6..=256).contains(&self.bitrate_kbps) {
+                    return Err(format!("bitrate_kbps must be between 6 and 256, got {}", self.bitrate_kbps));
+                }
+            }
+            AudioCodec::Flac => {
+                if !(8000..=96000).contains(&self.sample_rate) {
+                    return Err(format!("sample_rate must be between 8000 and 96000, got {}", self.sample_rate));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.codec {
+            AudioCodec::Opus => "opus",
+            AudioCodec::Flac => "flac",
+        }
+    }
+
+    fn ffmpeg_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-ac".to_string(),
+            self.channels.to_string(),
+            "-ar".to_string(),
+            self.sample_rate.to_string(),
+        ];
+        match self.codec {
+            AudioCodec::Opus => args.extend([
+                "-c:a".to_string(),
+                "libopus".to_string(),
+                "-b:a".to_string(),
+                format!("{}k", self.bitrate_kbps),
+            ]),
+            AudioCodec::Flac => args.extend(["-c:a".to_string(), "flac".to_string()]),
+        }
+        args
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscodedUploadResponse {
+    job_id: String,
+    transcoded: bool,
+    original_bytes: u64,
+    uploaded_bytes: u64,
+    // Why the original file was uploaded instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fallback_reason: Option<String>,
+}
+
+// Transcode source into a temp file, checking the result is complete
+async fn transcode(source: &Path, profile: &AudioProfile) -> Result<media::TempFile, String> {
+    let source_duration = media::probe_duration(source).await?;
+    let temp = media::TempFile::new("transcode", profile.extension());
+    media::transcode_audio(source, &profile.ffmpeg_args(), temp.path()).await?;
+
+    // A truncated encode would silently lose the end of the recording
+    let transcoded_duration = media::probe_duration(temp.path()).await?;
+    let tolerance = (source_duration * DURATION_TOLERANCE).max(MIN_DURATION_TOLERANCE_SECS);
+    if (transcoded_duration - source_duration).abs() > tolerance {
+        return Err(format!(
+            "Transcoded duration {:.2}s does not match the source ({:.2}s)",
+            transcoded_duration, source_duration
+        ));
+    }
+
+    Ok(temp)
+}
+
+// Upload a file after transcoding it to a compact audio format, falling back to the raw file
+#[tauri::command]
+pub async fn upload_file_transcoded(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    target: Option<AudioProfile>,
+    options: Option<ProcessOptions>,
+    preset: Option<String>,
+    max_duration_secs: Option<u64>,
+) -> Result<TranscodedUploadResponse, AppError> {
+    let profile = target.unwrap_or_default();
+    profile.validate()?;
+    let options = presets::resolve_options(&app, &state, preset, options).await?;
+
+    let source = PathBuf::from(&path);
+    let stem = source.file_stem()
+        .ok_or_else(|| "Invalid file path".to_string())?
+        .to_string_lossy()
+        .to_string();
+    let original_bytes = tokio::fs::metadata(&source)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+
+    // The temp file is removed when it goes out of scope, including on error
+    let (api_response, transcoded, uploaded_bytes, fallback_reason) = match transcode(&source, &profile).await {
+        Ok(temp) => {
+            let uploaded_bytes = tokio::fs::metadata(temp.path()).await.map(|m| m.len()).unwrap_or_default();
+            let file_name = format!("{}.{}", stem, profile.extension());
+            let api_response = crate::send_file(&state, temp.path(), file_name, &options, &UploadForm::default()).await?;
+            (api_response, true, uploaded_bytes, None)
+        }
+        Err(e) => {
+            eprintln!("Transcoding {} failed, uploading the original: {}", path, e);
+            let file_name = source.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| stem.clone());
+            let api_response = crate::send_file(&state, &source, file_name, &options, &UploadForm::default()).await?;
+            (api_response, false, original_bytes, Some(e))
+        }
+    };
+
+    crate::track_job(&state, &api_response.job_id, JobSource::File { path }, options).await;
+    timeouts::set_limit(&state, &api_response.job_id, max_duration_secs).await;
+
+    Ok(TranscodedUploadResponse {
+        job_id: api_response.job_id,
+        transcoded,
+        original_bytes,
+        uploaded_bytes,
+        fallback_reason,
+    })
+}
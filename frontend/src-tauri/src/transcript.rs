@@ -0,0 +1,152 @@
+// Typed view of the backend's JSON transcript format
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::{result_cache, AppError, AppState};
+
+// Segments scoring below this are flagged for review by default
+pub const DEFAULT_LOW_CONFIDENCE: f64 = 0.6;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Transcript {
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: f64,
+    pub end: f64,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Word {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probability: Option<f64>,
+}
+
+impl Segment {
+    // The backend's segment score, else the mean of its word probabilities
+    pub fn confidence(&self) -> Option<f64> {
+        if self.confidence.is_some() {
+            return self.confidence;
+        }
+        let probabilities: Vec<f64> = self.words.iter().filter_map(|word| word.probability).collect();
+        (!probabilities.is_empty()).then(|| probabilities.iter().sum::<f64>() / probabilities.len() as f64)
+    }
+}
+
+impl Transcript {
+    // Mean confidence over the segments that have one
+    pub fn average_confidence(&self) -> Option<f64> {
+        let scores: Vec<f64> = self.segments.iter().filter_map(Segment::confidence).collect();
+        (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64)
+    }
+}
+
+// Download and parse a job's JSON transcript, on the original timeline if silence was trimmed
+pub async fn fetch(state: &AppState, job_id: &str) -> Result<Transcript, AppError> {
+    let bytes = crate::fetch_result(state, job_id, "json", &[]).await?;
+    let transcript = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("Failed to parse transcript: {}", e))?;
+    Ok(transcript)
+}
+
+// The cached transcript when there is one, else a fresh download. Reports whether it came from the cache.
+pub async fn load(state: &AppState, job_id: &str) -> Result<(Transcript, bool), AppError> {
+    let loaded = match result_cache::read(state, job_id).await? {
+        Some(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(transcript) => Some(transcript),
+            Err(e) => {
+                eprintln!("Ignoring unreadable cached result for job {}: {}", job_id, e);
+                None
+            }
+        },
+        None => None,
+    };
+    let loaded = match loaded {
+        Some(transcript) => (transcript, true),
+        None => (fetch(state, job_id).await?, false),
+    };
+    crate::note_access(state, job_id).await;
+    Ok(loaded)
+}
+
+#[derive(Debug, Serialize)]
+pub struct LowConfidenceSegment {
+    start: f64,
+    end: f64,
+    confidence: f64,
+    text: String,
+}
+
+// Fields are None when the transcript carries no confidence data
+#[derive(Debug, Serialize)]
+pub struct ConfidenceSummary {
+    average: Option<f64>,
+    minimum: Option<f64>,
+    threshold: f64,
+    low_confidence_count: Option<usize>,
+    low_confidence_segments: Vec<LowConfidenceSegment>,
+}
+
+#[tauri::command]
+pub async fn get_confidence_summary(
+    state: State<'_, AppState>,
+    job_id: String,
+    threshold: Option<f64>,
+) -> Result<ConfidenceSummary, AppError> {
+    let threshold = threshold.unwrap_or(DEFAULT_LOW_CONFIDENCE);
+    let transcript = fetch(&state, &job_id).await?;
+
+    let scored: Vec<(&Segment, f64)> = transcript.segments
+        .iter()
+        .filter_map(|segment| segment.confidence().map(|confidence| (segment, confidence)))
+        .collect();
+    if scored.is_empty() {
+        return Ok(ConfidenceSummary {
+            average: None,
+            minimum: None,
+            threshold,
+            low_confidence_count: None,
+            low_confidence_segments: Vec::new(),
+        });
+    }
+
+    let average = transcript.average_confidence().unwrap_or_default();
+    let minimum = scored.iter().map(|(_, confidence)| *confidence).fold(f64::INFINITY, f64::min);
+    let low_confidence_segments: Vec<LowConfidenceSegment> = scored
+        .iter()
+        .filter(|(_, confidence)| *confidence < threshold)
+        .map(|(segment, confidence)| LowConfidenceSegment {
+            start: segment.start,
+            end: segment.end,
+            confidence: *confidence,
+            text: segment.text.clone(),
+        })
+        .collect();
+
+    Ok(ConfidenceSummary {
+        average: Some(average),
+        minimum: Some(minimum),
+        threshold,
+        low_confidence_count: Some(low_confidence_segments.len()),
+        low_confidence_segments,
+    })
+}
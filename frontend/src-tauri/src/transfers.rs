@@ -0,0 +1,451 @@
+// Shared capacity for uploads and downloads, biased toward one or the other on request
+
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tokio::sync::{watch, Semaphore};
+
+use crate::{AppError, AppState};
+
+// Transfers allowed at once, split between the two directions
+const TRANSFER_SLOTS: u32 = 4;
+
+// Chunk buffers allowed in memory at once, across every upload and download
+pub const DEFAULT_BUFFER_BUDGET_BYTES: u64 = 16 * 1024 * 1024;
+pub const MIN_BUFFER_BUDGET_BYTES: u64 = 256 * 1024;
+pub const MAX_BUFFER_BUDGET_BYTES: u64 = 1024 * 1024 * 1024;
+
+// The budget is counted in KiB so its permits fit the semaphore's u32 counts
+const BUFFER_UNIT_BYTES: u64 = 1024;
+
+// Largest chunk a transfer holds at once; its share of the budget is reserved before reading
+pub const CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferPriority {
+    #[default]
+    Balanced,
+    Uploads,
+    Downloads,
+}
+
+impl TransferPriority {
+    // Upload and download permits for this mode; each side always keeps at least one
+    fn split(self) -> (u32, u32) {
+        match self {
+            TransferPriority::Balanced => (TRANSFER_SLOTS / 2, TRANSFER_SLOTS / 2),
+            TransferPriority::Uploads => (TRANSFER_SLOTS - 1, 1),
+            TransferPriority::Downloads => (1, TRANSFER_SLOTS - 1),
+        }
+    }
+}
+
+pub struct TransferLimiter {
+    uploads: Pool,
+    downloads: Pool,
+    // While true, new transfers wait and running ones stop reading between chunks
+    paused: watch::Sender<bool>,
+    // Chunk buffer budget shared by every upload and download, in BUFFER_UNIT_BYTES
+    buffers: Pool,
+}
+
+// A semaphore that can shrink while its permits are out. Permits it no longer wants are
+// retired as they come back, counted under the same lock as its size so nothing drifts.
+#[derive(Clone)]
+struct Pool(Arc<PoolInner>);
+
+struct PoolInner {
+    semaphore: Semaphore,
+    size: Mutex<PoolSize>,
+}
+
+struct PoolSize {
+    // Permits the pool is heading toward
+    target: u32,
+    // Permits still out that are to be retired when released
+    retiring: u32,
+}
+
+impl Pool {
+    fn new(permits: u32) -> Self {
+        Pool(Arc::new(PoolInner {
+            semaphore: Semaphore::new(permits as usize),
+            size: Mutex::new(PoolSize { target: permits, retiring: 0 }),
+        }))
+    }
+
+    fn size(&self) -> MutexGuard<'_, PoolSize> {
+        self.0.size.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // Wait for permits, capped at the pool's size so a large request can still be met
+    async fn acquire(&self, permits: u32) -> Permit {
+        let permits = permits.clamp(1, self.size().target.max(1));
+        self.0.semaphore
+            .acquire_many(permits)
+            .await
+            .expect("transfer semaphores are never closed")
+            .forget();
+        Permit { pool: self.clone(), permits }
+    }
+
+    // Shrinking takes idle permits at once and retires the rest as they are released;
+    // transfers already running are left to finish
+    fn resize(&self, to: u32) {
+        let mut size = self.size();
+        match to.cmp(&size.target) {
+            Ordering::Greater => {
+                let grow = to - size.target;
+                let kept = grow.min(size.retiring);
+                size.retiring -= kept;
+                self.0.semaphore.add_permits((grow - kept) as usize);
+            }
+            Ordering::Less => {
+                let shrink = size.target - to;
+                let taken = self.0.semaphore.forget_permits(shrink as usize) as u32;
+                size.retiring += shrink - taken;
+            }
+            Ordering::Equal => {}
+        }
+        size.target = to;
+    }
+
+    // Permits held right now
+    fn in_use(&self) -> u32 {
+        let size = self.size();
+        (size.target + size.retiring).saturating_sub(self.0.semaphore.available_permits() as u32)
+    }
+}
+
+// Permits taken from a pool, returned or retired when dropped
+pub struct Permit {
+    pool: Pool,
+    permits: u32,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut size = self.pool.size();
+        let retired = self.permits.min(size.retiring);
+        size.retiring -= retired;
+        self.pool.0.semaphore.add_permits((self.permits - retired) as usize);
+    }
+}
+
+fn buffer_units(bytes: u64) -> u32 {
+    bytes.div_ceil(BUFFER_UNIT_BYTES).min(u32::MAX as u64) as u32
+}
+
+fn held_bytes(permit: &Permit) -> usize {
+    (permit.permits as u64 * BUFFER_UNIT_BYTES) as usize
+}
+
+// A download body, handed out in pieces no larger than the budget reserved for them
+pub struct Download {
+    response: reqwest::Response,
+    pending: bytes::Bytes,
+}
+
+impl Download {
+    pub fn new(response: reqwest::Response) -> Self {
+        Download { response, pending: bytes::Bytes::new() }
+    }
+}
+
+impl TransferLimiter {
+    pub fn new(priority: TransferPriority, buffer_budget_bytes: u64) -> Self {
+        let (uploads, downloads) = priority.split();
+        let units = buffer_units(buffer_budget_bytes.clamp(MIN_BUFFER_BUDGET_BYTES, MAX_BUFFER_BUDGET_BYTES));
+        TransferLimiter {
+            uploads: Pool::new(uploads),
+            downloads: Pool::new(downloads),
+            paused: watch::Sender::new(false),
+            buffers: Pool::new(units),
+        }
+    }
+
+    // Wait for an upload slot, held until the permit is dropped
+    pub async fn upload(&self) -> Permit {
+        self.resumed().await;
+        self.uploads.acquire(1).await
+    }
+
+    pub async fn download(&self) -> Permit {
+        self.resumed().await;
+        self.downloads.acquire(1).await
+    }
+
+    // Uploads and downloads holding a slot right now
+    pub fn in_flight(&self) -> (u32, u32) {
+        (self.uploads.in_use(), self.downloads.in_use())
+    }
+
+    // Pause or resume every transfer, reporting whether that changed anything
+    pub fn set_paused(&self, paused: bool) -> bool {
+        self.paused.send_if_modified(|current| std::mem::replace(current, paused) != paused)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    // Returns at once unless transfers are paused, then waits for them to resume
+    pub async fn resumed(&self) {
+        let mut paused = self.paused.subscribe();
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+
+    // Read the next chunk of a download within the shared buffer budget, waiting while
+    // transfers are paused or the budget is used up. Room is reserved before reading, and
+    // the permit holds it until dropped, so keep it until the chunk has been written out.
+    pub async fn next_chunk(
+        &self,
+        download: &mut Download,
+    ) -> Result<Option<(bytes::Bytes, Permit)>, reqwest::Error> {
+        self.resumed().await;
+        let permit = self.buffers.acquire(buffer_units(CHUNK_BYTES as u64)).await;
+        while download.pending.is_empty() {
+            match download.response.chunk().await? {
+                Some(chunk) => download.pending = chunk,
+                None => return Ok(None),
+            }
+        }
+        // Larger network reads are handed out a reserved share at a time
+        let length = download.pending.len().min(held_bytes(&permit));
+        Ok(Some((download.pending.split_to(length), permit)))
+    }
+
+    // An upload body read from reader in chunks, each within the shared buffer budget and
+    // read only once room for it is reserved. Pausing holds the upload between chunks.
+    pub fn upload_chunks<R>(&self, reader: R) -> impl futures_util::Stream<Item = std::io::Result<Vec<u8>>>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        let budget = self.buffers.clone();
+        let paused = self.paused.subscribe();
+        let initial = (reader, budget, paused, None);
+        futures_util::stream::try_unfold(initial, |(mut reader, budget, mut paused, previous)| async move {
+            // By the time the next chunk is asked for, the previous one has been sent on
+            drop::<Option<Permit>>(previous);
+            let _ = paused.wait_for(|paused| !paused).await;
+            let permit = budget.acquire(buffer_units(CHUNK_BYTES as u64)).await;
+            let mut chunk = vec![0u8; CHUNK_BYTES.min(held_bytes(&permit))];
+            let read = tokio::io::AsyncReadExt::read(&mut reader, &mut chunk).await?;
+            if read == 0 {
+                return Ok(None);
+            }
+            chunk.truncate(read);
+            Ok(Some((chunk, (reader, budget, paused, Some(permit)))))
+        })
+    }
+
+    // Apply both limits at once, e.g. after switching to a profile with its own
+    pub fn configure(&self, priority: TransferPriority, buffer_budget_bytes: u64) {
+        self.set_priority(priority);
+        self.set_buffer_budget(buffer_budget_bytes.clamp(MIN_BUFFER_BUDGET_BYTES, MAX_BUFFER_BUDGET_BYTES));
+    }
+
+    fn set_buffer_budget(&self, bytes: u64) {
+        self.buffers.resize(buffer_units(bytes));
+    }
+
+    fn set_priority(&self, priority: TransferPriority) {
+        let (uploads, downloads) = priority.split();
+        self.uploads.resize(uploads);
+        self.downloads.resize(downloads);
+    }
+}
+
+#[tauri::command]
+pub async fn set_transfer_priority(
+    state: State<'_, AppState>,
+    mode: TransferPriority,
+) -> Result<(), AppError> {
+    state.transfers.set_priority(mode);
+    state.settings.lock().await.transfer_priority = mode;
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
+
+// Cap the memory download buffers may use at once; transfers over it wait for room
+#[tauri::command]
+pub async fn set_transfer_buffer_budget(
+    state: State<'_, AppState>,
+    budget_bytes: u64,
+) -> Result<(), AppError> {
+    if !(MIN_BUFFER_BUDGET_BYTES..=MAX_BUFFER_BUDGET_BYTES).contains(&budget_bytes) {
+        return Err(format!(
+            "Transfer buffer budget must be between {} and {} bytes",
+            MIN_BUFFER_BUDGET_BYTES, MAX_BUFFER_BUDGET_BYTES
+        ).into());
+    }
+    state.transfers.set_buffer_budget(budget_bytes);
+    state.settings.lock().await.transfer_buffer_budget_bytes = budget_bytes;
+    crate::persist_settings(&state).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::Duration;
+
+    use futures_util::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    const BODY_BYTES: usize = 1024 * 1024;
+    const BUDGET_UNITS: usize = (MIN_BUFFER_BUDGET_BYTES / BUFFER_UNIT_BYTES) as usize;
+
+    // Serve BODY_BYTES to every request, written in pieces larger than a chunk
+    async fn streaming_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/result", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0u8; 4096];
+                    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                        let read = socket.read(&mut buffer).await.unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                    }
+                    let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", BODY_BYTES);
+                    socket.write_all(head.as_bytes()).await.unwrap();
+                    let piece = vec![7u8; 4 * CHUNK_BYTES];
+                    for _ in 0..BODY_BYTES / piece.len() {
+                        socket.write_all(&piece).await.unwrap();
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    // Chunks held across many concurrent downloads never exceed the budget, and every
+    // download still arrives whole
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn downloads_stay_within_budget() {
+        let limiter = Arc::new(TransferLimiter::new(TransferPriority::Balanced, MIN_BUFFER_BUDGET_BYTES));
+        let url = streaming_server().await;
+        let client = reqwest::Client::new();
+        let held = Arc::new(AtomicU64::new(0));
+        let peak = Arc::new(AtomicU64::new(0));
+
+        let downloads: Vec<_> = (0..16).map(|_| {
+            let (limiter, held, peak) = (limiter.clone(), held.clone(), peak.clone());
+            let request = client.get(&url);
+            tokio::spawn(async move {
+                let mut download = Download::new(request.send().await.unwrap());
+                let mut received = 0;
+                while let Some((chunk, permit)) = limiter.next_chunk(&mut download).await.unwrap() {
+                    assert!(chunk.len() <= CHUNK_BYTES);
+                    let now = held.fetch_add(chunk.len() as u64, Ordering::SeqCst) + chunk.len() as u64;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    // A slow disk, so downloads pile up on the budget
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    held.fetch_sub(chunk.len() as u64, Ordering::SeqCst);
+                    received += chunk.len();
+                    drop(permit);
+                }
+                received
+            })
+        }).collect();
+
+        let all = futures_util::future::join_all(downloads);
+        for received in tokio::time::timeout(Duration::from_secs(60), all).await.expect("downloads stalled") {
+            assert_eq!(received.unwrap(), BODY_BYTES);
+        }
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak > 0 && peak <= MIN_BUFFER_BUDGET_BYTES, "peak of {} bytes", peak);
+        assert_eq!(limiter.buffers.0.semaphore.available_permits(), BUDGET_UNITS);
+    }
+
+    // An upload holds the budget for one chunk at a time and gives it back when done
+    #[tokio::test]
+    async fn uploads_share_the_budget() {
+        let limiter = TransferLimiter::new(TransferPriority::Balanced, MIN_BUFFER_BUDGET_BYTES);
+        let content: Vec<u8> = (0..BODY_BYTES as u32).map(|i| (i % 251) as u8).collect();
+        let mut chunks = Box::pin(limiter.upload_chunks(std::io::Cursor::new(content.clone())));
+
+        let mut sent = Vec::new();
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk.unwrap();
+            assert!(chunk.len() <= CHUNK_BYTES);
+            let available = limiter.buffers.0.semaphore.available_permits();
+            assert_eq!(available, BUDGET_UNITS - CHUNK_BYTES / BUFFER_UNIT_BYTES as usize);
+            sent.extend_from_slice(&chunk);
+        }
+        drop(chunks);
+        assert_eq!(sent, content);
+        assert_eq!(limiter.buffers.0.semaphore.available_permits(), BUDGET_UNITS);
+    }
+
+    // With the budget taken by downloads, an upload waits for room before reading
+    #[tokio::test]
+    async fn uploads_wait_for_room() {
+        let limiter = TransferLimiter::new(TransferPriority::Balanced, MIN_BUFFER_BUDGET_BYTES);
+        let taken = limiter.buffers.acquire(buffer_units(MIN_BUFFER_BUDGET_BYTES)).await;
+        let mut chunks = Box::pin(limiter.upload_chunks(std::io::Cursor::new(vec![1u8; 16])));
+        assert!(tokio::time::timeout(Duration::from_millis(50), chunks.next()).await.is_err());
+        drop(taken);
+        let chunk = tokio::time::timeout(Duration::from_secs(5), chunks.next()).await.unwrap();
+        assert_eq!(chunk.unwrap().unwrap().len(), 16);
+    }
+
+    // Pausing holds an upload that is already running until transfers resume
+    #[tokio::test]
+    async fn paused_uploads_wait_between_chunks() {
+        let limiter = TransferLimiter::new(TransferPriority::Balanced, MIN_BUFFER_BUDGET_BYTES);
+        let mut chunks = Box::pin(limiter.upload_chunks(std::io::Cursor::new(vec![1u8; CHUNK_BYTES + 16])));
+        assert_eq!(chunks.next().await.unwrap().unwrap().len(), CHUNK_BYTES);
+        limiter.set_paused(true);
+        assert!(tokio::time::timeout(Duration::from_millis(50), chunks.next()).await.is_err());
+        limiter.set_paused(false);
+        let chunk = tokio::time::timeout(Duration::from_secs(5), chunks.next()).await.unwrap();
+        assert_eq!(chunk.unwrap().unwrap().len(), 16);
+    }
+
+    // Switching priority back and forth with transfers running keeps every pool at its size
+    #[tokio::test]
+    async fn priority_changes_do_not_drift() {
+        let limiter = TransferLimiter::new(TransferPriority::Balanced, MIN_BUFFER_BUDGET_BYTES);
+        let uploads: Vec<_> = futures_util::future::join_all((0..2).map(|_| limiter.upload())).await;
+        for _ in 0..3 {
+            limiter.set_priority(TransferPriority::Downloads);
+            assert_eq!(limiter.in_flight(), (2, 0));
+            limiter.set_priority(TransferPriority::Balanced);
+        }
+        limiter.set_priority(TransferPriority::Downloads);
+        drop(uploads);
+        assert_eq!(limiter.in_flight(), (0, 0));
+        assert_eq!(limiter.uploads.0.semaphore.available_permits(), 1);
+        assert_eq!(limiter.downloads.0.semaphore.available_permits(), 3);
+
+        limiter.set_priority(TransferPriority::Balanced);
+        assert_eq!(limiter.uploads.0.semaphore.available_permits(), 2);
+        assert_eq!(limiter.downloads.0.semaphore.available_permits(), 2);
+    }
+
+    // A smaller budget is taken from idle room first and from chunks in flight as they finish
+    #[tokio::test]
+    async fn budget_shrinks_as_chunks_finish() {
+        let limiter = TransferLimiter::new(TransferPriority::Balanced, 2 * MIN_BUFFER_BUDGET_BYTES);
+        let held = limiter.buffers.acquire(buffer_units(MIN_BUFFER_BUDGET_BYTES * 3 / 2)).await;
+        limiter.set_buffer_budget(MIN_BUFFER_BUDGET_BYTES);
+        assert_eq!(limiter.buffers.0.semaphore.available_permits(), 0);
+        assert_eq!(limiter.buffers.in_use(), buffer_units(MIN_BUFFER_BUDGET_BYTES * 3 / 2));
+
+        drop(held);
+        assert_eq!(limiter.buffers.0.semaphore.available_permits(), BUDGET_UNITS);
+        assert_eq!(limiter.buffers.in_use(), 0);
+
+        limiter.set_buffer_budget(2 * MIN_BUFFER_BUDGET_BYTES);
+        assert_eq!(limiter.buffers.0.semaphore.available_permits(), 2 * BUDGET_UNITS);
+    }
+}
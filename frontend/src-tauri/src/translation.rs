@@ -0,0 +1,65 @@
+// Translations requested with a job and picking one to download
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::records::JobRecord;
+use crate::{capabilities, AppError, AppState};
+
+// Formats offered when the backend doesn't advertise its own
+const DEFAULT_FORMATS: &[&str] = &["json", "srt", "vtt", "txt", "md"];
+
+#[derive(Debug, Serialize)]
+pub struct JobOutputs {
+    formats: Vec<String>,
+    // Language of the transcript itself, when known
+    language: Option<String>,
+    // Requested translations, each available in every format
+    translations: Vec<String>,
+}
+
+// The language to send when downloading, checked against what the job was asked to produce
+pub fn check_language(record: Option<&JobRecord>, language: &str) -> Result<String, String> {
+    let Some(record) = record else {
+        // Jobs we don't track can't be checked; the backend will say if it's wrong
+        return Ok(language.to_string());
+    };
+    let original = record.options.language.as_deref().filter(|language| *language != "auto");
+    let translations = record.options.translate_to.as_deref().unwrap_or_default();
+    let known = original.is_some_and(|original| original.eq_ignore_ascii_case(language))
+        || translations.iter().any(|target| target.eq_ignore_ascii_case(language));
+    if !known {
+        return Err(if translations.is_empty() {
+            format!("Job {} wasn't submitted with any translations", record.job_id)
+        } else {
+            format!(
+                "Job {} has no '{}' translation; requested: {}",
+                record.job_id,
+                language,
+                translations.join(", ")
+            )
+        });
+    }
+    Ok(language.to_string())
+}
+
+// What a job can be downloaded as: formats, and the languages via download_result's language
+#[tauri::command]
+pub async fn get_job_outputs(state: State<'_, AppState>, job_id: String) -> Result<JobOutputs, AppError> {
+    let record = state.job_records.lock().await
+        .get(&job_id)
+        .cloned()
+        .ok_or_else(|| AppError::JobNotFound { job_id: job_id.clone() })?;
+
+    // The advertised formats when the backend is reachable, else the usual ones
+    let advertised = capabilities::fetch(&state, false).await.ok().flatten().map(|caps| caps.formats);
+    let formats = advertised
+        .filter(|formats| !formats.is_empty())
+        .unwrap_or_else(|| DEFAULT_FORMATS.iter().map(|format| format.to_string()).collect());
+
+    Ok(JobOutputs {
+        formats,
+        language: record.options.language.filter(|language| language != "auto"),
+        translations: record.options.translate_to.unwrap_or_default(),
+    })
+}
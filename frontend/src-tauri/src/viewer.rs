@@ -0,0 +1,62 @@
+// Read-only viewer mode: existing results can be browsed and downloaded, nothing new submitted
+
+use tauri::{Invoke, Manager, Runtime};
+
+use crate::{AppError, AppState};
+
+// Environment variable that turns viewer mode on at runtime
+const VIEWER_MODE_ENV: &str = "QUICKSCRIPT_VIEWER_MODE";
+
+// Commands refused in viewer mode because they submit new work to the backend
+pub const DISABLED_COMMANDS: &[&str] = &[
+    "upload_file",
+    "upload_from_pipe",
+    "upload_file_segment",
+    "upload_file_transcoded",
+    "upload_file_trimmed",
+    "process_url",
+    "fetch_and_upload",
+    "restart_job",
+    "enqueue_job",
+    "upload_directory",
+    "detect_language",
+    "benchmark_upload",
+    "run_self_test",
+    "auto_improve",
+];
+
+// On when built with the viewer-mode feature or launched with QUICKSCRIPT_VIEWER_MODE=1
+pub fn enabled_at_startup() -> bool {
+    cfg!(feature = "viewer-mode")
+        || std::env::var(VIEWER_MODE_ENV).is_ok_and(|value| matches!(value.as_str(), "1" | "true" | "yes"))
+}
+
+pub fn is_disabled(command: &str) -> bool {
+    DISABLED_COMMANDS.contains(&command)
+}
+
+// Wrap the command handler so disabled commands are rejected before they run
+pub fn guard<R: Runtime>(handler: impl Fn(Invoke<R>) + Send + Sync + 'static) -> impl Fn(Invoke<R>) + Send + Sync + 'static {
+    move |invoke: Invoke<R>| {
+        let window = invoke.message.window();
+        let state = window.state::<AppState>();
+        let (viewer_mode, shutting_down) = (state.viewer_mode, crate::shutdown::in_progress(&state));
+        let command = invoke.message.command().to_string();
+        if viewer_mode && is_disabled(&command) {
+            invoke.resolver.reject(AppError::Disabled { command });
+            return;
+        }
+        // The same commands stop once a graceful quit has begun
+        if shutting_down && is_disabled(&command) {
+            invoke.resolver.reject(AppError::ShuttingDown { command });
+            return;
+        }
+        handler(invoke)
+    }
+}
+
+// Lets the UI hide controls that would only be refused
+#[tauri::command]
+pub async fn get_viewer_mode(state: tauri::State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.viewer_mode)
+}
@@ -0,0 +1,78 @@
+// Per-word timing exports built from the JSON result's word timings
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::transcript::{self, Segment};
+use crate::{subtitles, AppError, AppState};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordFormat {
+    // One WebVTT cue per word
+    Vtt,
+    // Array of { word, start, end, confidence, speaker }
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct WordTiming {
+    word: String,
+    start: f64,
+    end: f64,
+    confidence: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speaker: Option<String>,
+}
+
+// Write a job's word timings to dest, returning how many words were written
+#[tauri::command]
+pub async fn export_word_timings(
+    state: State<'_, AppState>,
+    job_id: String,
+    format: WordFormat,
+    dest: String,
+) -> Result<usize, AppError> {
+    let (result, _) = transcript::load(&state, &job_id).await?;
+    let words: Vec<WordTiming> = result.segments
+        .iter()
+        .flat_map(|segment| {
+            segment.words.iter().map(|word| WordTiming {
+                word: word.word.trim().to_string(),
+                start: word.start,
+                end: word.end,
+                confidence: word.probability,
+                speaker: segment.speaker.clone(),
+            })
+        })
+        .filter(|word| !word.word.is_empty())
+        .collect();
+    if words.is_empty() {
+        return Err(format!(
+            "The result for job {} only has segment-level timings; word timings must be enabled on the backend",
+            job_id
+        ).into());
+    }
+
+    let contents = match format {
+        WordFormat::Json => serde_json::to_string_pretty(&words)
+            .map_err(|e| format!("Failed to encode word timings: {}", e))?,
+        WordFormat::Vtt => {
+            let cues: Vec<Segment> = words
+                .iter()
+                .map(|word| Segment {
+                    start: word.start,
+                    end: word.end,
+                    text: word.word.clone(),
+                    speaker: word.speaker.clone(),
+                    ..Default::default()
+                })
+                .collect();
+            subtitles::write_vtt(&cues)
+        }
+    };
+    tokio::fs::write(&dest, contents)
+        .await
+        .map_err(|e| crate::error::io_error("Failed to write file", std::path::Path::new(&dest), e))?;
+    Ok(words.len())
+}
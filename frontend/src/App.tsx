@@ -21,6 +21,17 @@ type JobStatus = {
   result_url?: string;
 };
 
+// Commands reject with { kind, message, ... }
+type CommandError = {
+  kind: string;
+  message: string;
+};
+
+const errorMessage = (error: unknown): string =>
+  typeof error === 'object' && error !== null && 'message' in error
+    ? (error as CommandError).message
+    : String(error);
+
 function App() {
   // State
   const [currentJobId, setCurrentJobId] = useState<string | null>(null);
@@ -58,7 +69,7 @@ function App() {
     } catch (error) {
       console.error('Error uploading file:', error);
       setIsProcessing(false);
-      alert(`Error uploading file: ${error}`);
+      alert(`Error uploading file: ${errorMessage(error)}`);
     }
   };
 
@@ -87,7 +98,7 @@ function App() {
     } catch (error) {
       console.error('Error processing URL:', error);
       setIsProcessing(false);
-      alert(`Error processing URL: ${error}`);
+      alert(`Error processing URL: ${errorMessage(error)}`);
     }
   };
 
@@ -118,7 +129,7 @@ function App() {
     } catch (error) {
       console.error('Error polling job status:', error);
       setIsProcessing(false);
-      alert(`Error polling job status: ${error}`);
+      alert(`Error polling job status: ${errorMessage(error)}`);
     }
   };
 
@@ -151,7 +162,7 @@ function App() {
 
     } catch (error) {
       console.error('Error fetching markdown content:', error);
-      alert(`Error fetching markdown content: ${error}`);
+      alert(`Error fetching markdown content: ${errorMessage(error)}`);
     }
   };
 
@@ -184,7 +195,7 @@ function App() {
 
     } catch (error) {
       console.error(`Error exporting as ${format}:`, error);
-      alert(`Error exporting as ${format}: ${error}`);
+      alert(`Error exporting as ${format}: ${errorMessage(error)}`);
     }
   };
 
@@ -203,7 +214,7 @@ function App() {
 
     } catch (error) {
       console.error('Error cancelling job:', error);
-      alert(`Error cancelling job: ${error}`);
+      alert(`Error cancelling job: ${errorMessage(error)}`);
     }
   };
 